@@ -7,7 +7,9 @@
 from __future__ import annotations
 
 from typing import Any
+from typing import Callable
 from typing import Generator
+from typing import Literal
 # from typing import Self
 from pydantic import BaseModel
 from pydantic import ConfigDict
@@ -30,10 +32,43 @@ class PsetId(BaseModel):
     )
     id_: int = Field(alias="id")
 
+    def __eq__(self, other: object) -> bool:
+        ...
+
+    def equals(self, other: PsetId, /, *, ignore_ids: bool = False) -> bool:
+        """
+        Compares by value, optionally ignoring ids
+        """
+        ...
+
     @staticmethod
     def model_validate(value: Any, /) -> PsetId:
         ...
 
+class IfcLogical:
+    """
+    Three-valued logic of IfcLogical
+    """
+
+    TRUE: IfcLogical
+    FALSE: IfcLogical
+    UNKNOWN: IfcLogical
+
+class IfcValue:
+    """
+    Value of a property, typed by its IFC defined type
+    (subclasses `IfcValue.Boolean`, `IfcValue.LengthMeasure`, etc. each carry a `value`)
+    """
+
+    value: bool | int | float | str | IfcLogical
+
+    @property
+    def value_type(self) -> str:
+        """
+        Name of the IFC defined type, e.g. `IfcLengthMeasure`
+        """
+        ...
+
 class Pset(BaseModel):
     """
     Class structure for Pset verbose expansion
@@ -51,11 +86,731 @@ class Pset(BaseModel):
     # NOTE: This is a new field since ifcopenshell==0.8.1. It is unclear if it is always set
     value_type: str | None = Field(default=None, alias="value-type")
 
+    def __eq__(self, other: object) -> bool:
+        ...
+
+    def equals(self, other: Pset, /, *, ignore_ids: bool = False) -> bool:
+        """
+        Compares by value, optionally ignoring ids
+        """
+        ...
+
+    @property
+    def typed_value(self) -> IfcValue | None:
+        """
+        Value typed by `value_type`,
+        or `None` if the value is unset or the type is not modelled
+        """
+        ...
+
+    def unit(self, units: UnitAssignment | None = None, /) -> str | None:
+        """
+        Symbol of the unit of the value, as assigned by `units` or else the SI unit
+        """
+        ...
+
+    def convert_to(self, unit: str, /, *, units: UnitAssignment | None = None) -> Pset:
+        """
+        Converts the value from the unit assigned by `units` (or else the SI unit) into `unit`,
+        e.g. `convert_to("mm")` or `convert_to("m2")`
+        """
+        ...
+
     @staticmethod
     def model_validate(value: Any, /) -> Pset:
         ...
 
-class Psets(RootModel[Pset | PsetId | dict[str, Psets | Any]]):
+class UnitAssignment:
+    """
+    Units of a project (IfcUnitAssignment), falling back to SI units for unassigned types
+    """
+
+    def __init__(self, units: dict[str, str] | None = None, /) -> None:
+        """
+        Assigns units by unit type, e.g. `UnitAssignment({"LENGTHUNIT": "mm"})`
+        """
+        ...
+
+    @staticmethod
+    def model_validate(value: Any, /) -> UnitAssignment:
+        """
+        Reads an IfcUnitAssignment (as given by `get_info(recursive=True)`)
+        or a mapping of unit types onto symbols
+        """
+        ...
+
+    @property
+    def units(self) -> dict[str, str]:
+        ...
+
+    def unit_of(self, value_type: str, /) -> str | None:
+        """
+        Symbol of the unit of a measure type, or `None` if the type has no unit
+        """
+        ...
+
+    def convert(self, value: float, value_type: str, unit: str, /) -> float:
+        ...
+
+    def __eq__(self, other: object) -> bool:
+        ...
+
+class TemplateIssue:
+    """
+    Deviation of a property from its template
+    """
+
+    path: str
+    expected: str
+    found: str
+
+class PsetsValidation:
+    """
+    Outcome of validating Psets against a template library
+    """
+
+    unchecked_psets: list[str]
+    misapplied_psets: list[str]
+    undecided_psets: list[str]
+    unknown_properties: list[str]
+    missing_properties: list[str]
+    wrong_types: list[TemplateIssue]
+    invalid_values: list[TemplateIssue]
+
+    @property
+    def is_valid(self) -> bool:
+        """
+        Whether no issue was found in the checked sets
+        """
+        ...
+
+    @property
+    def is_complete(self) -> bool:
+        """
+        Whether all sets had a template to be checked against (see `unchecked_psets`)
+        and a decided applicability (see `undecided_psets`)
+        """
+        ...
+
+class PsetTemplateLibrary:
+    """
+    Library of property set templates of a schema
+    """
+
+    @staticmethod
+    def bundled(schema: Literal["IFC4", "IFC4X3"] = "IFC4X3", /) -> PsetTemplateLibrary:
+        """
+        Library shipped with the package, holding the common sets of the buildingSMART definitions
+        but not all of them (see `PsetsValidation.unchecked_psets`); complete libraries
+        can be loaded from the PSD files with `from_psd` or `load_psd`
+        """
+        ...
+
+    @staticmethod
+    def model_validate(value: Any, /) -> PsetTemplateLibrary:
+        ...
+
+    @property
+    def schema(self) -> str:
+        ...
+
+    def names(self) -> list[str]:
+        ...
+
+    def applicable_to(self, entity_class: str, /) -> list[str]:
+        """
+        Names of the sets applying to an entity class or one of its supertypes,
+        leaving out those undecided for classes outside the known class hierarchy
+        """
+        ...
+
+    def required_properties(self, name: str, /) -> list[str]:
+        """
+        Names of the properties which must be present in a set
+        """
+        ...
+
+    def optional_properties(self, name: str, /) -> list[str]:
+        """
+        Names of the properties which may be left out of a set
+        """
+        ...
+
+    @staticmethod
+    def from_psd(documents: list[str], /, *, schema: str = "IFC4X3") -> PsetTemplateLibrary:
+        """
+        Library of the sets of PSD documents (given as XML text),
+        properties are optional unless marked with `required="true"`
+        """
+        ...
+
+    def add_psd(self, text: str, /) -> str:
+        """
+        Adds (or replaces) the set of a PSD document, returning its name
+        """
+        ...
+
+    def load_psd(self, path: str, /) -> list[str]:
+        """
+        Adds the sets of a PSD file or of all XML files of a directory, returning their names
+        """
+        ...
+
+    def validate(self, psets: Psets, entity_class: str | None = None, /, *, delimiter: str = ":") -> PsetsValidation:
+        ...
+
+    def __len__(self) -> int:
+        ...
+
+    def __contains__(self, name: str) -> bool:
+        ...
+
+    def __eq__(self, other: object) -> bool:
+        ...
+
+class QuantityDimension:
+    """
+    Physical dimension of a base quantity
+    """
+
+    LENGTH: QuantityDimension
+    AREA: QuantityDimension
+    VOLUME: QuantityDimension
+    COUNT: QuantityDimension
+    WEIGHT: QuantityDimension
+    TIME: QuantityDimension
+
+class Quantity:
+    """
+    Base quantity of a quantity set (IfcQuantityLength, IfcQuantityArea, ...)
+    """
+
+    id_: int
+    class_: str
+    value: Any
+    formula: str | None
+
+    def __init__(
+        self,
+        /,
+        *,
+        id_: int,
+        class_: str,
+        value: Any,
+        formula: str | None = None,
+    ) -> None:
+        ...
+
+    @staticmethod
+    def model_validate(value: Any, /) -> Quantity:
+        ...
+
+    @property
+    def dimension(self) -> QuantityDimension:
+        ...
+
+    @property
+    def typed_value(self) -> IfcValue:
+        """
+        Value typed by the measure type of the dimension
+        """
+        ...
+
+    def unit(self, units: UnitAssignment | None = None, /) -> str | None:
+        """
+        Symbol of the unit of the value, as assigned by `units` or else the SI unit
+        """
+        ...
+
+    def convert_to(self, unit: str, /, *, units: UnitAssignment | None = None) -> Quantity:
+        """
+        Converts the value from the unit assigned by `units` (or else the SI unit) into `unit`,
+        e.g. `convert_to("mm")` or `convert_to("m2")`
+        """
+        ...
+
+    def __add__(self, other: Quantity) -> Quantity:
+        """
+        Sums quantities of the same dimension into a new quantity (without id or formula)
+        of the class of the left one
+        """
+        ...
+
+    def __radd__(self, other: Literal[0]) -> Quantity:
+        ...
+
+    def __lt__(self, other: Quantity) -> bool:
+        """
+        Orders by magnitude, refusing to compare quantities of different dimensions
+        """
+        ...
+
+    def __le__(self, other: Quantity) -> bool:
+        ...
+
+    def __gt__(self, other: Quantity) -> bool:
+        ...
+
+    def __ge__(self, other: Quantity) -> bool:
+        ...
+
+    def __eq__(self, other: object) -> bool:
+        """
+        Compares by value, as Psets holding the quantities do (see `same_magnitude`)
+        """
+        ...
+
+    def equals(self, other: Quantity, /, *, ignore_ids: bool = False) -> bool:
+        """
+        Compares by value, optionally ignoring ids
+        """
+        ...
+
+    def same_magnitude(self, other: Quantity, /) -> bool:
+        """
+        Whether the magnitudes agree, refusing to compare quantities of different dimensions
+        """
+        ...
+
+class PsetEnumeration:
+    """
+    Allowed values of an enumerated property (IfcPropertyEnumeration)
+    """
+
+    id_: int | None
+    name: str | None
+    values: list[Any]
+
+    def __init__(
+        self,
+        /,
+        *,
+        values: list[Any],
+        name: str | None = None,
+        id_: int | None = None,
+    ) -> None:
+        ...
+
+class PsetEnumeratedValue:
+    """
+    Property with values selected from an enumeration (IfcPropertyEnumeratedValue)
+    """
+
+    id_: int
+    class_: str
+    values: list[Any]
+    value_type: str | None
+    enumeration: PsetEnumeration | None
+
+    def __init__(
+        self,
+        /,
+        *,
+        id_: int,
+        values: list[Any],
+        value_type: str | None = None,
+        enumeration: PsetEnumeration | None = None,
+        class_: str = "IfcPropertyEnumeratedValue",
+    ) -> None:
+        ...
+
+    @staticmethod
+    def model_validate(value: Any, /) -> PsetEnumeratedValue:
+        ...
+
+    @property
+    def allowed_values(self) -> list[Any] | None:
+        """
+        Values allowed by the enumeration, if one is referenced
+        """
+        ...
+
+    @property
+    def typed_values(self) -> list[IfcValue] | None:
+        ...
+
+    def validate(self) -> None:
+        """
+        Checks that all selected values are allowed and match the value type
+        """
+        ...
+
+    def __eq__(self, other: object) -> bool:
+        ...
+
+class PsetBoundedValue:
+    """
+    Property with a range and/or set point (IfcPropertyBoundedValue), each with its own type
+    """
+
+    id_: int
+    class_: str
+    upper_bound: Any | None
+    upper_bound_type: str | None
+    lower_bound: Any | None
+    lower_bound_type: str | None
+    set_point: Any | None
+    set_point_type: str | None
+
+    def __init__(
+        self,
+        /,
+        *,
+        id_: int,
+        upper_bound: Any | None = None,
+        lower_bound: Any | None = None,
+        set_point: Any | None = None,
+        upper_bound_type: str | None = None,
+        lower_bound_type: str | None = None,
+        set_point_type: str | None = None,
+        class_: str = "IfcPropertyBoundedValue",
+    ) -> None:
+        ...
+
+    @staticmethod
+    def model_validate(value: Any, /) -> PsetBoundedValue:
+        ...
+
+    def contains(self, x: float, /) -> bool:
+        """
+        Checks whether a number lies within the bounds (a missing bound is unbounded)
+        """
+        ...
+
+    def __contains__(self, x: float) -> bool:
+        ...
+
+    def validate(self) -> None:
+        """
+        Checks that the bounds match their types and are ordered
+        """
+        ...
+
+    def __eq__(self, other: object) -> bool:
+        ...
+
+class CurveInterpolation:
+    """
+    Interpolation between the rows of a table property (IfcCurveInterpolationEnum)
+    """
+
+    LINEAR: CurveInterpolation
+    LOG_LINEAR: CurveInterpolation
+    LOG_LOG: CurveInterpolation
+    NOTDEFINED: CurveInterpolation
+
+class PsetTableValue:
+    """
+    Property mapping defining values onto defined values (IfcPropertyTableValue)
+    """
+
+    id_: int
+    class_: str
+    defining_values: list[Any]
+    defined_values: list[Any]
+    defining_value_type: str | None
+    defined_value_type: str | None
+    expression: str | None
+    curve_interpolation: CurveInterpolation | None
+
+    def __init__(
+        self,
+        /,
+        *,
+        id_: int,
+        defining_values: list[Any],
+        defined_values: list[Any],
+        defining_value_type: str | None = None,
+        defined_value_type: str | None = None,
+        expression: str | None = None,
+        curve_interpolation: CurveInterpolation | None = None,
+        class_: str = "IfcPropertyTableValue",
+    ) -> None:
+        ...
+
+    @staticmethod
+    def model_validate(value: Any, /) -> PsetTableValue:
+        ...
+
+    def rows(self) -> list[tuple[Any, Any]]:
+        """
+        Pairs of defining and defined values
+        """
+        ...
+
+    def lookup(self, x: Any, /) -> Any | None:
+        """
+        Defined value for a defining value,
+        interpolated between rows if the table declares a curve interpolation
+        """
+        ...
+
+    def __len__(self) -> int:
+        ...
+
+    def validate(self) -> None:
+        """
+        Checks that both columns have the same length and match their types
+        """
+        ...
+
+    def __eq__(self, other: object) -> bool:
+        ...
+
+class PsetListValue:
+    """
+    Property holding an ordered list of values of one type (IfcPropertyListValue)
+    """
+
+    id_: int
+    class_: str
+    values: list[Any]
+    value_type: str | None
+
+    def __init__(
+        self,
+        /,
+        *,
+        id_: int,
+        values: list[Any],
+        value_type: str | None = None,
+        class_: str = "IfcPropertyListValue",
+    ) -> None:
+        ...
+
+    @staticmethod
+    def model_validate(value: Any, /) -> PsetListValue:
+        ...
+
+    @property
+    def typed_values(self) -> list[IfcValue] | None:
+        ...
+
+    def __len__(self) -> int:
+        ...
+
+    def __getitem__(self, index: int) -> Any:
+        ...
+
+    def __contains__(self, value: Any) -> bool:
+        ...
+
+    def __eq__(self, other: object) -> bool:
+        ...
+
+class PsetReferenceValue:
+    """
+    Property referring to another entity, e.g. a material or time series (IfcPropertyReferenceValue)
+    """
+
+    id_: int
+    class_: str
+    usage_name: str | None
+    referenced_id: int | None
+    referenced_class: str | None
+
+    def __init__(
+        self,
+        /,
+        *,
+        id_: int,
+        referenced_id: int | None = None,
+        referenced_class: str | None = None,
+        usage_name: str | None = None,
+        class_: str = "IfcPropertyReferenceValue",
+    ) -> None:
+        ...
+
+    @staticmethod
+    def model_validate(value: Any, /) -> PsetReferenceValue:
+        ...
+
+    def resolve(self, resolver: dict[int, Any] | Callable[[int | None, str | None], Any], /) -> Any | None:
+        """
+        Resolves the referenced entity, either through a mapping from ids to objects
+        or a callable receiving the referenced id and class (`None` if unknown)
+        """
+        ...
+
+    def __eq__(self, other: object) -> bool:
+        ...
+
+class PsetComplexProperty:
+    """
+    Group of child properties (IfcComplexProperty), e.g. the layers of a construction.
+    Its children are addressable through the paths of Psets like nested entries.
+    """
+
+    id_: int
+    class_: str
+    usage_name: str | None
+
+    def __init__(
+        self,
+        /,
+        *,
+        id_: int,
+        properties: dict[str, Any] | None = None,
+        usage_name: str | None = None,
+        class_: str = "IfcComplexProperty",
+    ) -> None:
+        ...
+
+    @staticmethod
+    def model_validate(value: Any, /) -> PsetComplexProperty:
+        ...
+
+    @property
+    def properties(self) -> dict[str, Any]:
+        """
+        Child properties, by name
+        """
+        ...
+
+    def __len__(self) -> int:
+        ...
+
+    def __contains__(self, key: str) -> bool:
+        ...
+
+    def __getitem__(self, key: str) -> Any:
+        ...
+
+    def __eq__(self, other: object) -> bool:
+        ...
+
+# NOTE: properties of a class with a dedicated representation
+PsetProperty = PsetEnumeratedValue | PsetBoundedValue | PsetTableValue | PsetListValue | PsetReferenceValue
+
+class PsetChange:
+    """
+    Change of a single entry present in both Psets
+    """
+
+    path: str
+    old_class: str | None
+    new_class: str | None
+    old_value: Any
+    new_value: Any
+    old_value_type: str | None
+    new_value_type: str | None
+    old_id: int | None
+    new_id: int | None
+
+    @property
+    def value_changed(self) -> bool:
+        ...
+
+    @property
+    def value_type_changed(self) -> bool:
+        ...
+
+    @property
+    def id_changed(self) -> bool:
+        ...
+
+class PsetsDiff:
+    """
+    Structured change set between two Psets
+    """
+
+    added_psets: list[str]
+    removed_psets: list[str]
+    added_properties: list[str]
+    removed_properties: list[str]
+    changed: list[PsetChange]
+
+    def __bool__(self) -> bool:
+        ...
+
+class IdsStatus:
+    """
+    Outcome of an IDS check
+    """
+
+    PASS: IdsStatus
+    FAIL: IdsStatus
+    SKIPPED: IdsStatus
+
+class IdsRequirementResult:
+    description: str
+    status: IdsStatus
+    failures: list[tuple[str, str]]
+
+class IdsSpecificationResult:
+    name: str
+    status: IdsStatus
+    applicable: list[str]
+    messages: list[str]
+    requirements: list[IdsRequirementResult]
+
+class IdsReport:
+    """
+    Per-specification, per-requirement outcome of an IDS check
+    """
+
+    title: str | None
+    specifications: list[IdsSpecificationResult]
+
+    @property
+    def passed(self) -> bool:
+        ...
+
+class IdsDocument:
+    """
+    Information Delivery Specification (IDS 1.0), checking property facets only
+    """
+
+    @staticmethod
+    def from_xml(text: str, /) -> IdsDocument:
+        ...
+
+    @property
+    def title(self) -> str | None:
+        ...
+
+    @property
+    def specifications(self) -> list[str]:
+        ...
+
+    def check(
+        self,
+        psets: Psets | dict[str, Psets],
+        /,
+        *,
+        entity_class: str | dict[str, str] | None = None,
+    ) -> IdsReport:
+        """
+        Checks elements given by their Psets (a single Psets is named "psets"),
+        with the entity class optionally followed by the predefined type (`IfcWall/SHEAR`);
+        specifications whose applicability cannot be evaluated on some element
+        (unknown entity class or predefined type, unsupported facets) are SKIPPED
+        """
+        ...
+
+    def __len__(self) -> int:
+        ...
+
+class StepIdAllocator:
+    """
+    Allocator of entity ids, continuing after the ids of a file
+    """
+
+    def __init__(self, next_id: int = 1) -> None:
+        ...
+
+    @staticmethod
+    def from_ifc(path: str, /) -> StepIdAllocator:
+        ...
+
+    @property
+    def next_id(self) -> int:
+        ...
+
+    def allocate(self) -> int:
+        ...
+
+class Psets(RootModel[Pset | PsetId | Quantity | PsetProperty | PsetComplexProperty | dict[str, Psets | Any]]):
     """
     Basic part of pset
     """
@@ -63,23 +818,342 @@ class Psets(RootModel[Pset | PsetId | dict[str, Psets | Any]]):
     model_config = ConfigDict(
         populate_by_name=True,
     )
-    root: Pset | PsetId | dict[str, Psets | Any] = {}
+    root: Pset | PsetId | Quantity | PsetProperty | PsetComplexProperty | dict[str, Psets | Any] = {}
 
     @staticmethod
     def model_validate(value: Any, /) -> Psets:
         ...
 
+    def __eq__(self, other: object) -> bool:
+        ...
+
+    def __hash__(self) -> int:
+        ...
+
+    def equals(self, other: Psets, /, *, ignore_ids: bool = False) -> bool:
+        """
+        Compares by value, optionally ignoring ids
+        """
+        ...
+
     def flatten(
         self,
         /,
         *,
         delimiter: str = ":",
-    ) -> dict[str, Pset | PsetId | Any]:
+    ) -> dict[str, Pset | PsetId | Quantity | PsetProperty | Any]:
+        """
+        Maps addresses to entries; a delimiter (or backslash) within a key is escaped
+        by a backslash, and empty sets are kept as empty dictionaries
+        """
+        ...
+
+    def sorted(self) -> Psets:
+        """
+        Returns a copy with all nested entries sorted by key
+        (by default entries keep the order of the input)
+        """
+        ...
+
+    @staticmethod
+    def unflatten(
+        elements: dict[str, Pset | PsetId | Quantity | PsetProperty | Any],
+        /,
+        *,
+        delimiter: str = ":",
+    ) -> Psets:
+        """
+        Rebuilds the nested structure from the output of `flatten`
+        """
+        ...
+
+    def get(
+        self,
+        path: str,
+        default: Any = None,
+        /,
+        *,
+        delimiter: str = ":",
+    ) -> Pset | PsetId | Quantity | PsetProperty | PsetComplexProperty | Psets | Any:
+        """
+        Gets the entry at an address as produced by `flatten`, split on the delimiter;
+        unlike `[]` and `in`, which take the key of a direct child as it is
+        """
         ...
 
-    def __iter__(self) ->  Generator[
-        tuple[str | None, Pset | PsetId | Any],
+    def with_value(
+        self,
+        path: str,
+        value: Pset | PsetId | Quantity | PsetProperty | PsetComplexProperty | Psets | Any,
+        /,
+        *,
+        delimiter: str = ":",
+    ) -> Psets:
+        """
+        Returns a copy with the entry at an address set,
+        creating intermediate nested entries if missing
+        """
+        ...
+
+    def without(
+        self,
+        path: str,
+        /,
+        *,
+        delimiter: str = ":",
+    ) -> Psets:
+        """
+        Returns a copy with the entry at an address removed
+        """
+        ...
+
+    def set(
+        self,
+        path: str,
+        value: Pset | PsetId | Quantity | PsetProperty | PsetComplexProperty | Psets | Any,
+        /,
+        *,
+        delimiter: str = ":",
+    ) -> Psets:
+        """
+        Same as `with_value`: Psets are immutable, so the updated copy is returned
+        """
+        ...
+
+    def delete(
+        self,
+        path: str,
+        /,
+        *,
+        delimiter: str = ":",
+    ) -> Psets:
+        """
+        Same as `without`: Psets are immutable, so the updated copy is returned
+        """
+        ...
+
+    def __iter__(self) -> Generator[
+        tuple[str | None, Pset | PsetId | Quantity | PsetProperty | PsetComplexProperty | Psets | Any],
+        None,
+        None,
+    ]:
+        ...
+
+    def walk(
+        self,
+        /,
+        *,
+        order: Literal["pre", "post"] = "pre",
+        max_depth: int | None = None,
+        leaves_only: bool = False,
+    ) -> Generator[
+        tuple[tuple[str, ...], int, Pset | PsetId | Quantity | PsetProperty | PsetComplexProperty | Psets | Any],
         None,
         None,
     ]:
+        """
+        Traverses all nodes lazily (starting with the root at depth 0),
+        yielding the path, depth and node of each;
+        with `leaves_only`, (empty) sets are skipped
+        """
+        ...
+
+    def diff(
+        self,
+        other: Psets,
+        /,
+        *,
+        ignore_ids: bool = False,
+        delimiter: str = ":",
+    ) -> PsetsDiff:
+        """
+        Computes the changes needed to turn this into `other`
+        """
+        ...
+
+    def merge(
+        self,
+        other: Psets,
+        /,
+        *,
+        strategy: Literal["prefer-left", "prefer-right", "raise-on-conflict"]
+        | Callable[
+            [tuple[str, ...], Pset | PsetId | Quantity | PsetProperty | PsetComplexProperty | Psets | Any, Pset | PsetId | Quantity | PsetProperty | PsetComplexProperty | Psets | Any],
+            Pset | PsetId | Quantity | PsetProperty | PsetComplexProperty | Psets | Any,
+        ] = "prefer-right",
+        ignore_ids: bool = False,
+    ) -> Psets:
+        """
+        Merges with `other`, recursing through nested entries present on both sides.
+        Entries that differ are resolved by the strategy,
+        which can be a callback receiving the path, left and right entries;
+        with `ignore_ids`, entries differing only by their ids are kept from this side.
+        """
+        ...
+
+    def to_patch(self, other: Psets, /) -> list[dict[str, Any]]:
+        """
+        Creates a JSON Patch (RFC 6902) turning this into `other`,
+        with paths relative to the JSON form
+        """
+        ...
+
+    def apply_patch(self, patch: list[dict[str, Any]], /) -> Psets:
+        """
+        Returns a copy with a JSON Patch (RFC 6902) applied
+        """
+        ...
+
+    def select(
+        self,
+        pattern: str,
+        /,
+        *,
+        regex: bool = False,
+        class_: str | Callable[[str | None], bool] | None = None,
+        value_type: str | Callable[[str | None], bool] | None = None,
+        value: Any | Callable[[Any], bool] | None = None,
+        delimiter: str = ":",
+    ) -> list[tuple[str, Pset | PsetId | Quantity | PsetProperty | Any]]:
+        """
+        Lists the flattened entries whose address matches the pattern
+        and which satisfy the predicates (either values to compare or callables).
+
+        Glob patterns are matched per segment: `*` and `?` do not cross delimiters,
+        whilst a `**` segment matches any number of segments.
+        """
+        ...
+
+    def validate_against_templates(
+        self,
+        entity_class: str | None = None,
+        *,
+        schema: Literal["IFC4", "IFC4X3"] = "IFC4X3",
+        library: PsetTemplateLibrary | None = None,
+        delimiter: str = ":",
+    ) -> PsetsValidation:
+        """
+        Checks the sets against a template library (the bundled one of `schema` by default),
+        reporting unknown properties, wrong types, invalid enumeration values
+        and sets which do not apply to the entity class (if given)
+        """
+        ...
+
+    def convert_units(self, target: UnitAssignment, /, *, units: UnitAssignment | None = None) -> Psets:
+        """
+        Converts measure values of single-value properties and quantities
+        from the units of `units` (or else SI units) into those of `target`
+        """
         ...
+
+    def resolve_references(
+        self,
+        resolver: dict[int, Any] | Callable[[int | None, str | None], Any],
+        /,
+        *,
+        delimiter: str = ":",
+    ) -> dict[str, Any | None]:
+        """
+        Resolves all reference properties, keyed by their flattened path
+        """
+        ...
+
+    def to_step(
+        self,
+        element_id: int,
+        allocator: StepIdAllocator,
+        /,
+        *,
+        schema: str = "IFC4",
+        owner_history: int | None = None,
+    ) -> list[str]:
+        """
+        Entity lines (`#n=IFCPROPERTYSET(...);`) of the sets and their relationships
+        to the element `#element_id`, with ids taken from `allocator`
+        """
+        ...
+
+    def __len__(self) -> int:
+        ...
+
+    def __contains__(self, key: str) -> bool:
+        """
+        Whether a direct child has the key (not split on a delimiter, see `get`)
+        """
+        ...
+
+    def __getitem__(self, key: str) -> Pset | PsetId | Quantity | PsetProperty | PsetComplexProperty | Psets | Any:
+        """
+        Direct child with the key (not split on a delimiter, see `get`)
+        """
+        ...
+
+    def keys(self) -> list[str | None]:
+        ...
+
+    def values(self) -> list[Pset | PsetId | Quantity | PsetProperty | PsetComplexProperty | Psets | Any]:
+        ...
+
+    def items(self) -> list[tuple[str | None, Pset | PsetId | Quantity | PsetProperty | PsetComplexProperty | Psets | Any]]:
+        ...
+
+class PsetsCollection:
+    """
+    Psets of many elements, by the key of the element (GlobalId or STEP id);
+    integer keys (STEP ids) are stored in their decimal form
+    """
+
+    def __init__(self, value: dict[str | int, Psets | Any] | None = None, /) -> None:
+        ...
+
+    @staticmethod
+    def model_validate(value: dict[str | int, Psets | Any], /) -> PsetsCollection:
+        ...
+
+    def __iter__(self) -> Generator[tuple[str, Psets], None, None]:
+        ...
+
+    def __len__(self) -> int:
+        ...
+
+    def __contains__(self, key: str | int) -> bool:
+        ...
+
+    def __getitem__(self, key: str | int) -> Psets:
+        ...
+
+    def get(self, key: str | int, default: Any = None, /) -> Psets | Any:
+        ...
+
+    def keys(self) -> list[str]:
+        ...
+
+    def values(self) -> list[Psets]:
+        ...
+
+    def items(self) -> list[tuple[str, Psets]]:
+        ...
+
+    def flatten(self, *, delimiter: str = ":") -> dict[str, Any]:
+        """
+        Flattens the Psets of all elements, prefixing each path with the key of the element
+        """
+        ...
+
+    def to_json(self) -> dict[str, Any]:
+        ...
+
+def read_ifc_psets(path: str, /, *, include_types: bool = True, strict: bool = True) -> dict[str, Psets]:
+    """
+    Reads the property and quantity sets of all elements of an IFC (STEP) file,
+    by the GlobalId of the elements (or their STEP id for elements without GlobalId),
+    as by `ifcopenshell.util.element.get_psets(..., verbose=True)`;
+    with `strict=False` sets with invalid properties are kept as plain values instead of failing
+    """
+    ...
+
+def patch_ifc_file(source: str, target: str, lines: list[str], /) -> None:
+    """
+    Writes a copy of an IFC (STEP) file with entity lines added to the end of its data section
+    """
+    ...
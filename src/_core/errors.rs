@@ -2,6 +2,8 @@
 // IMPORTS
 // ----------------------------------------------------------------
 
+use pyo3::exceptions::PyIndexError;
+use pyo3::exceptions::PyKeyError;
 use pyo3::exceptions::PyTypeError;
 use pyo3::prelude::PyErr;
 use std::fmt::Debug;
@@ -24,3 +26,19 @@ where
     let err_str = format!("{:?}", err);
     return PyErr::new::<PyTypeError, _>(err_str);
 }
+
+pub fn err_to_py_key_error<E>(err: E) -> PyErr
+where
+    E: Debug,
+{
+    let err_str = format!("{:?}", err);
+    return PyErr::new::<PyKeyError, _>(err_str);
+}
+
+pub fn err_to_py_index_error<E>(err: E) -> PyErr
+where
+    E: Debug,
+{
+    let err_str = format!("{:?}", err);
+    return PyErr::new::<PyIndexError, _>(err_str);
+}
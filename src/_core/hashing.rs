@@ -0,0 +1,21 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+
+// ----------------------------------------------------------------
+// METHODS
+// ----------------------------------------------------------------
+
+/// Hash of a value, as exposed to python via `__hash__`
+pub fn hash_of<T>(value: &T) -> u64
+where
+    T: Hash,
+{
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    return hasher.finish();
+}
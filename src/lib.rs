@@ -11,13 +11,13 @@ use pyo3::types::PyModuleMethods;
 // use pyo3::FromPyObject;
 // use pyo3::exceptions::PyTypeError;
 // use pyo3::prelude::PyErr;
-// use pyo3::prelude::pyfunction;
-// use pyo3::prelude::wrap_pyfunction;
+use pyo3::prelude::wrap_pyfunction;
 // use pyo3::types::PyAny;
 
 mod _core;
 mod models;
 use models::bim;
+use models::step;
 // use models::tree;
 // use models::json;
 
@@ -28,9 +28,36 @@ use models::bim;
 #[pymodule(name = "example_package")]
 pub fn createmodule<'a>(_py: Python<'a>, m: &Bound<'a, PyModule>) -> PyResult<()> {
     // m.add_function(wrap_pyfunction!(probe, m)?)?;
+    m.add_function(wrap_pyfunction!(step::extract::read_ifc_psets, m)?)?;
+    m.add_function(wrap_pyfunction!(step::writer::patch_ifc_file, m)?)?;
     m.add_class::<bim::psets::PsetId>()?;
     m.add_class::<bim::psets::Pset>()?;
+    m.add_class::<bim::psets::QuantityDimension>()?;
+    m.add_class::<bim::psets::Quantity>()?;
     m.add_class::<bim::psets::Psets>()?;
+    m.add_class::<bim::collection::PsetsCollection>()?;
+    m.add_class::<bim::properties::PsetEnumeration>()?;
+    m.add_class::<bim::properties::PsetEnumeratedValue>()?;
+    m.add_class::<bim::properties::PsetBoundedValue>()?;
+    m.add_class::<bim::properties::CurveInterpolation>()?;
+    m.add_class::<bim::properties::PsetTableValue>()?;
+    m.add_class::<bim::properties::PsetListValue>()?;
+    m.add_class::<bim::properties::PsetReferenceValue>()?;
+    m.add_class::<bim::properties::PsetComplexProperty>()?;
+    m.add_class::<bim::values::IfcLogical>()?;
+    m.add_class::<bim::values::IfcValue>()?;
+    m.add_class::<bim::units::UnitAssignment>()?;
+    m.add_class::<bim::templates::PsetTemplateLibrary>()?;
+    m.add_class::<bim::templates::TemplateIssue>()?;
+    m.add_class::<bim::templates::PsetsValidation>()?;
+    m.add_class::<bim::ids::IdsDocument>()?;
+    m.add_class::<bim::ids::IdsStatus>()?;
+    m.add_class::<bim::ids::IdsReport>()?;
+    m.add_class::<bim::ids::IdsSpecificationResult>()?;
+    m.add_class::<bim::ids::IdsRequirementResult>()?;
+    m.add_class::<bim::diff::PsetChange>()?;
+    m.add_class::<bim::diff::PsetsDiff>()?;
+    m.add_class::<step::writer::StepIdAllocator>()?;
     return Ok(());
 }
 
@@ -0,0 +1,293 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use indexmap::IndexMap;
+use pyo3::Bound;
+use pyo3::FromPyObject; // NOTE: needed, in order to "import" the FromPyObject implementations of other structs
+use pyo3::IntoPyObject;
+use pyo3::Py;
+use pyo3::PyRef;
+use pyo3::PyRefMut;
+use pyo3::PyResult;
+use pyo3::Python;
+use pyo3::prelude::pyclass;
+use pyo3::prelude::pymethods;
+use pyo3::types::PyAny;
+use pyo3::types::PyAnyMethods;
+use pyo3::types::PyDict;
+use pyo3::types::PyDictMethods;
+use pyo3::types::PyString;
+use pyo3::types::PyTuple;
+use serde_json::Map;
+use serde_json::Value;
+use std::result::Result;
+
+use super::psets::PsetFlattenedValue;
+use super::psets::Psets;
+use crate::_core::errors::err_to_py_key_error;
+use crate::_core::errors::err_to_py_string;
+use crate::models::json::base::JsonConversion;
+use crate::models::json::model::ValueWrap;
+
+// ----------------------------------------------------------------
+// STRUCTS
+// ----------------------------------------------------------------
+
+/// Psets of many elements, by the key of the element (GlobalId or STEP id)
+#[pyclass(frozen)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PsetsCollection {
+    pub elements: IndexMap<String, Psets>,
+}
+
+#[pyclass]
+struct PsetsCollectionIterator {
+    entity: Py<PsetsCollection>,
+    index: usize,
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF PsetsCollection
+// ----------------------------------------------------------------
+
+/// Only for python implementation
+#[pymethods]
+impl PsetsCollection {
+    #[new]
+    #[pyo3(signature = (value=None, /))]
+    pub fn new(value: Option<&Bound<'_, PyAny>>) -> PyResult<Self> {
+        match value {
+            Some(value) => Self::model_validate(value),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Reads the Psets of all elements from a dictionary, keyed by GlobalId or STEP id
+    #[staticmethod]
+    #[pyo3(signature = (value, /))]
+    pub fn model_validate(value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let Ok(value) = value.downcast::<PyDict>() else {
+            let raw = ValueWrap::extract_bound(value)?;
+            let value = raw.to_json().map_err(err_to_py_string)?;
+            return Self::from_json(&value).map_err(err_to_py_string);
+        };
+        let mut elements = IndexMap::<String, Psets>::new();
+        for (key, x) in value.iter() {
+            let key = element_key(&key)?;
+            // NOTE: Psets objects are taken as they are, anything else is parsed
+            let psets = match x.extract::<Psets>() {
+                Ok(psets) => psets,
+                Err(_) => {
+                    let value = ValueWrap::extract_bound(&x)?
+                        .to_json()
+                        .map_err(err_to_py_string)?;
+                    Psets::from_json(&value)
+                        .map_err(|err| err_to_py_string(format!("{}: {}", key, err)))?
+                }
+            };
+            elements.insert(key, psets);
+        }
+        return Ok(Self { elements });
+    }
+
+    #[staticmethod]
+    fn __class_name__() -> String {
+        "PsetsCollection".to_string()
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PsetsCollectionIterator {
+        return PsetsCollectionIterator {
+            entity: slf.into(),
+            index: 0,
+        };
+    }
+
+    fn __len__(&self) -> usize {
+        self.elements.len()
+    }
+
+    fn __contains__(&self, key: &Bound<'_, PyAny>) -> PyResult<bool> {
+        let key = element_key(key)?;
+        return Ok(self.elements.contains_key(&key));
+    }
+
+    fn __getitem__(&self, key: &Bound<'_, PyAny>) -> PyResult<Psets> {
+        let key = element_key(key)?;
+        match self.elements.get(&key) {
+            Some(psets) => {
+                return Ok(psets.clone());
+            }
+            None => {
+                return Err(err_to_py_key_error(key));
+            }
+        }
+    }
+
+    #[pyo3(signature = (key, default=None, /))]
+    fn get<'a>(
+        &self,
+        py: Python<'a>,
+        key: &Bound<'a, PyAny>,
+        default: Option<Bound<'a, PyAny>>,
+    ) -> PyResult<Bound<'a, PyAny>> {
+        let key = element_key(key)?;
+        match self.elements.get(&key) {
+            Some(psets) => {
+                return Ok(Bound::new(py, psets.clone())?.into_any());
+            }
+            None => {
+                let result = default.unwrap_or_else(|| py.None().into_bound(py));
+                return Ok(result);
+            }
+        }
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.elements.keys().cloned().collect()
+    }
+
+    fn values(&self) -> Vec<Psets> {
+        self.elements.values().cloned().collect()
+    }
+
+    fn items(&self) -> Vec<(String, Psets)> {
+        self.elements
+            .iter()
+            .map(|(key, x)| (key.clone(), x.clone()))
+            .collect()
+    }
+
+    /// Flattens the Psets of all elements, prefixing each path with the key of the element
+    #[pyo3(signature = (/, *, delimiter=":".to_string()))]
+    fn flatten<'a>(
+        &self,
+        py: Python<'a>,
+        delimiter: String,
+    ) -> PyResult<IndexMap<String, Bound<'a, PyAny>>> {
+        let mut result = IndexMap::<String, Bound<'a, PyAny>>::new();
+        for (key, psets) in self.elements.iter() {
+            for (path, x) in psets.flatten(py, delimiter.clone())? {
+                result.insert(join_path(key, &path, &delimiter), x);
+            }
+        }
+        return Ok(result);
+    }
+
+    #[pyo3(name = "to_json")]
+    fn py_to_json<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let raw = self.to_json().map_err(err_to_py_string)?;
+        return ValueWrap(raw).into_pyobject(py);
+    }
+
+    fn __eq__(&self, other: PyRef<'_, Self>) -> bool {
+        self == &*other
+    }
+
+    pub fn __str__(&self) -> PyResult<String> {
+        Ok(self.to_string())
+    }
+}
+
+/// Rust implementation of struct
+impl PsetsCollection {
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    pub fn rust_get(&self, key: &str) -> Option<&Psets> {
+        self.elements.get(key)
+    }
+
+    /// Flattened entries of all elements, each path prefixed with the key of the element
+    pub fn rust_flatten(&self, delimiter: Option<&String>) -> IndexMap<String, PsetFlattenedValue> {
+        let mut result = IndexMap::<String, PsetFlattenedValue>::new();
+        for (key, psets) in self.elements.iter() {
+            let delimiter = delimiter.map_or_else(|| ":".to_string(), |x| x.clone());
+            let key = Psets::escape_key(key, &delimiter);
+            result.extend(psets.rust_flatten(Some(&delimiter), Some(&key)));
+        }
+        return result;
+    }
+}
+
+impl ToString for PsetsCollection {
+    fn to_string(&self) -> String {
+        format!("PsetsCollection(elements={})", self.elements.len())
+    }
+}
+
+impl JsonConversion<Value> for PsetsCollection {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        let Value::Object(object) = value else {
+            return Err("a collection must map element keys to Psets".to_string());
+        };
+        let mut elements = IndexMap::<String, Psets>::new();
+        for (key, x) in object.iter() {
+            let psets = Psets::from_json(x).map_err(|err| format!("{}: {}", key, err))?;
+            elements.insert(key.clone(), psets);
+        }
+        return Ok(Self { elements });
+    }
+
+    fn to_json(&self) -> Result<Value, String> {
+        let mut result = Map::<String, Value>::new();
+        for (key, psets) in self.elements.iter() {
+            result.insert(key.clone(), psets.to_json()?);
+        }
+        return Ok(Value::Object(result));
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF PsetsCollectionIterator
+// ----------------------------------------------------------------
+
+#[pymethods]
+impl PsetsCollectionIterator {
+    fn __iter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    fn __next__<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        py: Python<'py>,
+    ) -> PyResult<Option<Bound<'py, PyTuple>>> {
+        let index = slf.index;
+        slf.index += 1;
+        // NOTE: PsetsCollection is frozen, so the entity can be read without cloning
+        match slf.entity.get().elements.get_index(index) {
+            Some((key, psets)) => {
+                let key = PyString::new(py, key).into_any();
+                let value = Bound::new(py, psets.clone())?.into_any();
+                return Ok(Some(PyTuple::new(py, vec![key, value])?));
+            }
+            None => {
+                return Ok(None);
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+// AUXILIARY METHODS
+// ----------------------------------------------------------------
+
+/// Key of an element from a python key, STEP ids (integers) are keyed by their decimal form
+fn element_key(key: &Bound<'_, PyAny>) -> PyResult<String> {
+    if let Ok(id) = key.extract::<i64>() {
+        return Ok(id.to_string());
+    }
+    return key.extract::<String>();
+}
+
+fn join_path(key: &str, path: &str, delimiter: &str) -> String {
+    match path.is_empty() {
+        true => Psets::escape_key(key, delimiter),
+        false => format!("{}{}{}", Psets::escape_key(key, delimiter), delimiter, path),
+    }
+}
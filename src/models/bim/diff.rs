@@ -0,0 +1,298 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use indexmap::IndexSet;
+use pyo3::PyResult;
+use pyo3::prelude::pyclass;
+use pyo3::prelude::pymethods;
+use serde_json::Value;
+use serde_json::json;
+
+use super::psets::PsetFlattenedValue;
+use super::psets::Psets;
+use crate::models::json::model::ValueWrap;
+
+// ----------------------------------------------------------------
+// STRUCTS
+// ----------------------------------------------------------------
+
+/// Change of a single entry present in both Psets
+#[pyclass(get_all)]
+#[derive(Clone, Debug)]
+pub struct PsetChange {
+    pub path: String,
+    pub old_class: Option<String>,
+    pub new_class: Option<String>,
+    pub old_value: ValueWrap,
+    pub new_value: ValueWrap,
+    pub old_value_type: Option<String>,
+    pub new_value_type: Option<String>,
+    pub old_id: Option<i64>,
+    pub new_id: Option<i64>,
+}
+
+/// Structured change set between two Psets
+#[pyclass(get_all)]
+#[derive(Clone, Debug, Default)]
+pub struct PsetsDiff {
+    pub added_psets: Vec<String>,
+    pub removed_psets: Vec<String>,
+    pub added_properties: Vec<String>,
+    pub removed_properties: Vec<String>,
+    pub changed: Vec<PsetChange>,
+}
+
+/// Comparable parts of a flattened entry
+struct PsetEntryParts {
+    class_: Option<String>,
+    value: ValueWrap,
+    value_type: Option<String>,
+    id_: Option<i64>,
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF PsetEntryParts
+// ----------------------------------------------------------------
+
+impl PsetEntryParts {
+    fn new(path: &str, entity: &PsetFlattenedValue, delimiter: &str) -> Self {
+        match entity {
+            PsetFlattenedValue::Pset(x) => Self {
+                class_: Some(x.class_.clone()),
+                value: x.value.clone(),
+                value_type: x.value_type.clone(),
+                id_: Some(x.id_),
+            },
+            PsetFlattenedValue::Quantity(x) => Self {
+                class_: Some(x.class_.clone()),
+                value: x.value.clone(),
+                value_type: x.dimension().ok().map(|x| x.value_type().to_string()),
+                id_: Some(x.id_),
+            },
+            PsetFlattenedValue::Property(x) => Self {
+                class_: Some(x.class_().clone()),
+                value: ValueWrap(x.value()),
+                value_type: x.value_type().cloned(),
+                id_: Some(x.id_()),
+            },
+            PsetFlattenedValue::Complex(x) => Self {
+                class_: Some(x.class_.clone()),
+                value: ValueWrap(x.usage_name.as_ref().map_or(Value::Null, |x| json!(x))),
+                value_type: None,
+                id_: Some(x.id_),
+            },
+            PsetFlattenedValue::PsetId(x) => Self {
+                class_: None,
+                value: ValueWrap(Value::Null),
+                value_type: None,
+                id_: Some(x.id_),
+            },
+            PsetFlattenedValue::Value(x) => match id_of_entry(path, entity, delimiter) {
+                Some(id_) => Self {
+                    class_: None,
+                    value: ValueWrap(Value::Null),
+                    value_type: None,
+                    id_: Some(id_),
+                },
+                None => Self {
+                    class_: None,
+                    value: x.clone(),
+                    value_type: None,
+                    id_: None,
+                },
+            },
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF PsetChange
+// ----------------------------------------------------------------
+
+impl PsetChange {
+    /// Compares two entries at the same address, returning `None` if they agree
+    fn compare(
+        path: &str,
+        old: &PsetFlattenedValue,
+        new: &PsetFlattenedValue,
+        ignore_ids: bool,
+        delimiter: &str,
+    ) -> Option<Self> {
+        let mut old = PsetEntryParts::new(path, old, delimiter);
+        let mut new = PsetEntryParts::new(path, new, delimiter);
+        if ignore_ids {
+            old.id_ = None;
+            new.id_ = None;
+        }
+        let ValueWrap(old_value) = &old.value;
+        let ValueWrap(new_value) = &new.value;
+        let same = old.class_ == new.class_
+            && old_value == new_value
+            && old.value_type == new.value_type
+            && old.id_ == new.id_;
+        if same {
+            return None;
+        }
+        let result = Self {
+            path: path.to_string(),
+            old_class: old.class_,
+            new_class: new.class_,
+            old_value: old.value,
+            new_value: new.value,
+            old_value_type: old.value_type,
+            new_value_type: new.value_type,
+            old_id: old.id_,
+            new_id: new.id_,
+        };
+        return Some(result);
+    }
+}
+
+#[pymethods]
+impl PsetChange {
+    #[getter]
+    pub fn value_changed(&self) -> bool {
+        let ValueWrap(old_value) = &self.old_value;
+        let ValueWrap(new_value) = &self.new_value;
+        return old_value != new_value || self.old_class != self.new_class;
+    }
+
+    #[getter]
+    pub fn value_type_changed(&self) -> bool {
+        self.old_value_type != self.new_value_type
+    }
+
+    #[getter]
+    pub fn id_changed(&self) -> bool {
+        self.old_id != self.new_id
+    }
+
+    pub fn __str__(&self) -> PyResult<String> {
+        Ok(self.to_string())
+    }
+}
+
+impl ToString for PsetChange {
+    fn to_string(&self) -> String {
+        let ValueWrap(old_value) = &self.old_value;
+        let ValueWrap(new_value) = &self.new_value;
+        let mut parts: Vec<String> = vec![];
+        if self.value_changed() {
+            parts.push(format!("value {} -> {}", old_value, new_value));
+        }
+        if self.value_type_changed() {
+            parts.push(format!(
+                "value-type {:?} -> {:?}",
+                self.old_value_type, self.new_value_type
+            ));
+        }
+        if self.id_changed() {
+            parts.push(format!("id {:?} -> {:?}", self.old_id, self.new_id));
+        }
+        return format!("{}: {}", self.path, parts.join(", "));
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF PsetsDiff
+// ----------------------------------------------------------------
+
+/// Rust implementation of struct
+impl PsetsDiff {
+    /// Computes the changes needed to turn `old` into `new`
+    pub fn new(old: &Psets, new: &Psets, ignore_ids: bool, delimiter: Option<&String>) -> Self {
+        let delimiter = delimiter.map_or_else(|| ":".to_string(), |x| x.clone());
+        let old_elements = old.rust_flatten(Some(&delimiter), None);
+        let new_elements = new.rust_flatten(Some(&delimiter), None);
+        let old_psets: IndexSet<String> = old
+            .children()
+            .map_or_else(IndexSet::new, |x| x.keys().cloned().collect());
+        let new_psets: IndexSet<String> = new
+            .children()
+            .map_or_else(IndexSet::new, |x| x.keys().cloned().collect());
+
+        let mut result = Self {
+            added_psets: new_psets.difference(&old_psets).cloned().collect(),
+            removed_psets: old_psets.difference(&new_psets).cloned().collect(),
+            ..Self::default()
+        };
+
+        for (path, x) in old_elements.iter() {
+            let pset = Psets::split_path(path, Some(&delimiter))[0].clone();
+            if result.removed_psets.contains(&pset) {
+                continue;
+            }
+            match new_elements.get(path) {
+                Some(y) => {
+                    if let Some(change) = PsetChange::compare(path, x, y, ignore_ids, &delimiter) {
+                        result.changed.push(change);
+                    }
+                }
+                None => {
+                    if ignore_ids && id_of_entry(path, x, &delimiter).is_some() {
+                        continue;
+                    }
+                    result.removed_properties.push(path.clone());
+                }
+            }
+        }
+
+        for (path, x) in new_elements.iter() {
+            let pset = Psets::split_path(path, Some(&delimiter))[0].clone();
+            if result.added_psets.contains(&pset) || old_elements.contains_key(path) {
+                continue;
+            }
+            if ignore_ids && id_of_entry(path, x, &delimiter).is_some() {
+                continue;
+            }
+            result.added_properties.push(path.clone());
+        }
+
+        return result;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added_psets.is_empty()
+            && self.removed_psets.is_empty()
+            && self.added_properties.is_empty()
+            && self.removed_properties.is_empty()
+            && self.changed.is_empty()
+    }
+}
+
+#[pymethods]
+impl PsetsDiff {
+    fn __bool__(&self) -> bool {
+        !self.is_empty()
+    }
+
+    pub fn __str__(&self) -> PyResult<String> {
+        Ok(self.to_string())
+    }
+}
+
+impl ToString for PsetsDiff {
+    fn to_string(&self) -> String {
+        let mut lines: Vec<String> = vec![];
+        lines.extend(self.added_psets.iter().map(|x| format!("+ {}", x)));
+        lines.extend(self.removed_psets.iter().map(|x| format!("- {}", x)));
+        lines.extend(self.added_properties.iter().map(|x| format!("+ {}", x)));
+        lines.extend(self.removed_properties.iter().map(|x| format!("- {}", x)));
+        lines.extend(self.changed.iter().map(|x| format!("~ {}", x.to_string())));
+        return lines.join("\n");
+    }
+}
+
+// ----------------------------------------------------------------
+// AUXILIARY METHODS
+// ----------------------------------------------------------------
+
+/// Id of a (verbose) pset, stored as a plain entry with key `id` alongside its properties
+fn id_of_entry(path: &str, entity: &PsetFlattenedValue, delimiter: &str) -> Option<i64> {
+    let key = path.rsplit(delimiter).next()?;
+    match (key, entity) {
+        ("id", PsetFlattenedValue::Value(ValueWrap(value))) => value.as_i64(),
+        _ => None,
+    }
+}
@@ -0,0 +1,887 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use indexmap::IndexMap;
+use pyo3::Bound;
+use pyo3::PyResult;
+use pyo3::prelude::pyclass;
+use pyo3::prelude::pymethods;
+use pyo3::types::PyAny;
+use pyo3::types::PyAnyMethods;
+use regex::Regex;
+use roxmltree::Document;
+use roxmltree::Node;
+use serde_json::Value;
+use std::result::Result;
+
+use super::properties::PsetProperty;
+use super::psets::PsetNestedValue;
+use super::psets::Psets;
+use crate::_core::errors::err_to_py_string;
+use crate::_core::errors::err_to_string;
+use crate::models::json::model::ValueWrap;
+
+// ----------------------------------------------------------------
+// STRUCTS
+// ----------------------------------------------------------------
+
+/// Value of a facet parameter, either a simple value or an XSD restriction
+#[derive(Clone, Debug)]
+pub enum IdsValue {
+    Simple(String),
+    Restriction(Box<IdsRestriction>),
+}
+
+/// XSD restriction of a facet parameter (all given constraints must hold)
+#[derive(Clone, Debug, Default)]
+pub struct IdsRestriction {
+    pub enumeration: Vec<String>,
+    pub patterns: Vec<Regex>,
+    pub min_inclusive: Option<f64>,
+    pub min_exclusive: Option<f64>,
+    pub max_inclusive: Option<f64>,
+    pub max_exclusive: Option<f64>,
+    pub length: Option<usize>,
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdsCardinality {
+    Required,
+    Optional,
+    Prohibited,
+}
+
+/// Condition on an element, as used by applicabilities and requirements
+#[derive(Clone, Debug)]
+pub enum IdsFacet {
+    Entity {
+        name: IdsValue,
+        predefined_type: Option<IdsValue>,
+    },
+    Property {
+        property_set: IdsValue,
+        base_name: IdsValue,
+        value: Option<IdsValue>,
+        data_type: Option<String>,
+        cardinality: IdsCardinality,
+    },
+    /// facets addressing data outside of property sets (attributes, classifications, ...)
+    Unsupported { name: String },
+}
+
+/// Specification of an IDS, applying requirements to the elements matching its applicability
+#[derive(Clone, Debug)]
+pub struct IdsSpecification {
+    pub name: String,
+    pub description: Option<String>,
+    pub ifc_versions: Vec<String>,
+    pub min_occurs: usize,
+    pub max_occurs: Option<usize>,
+    pub applicability: Vec<IdsFacet>,
+    pub requirements: Vec<IdsFacet>,
+}
+
+/// Information Delivery Specification (IDS 1.0)
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct IdsDocument {
+    pub title: Option<String>,
+    pub specifications: Vec<IdsSpecification>,
+}
+
+/// Element to be checked, given by its property sets and (optionally) its class and predefined type
+pub struct IdsElement<'a> {
+    pub name: String,
+    pub entity_class: Option<String>,
+    pub predefined_type: Option<String>,
+    pub psets: &'a Psets,
+}
+
+/// Outcome of a check
+#[pyclass(eq, eq_int)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IdsStatus {
+    #[pyo3(name = "PASS")]
+    Pass,
+    #[pyo3(name = "FAIL")]
+    Fail,
+    /// the facet cannot be evaluated on property sets
+    #[pyo3(name = "SKIPPED")]
+    Skipped,
+}
+
+#[pyclass(get_all)]
+#[derive(Clone, Debug)]
+pub struct IdsRequirementResult {
+    pub description: String,
+    pub status: IdsStatus,
+    /// failing elements with the reason
+    pub failures: Vec<(String, String)>,
+}
+
+#[pyclass(get_all)]
+#[derive(Clone, Debug)]
+pub struct IdsSpecificationResult {
+    pub name: String,
+    pub status: IdsStatus,
+    /// names of the elements matching the applicability
+    pub applicable: Vec<String>,
+    /// violations of the specification as a whole (occurrence of applicable elements),
+    /// or the elements whose applicability cannot be evaluated
+    pub messages: Vec<String>,
+    pub requirements: Vec<IdsRequirementResult>,
+}
+
+#[pyclass(get_all)]
+#[derive(Clone, Debug)]
+pub struct IdsReport {
+    pub title: Option<String>,
+    pub specifications: Vec<IdsSpecificationResult>,
+}
+
+/// Property found on an element, with its values (several for lists and enumerations)
+struct IdsPropertyValue {
+    value_type: Option<String>,
+    values: Vec<Value>,
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF IdsValue
+// ----------------------------------------------------------------
+
+/// Rust implementation of struct
+impl IdsValue {
+    fn from_xml(node: Node) -> Result<Self, String> {
+        if let Some(x) = child(node, "simpleValue") {
+            return Ok(Self::Simple(
+                x.text().unwrap_or_default().trim().to_string(),
+            ));
+        }
+        let Some(restriction) = child(node, "restriction") else {
+            return Err(format!(
+                "{} requires a simple value or restriction",
+                tag(node)
+            ));
+        };
+        let mut result = IdsRestriction::default();
+        for x in restriction.children().filter(Node::is_element) {
+            let value = x.attribute("value").unwrap_or_default();
+            let number = || {
+                value
+                    .trim()
+                    .parse::<f64>()
+                    .map_err(|_| format!("{} must be a number, got '{}'", tag(x), value))
+            };
+            let count = || {
+                value
+                    .trim()
+                    .parse::<usize>()
+                    .map_err(|_| format!("{} must be a count, got '{}'", tag(x), value))
+            };
+            match tag(x) {
+                "enumeration" => result.enumeration.push(value.to_string()),
+                "pattern" => {
+                    let expr = Regex::new(&format!("^(?:{})$", value)).map_err(err_to_string)?;
+                    result.patterns.push(expr);
+                }
+                "minInclusive" => result.min_inclusive = Some(number()?),
+                "minExclusive" => result.min_exclusive = Some(number()?),
+                "maxInclusive" => result.max_inclusive = Some(number()?),
+                "maxExclusive" => result.max_exclusive = Some(number()?),
+                "length" => result.length = Some(count()?),
+                "minLength" => result.min_length = Some(count()?),
+                "maxLength" => result.max_length = Some(count()?),
+                other => {
+                    return Err(format!("unsupported restriction '{}'", other));
+                }
+            }
+        }
+        return Ok(Self::Restriction(Box::new(result)));
+    }
+
+    /// Whether a name (of a property set, property or entity) matches
+    pub fn matches_name(&self, name: &str) -> bool {
+        return self.matches(&Value::String(name.to_string()));
+    }
+
+    pub fn matches(&self, value: &Value) -> bool {
+        match self {
+            Self::Simple(expected) => simple_matches(expected, value),
+            Self::Restriction(x) => {
+                let text = match value {
+                    Value::String(x) => x.clone(),
+                    _ => value.to_string(),
+                };
+                let number = match value {
+                    Value::Number(x) => x.as_f64(),
+                    Value::String(x) => x.trim().parse::<f64>().ok(),
+                    _ => None,
+                };
+                let length = text.chars().count();
+                let bound = |limit: Option<f64>, check: fn(f64, f64) -> bool| match limit {
+                    None => true,
+                    Some(limit) => number.is_some_and(|x| check(x, limit)),
+                };
+                return (x.enumeration.is_empty()
+                    || x.enumeration.iter().any(|e| simple_matches(e, value)))
+                    && x.patterns.iter().all(|e| e.is_match(&text))
+                    && bound(x.min_inclusive, |x, y| x >= y)
+                    && bound(x.min_exclusive, |x, y| x > y)
+                    && bound(x.max_inclusive, |x, y| x <= y)
+                    && bound(x.max_exclusive, |x, y| x < y)
+                    && x.length.is_none_or(|n| length == n)
+                    && x.min_length.is_none_or(|n| length >= n)
+                    && x.max_length.is_none_or(|n| length <= n);
+            }
+        }
+    }
+}
+
+impl ToString for IdsValue {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Simple(x) => x.clone(),
+            Self::Restriction(x) => {
+                let mut parts: Vec<String> = vec![];
+                if !x.enumeration.is_empty() {
+                    parts.push(format!("one of [{}]", x.enumeration.join(", ")));
+                }
+                for e in x.patterns.iter() {
+                    // NOTE: strips the anchors added when compiling the pattern
+                    let pattern = e.as_str().trim_start_matches("^(?:").trim_end_matches(")$");
+                    parts.push(format!("pattern /{}/", pattern));
+                }
+                let bounds = [
+                    (">=", x.min_inclusive),
+                    (">", x.min_exclusive),
+                    ("<=", x.max_inclusive),
+                    ("<", x.max_exclusive),
+                ];
+                for (op, limit) in bounds {
+                    if let Some(limit) = limit {
+                        parts.push(format!("{} {}", op, limit));
+                    }
+                }
+                let lengths = [
+                    ("length", x.length),
+                    ("min-length", x.min_length),
+                    ("max-length", x.max_length),
+                ];
+                for (name, n) in lengths {
+                    if let Some(n) = n {
+                        parts.push(format!("{} {}", name, n));
+                    }
+                }
+                return parts.join(", ");
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF IdsFacet
+// ----------------------------------------------------------------
+
+/// Rust implementation of struct
+impl IdsFacet {
+    fn from_xml(node: Node) -> Result<Self, String> {
+        match tag(node) {
+            "entity" => {
+                let name = child(node, "name").ok_or("entity facet requires a name")?;
+                let predefined_type = child(node, "predefinedType")
+                    .map(IdsValue::from_xml)
+                    .transpose()?;
+                let result = Self::Entity {
+                    name: IdsValue::from_xml(name)?,
+                    predefined_type,
+                };
+                return Ok(result);
+            }
+            "property" => {
+                let property_set =
+                    child(node, "propertySet").ok_or("property facet requires a property set")?;
+                let base_name =
+                    child(node, "baseName").ok_or("property facet requires a base name")?;
+                let value = child(node, "value").map(IdsValue::from_xml).transpose()?;
+                let cardinality = match node.attribute("cardinality").unwrap_or("required") {
+                    "required" => IdsCardinality::Required,
+                    "optional" => IdsCardinality::Optional,
+                    "prohibited" => IdsCardinality::Prohibited,
+                    other => {
+                        return Err(format!("invalid cardinality '{}'", other));
+                    }
+                };
+                let result = Self::Property {
+                    property_set: IdsValue::from_xml(property_set)?,
+                    base_name: IdsValue::from_xml(base_name)?,
+                    value,
+                    data_type: node.attribute("dataType").map(|x| x.to_string()),
+                    cardinality,
+                };
+                return Ok(result);
+            }
+            other => {
+                let result = Self::Unsupported {
+                    name: other.to_string(),
+                };
+                return Ok(result);
+            }
+        }
+    }
+
+    /// Checks an element, returning `None` if the facet cannot be evaluated on it
+    pub fn check(&self, element: &IdsElement) -> Option<Result<(), String>> {
+        match self {
+            Self::Entity {
+                name,
+                predefined_type,
+            } => {
+                let entity_class = element.entity_class.as_ref()?;
+                if !name.matches_name(&entity_class.to_uppercase()) {
+                    return Some(Err(format!(
+                        "entity {} is not {}",
+                        entity_class,
+                        name.to_string()
+                    )));
+                }
+                let Some(expected) = predefined_type else {
+                    return Some(Ok(()));
+                };
+                let found = element.predefined_type.as_ref()?;
+                let result = match expected.matches_name(&found.to_uppercase()) {
+                    true => Ok(()),
+                    false => Err(format!(
+                        "predefined type {} is not {}",
+                        found,
+                        expected.to_string()
+                    )),
+                };
+                return Some(result);
+            }
+            Self::Property {
+                property_set,
+                base_name,
+                value,
+                data_type,
+                cardinality,
+            } => {
+                let found = find_properties(element.psets, property_set, base_name);
+                let satisfies = |x: &IdsPropertyValue| -> Result<(), String> {
+                    let mismatched_type = data_type
+                        .as_ref()
+                        .zip(x.value_type.as_ref())
+                        .filter(|(expected, found)| !expected.eq_ignore_ascii_case(found));
+                    if let Some((expected, found)) = mismatched_type {
+                        return Err(format!("data type {} is not {}", found, expected));
+                    }
+                    if let Some(expected) = value {
+                        if let Some(found) = x.values.iter().find(|x| !expected.matches(x)) {
+                            return Err(format!(
+                                "value {} does not match {}",
+                                found,
+                                expected.to_string()
+                            ));
+                        }
+                    }
+                    return Ok(());
+                };
+                let result = match cardinality {
+                    IdsCardinality::Required if found.is_empty() => {
+                        Err(format!("missing property {}", self.to_string()))
+                    }
+                    IdsCardinality::Required | IdsCardinality::Optional => {
+                        found.iter().try_for_each(satisfies)
+                    }
+                    IdsCardinality::Prohibited => {
+                        match found.iter().any(|x| satisfies(x).is_ok()) {
+                            true => Err(format!("prohibited property {}", self.to_string())),
+                            false => Ok(()),
+                        }
+                    }
+                };
+                return Some(result);
+            }
+            Self::Unsupported { .. } => None,
+        }
+    }
+}
+
+impl ToString for IdsFacet {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Entity {
+                name,
+                predefined_type: None,
+            } => format!("entity {}", name.to_string()),
+            Self::Entity {
+                name,
+                predefined_type: Some(predefined_type),
+            } => format!(
+                "entity {}/{}",
+                name.to_string(),
+                predefined_type.to_string()
+            ),
+            Self::Property {
+                property_set,
+                base_name,
+                value,
+                ..
+            } => {
+                let mut result = format!("{}.{}", property_set.to_string(), base_name.to_string());
+                if let Some(value) = value {
+                    result = format!("{} = {}", result, value.to_string());
+                }
+                return result;
+            }
+            Self::Unsupported { name } => format!("{} facet", name),
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF IdsSpecification
+// ----------------------------------------------------------------
+
+/// Rust implementation of struct
+impl IdsSpecification {
+    fn from_xml(node: Node) -> Result<Self, String> {
+        let name = node.attribute("name").unwrap_or_default().to_string();
+        let applicability = child(node, "applicability")
+            .ok_or_else(|| format!("specification '{}' requires an applicability", name))?;
+        // NOTE: occurrences are given on the applicability since IDS 1.0, before on the specification
+        let occurs = |key: &str| applicability.attribute(key).or(node.attribute(key));
+        let min_occurs = match occurs("minOccurs") {
+            None => 0,
+            Some(x) => x.parse::<usize>().map_err(err_to_string)?,
+        };
+        let max_occurs = match occurs("maxOccurs") {
+            None | Some("unbounded") => None,
+            Some(x) => Some(x.parse::<usize>().map_err(err_to_string)?),
+        };
+        let facets = |node: Option<Node>| -> Result<Vec<IdsFacet>, String> {
+            return node
+                .into_iter()
+                .flat_map(|x| x.children().filter(Node::is_element))
+                .map(IdsFacet::from_xml)
+                .collect();
+        };
+        let result = Self {
+            description: node.attribute("description").map(|x| x.to_string()),
+            ifc_versions: node
+                .attribute("ifcVersion")
+                .unwrap_or_default()
+                .split_whitespace()
+                .map(|x| x.to_string())
+                .collect(),
+            min_occurs,
+            max_occurs,
+            applicability: facets(Some(applicability))?,
+            requirements: facets(child(node, "requirements"))?,
+            name,
+        };
+        return Ok(result);
+    }
+
+    pub fn rust_check(&self, elements: &[IdsElement]) -> IdsSpecificationResult {
+        // NOTE: elements are applicable if all facets hold, and excluded if any facet fails,
+        // otherwise (facets which cannot be evaluated) they are left undecided
+        let mut applicable: Vec<&IdsElement> = vec![];
+        let mut undecided: Vec<&IdsElement> = vec![];
+        for element in elements.iter() {
+            let outcomes: Vec<_> = self
+                .applicability
+                .iter()
+                .map(|x| x.check(element))
+                .collect();
+            if outcomes.iter().any(|x| matches!(x, Some(Err(_)))) {
+                continue;
+            } else if outcomes.iter().any(|x| x.is_none()) {
+                undecided.push(element);
+            } else {
+                applicable.push(element);
+            }
+        }
+
+        let mut messages: Vec<String> = vec![];
+        // undecided elements may still be applicable, so too few elements is not a violation
+        if undecided.is_empty() && applicable.len() < self.min_occurs {
+            messages.push(format!(
+                "expected at least {} applicable elements, found {}",
+                self.min_occurs,
+                applicable.len()
+            ));
+        }
+        if self.max_occurs.is_some_and(|n| applicable.len() > n) {
+            messages.push(format!(
+                "expected at most {} applicable elements, found {}",
+                self.max_occurs.unwrap_or_default(),
+                applicable.len()
+            ));
+        }
+
+        let requirements: Vec<IdsRequirementResult> = self
+            .requirements
+            .iter()
+            .map(|facet| {
+                let outcomes: Vec<_> = applicable
+                    .iter()
+                    .map(|element| (element, facet.check(element)))
+                    .collect();
+                let failures: Vec<(String, String)> = outcomes
+                    .iter()
+                    .filter_map(|(element, x)| match x {
+                        Some(Err(reason)) => Some((element.name.clone(), reason.clone())),
+                        _ => None,
+                    })
+                    .collect();
+                let evaluated = outcomes.iter().any(|(_, x)| x.is_some());
+                let status = match (failures.is_empty(), evaluated) {
+                    (false, _) => IdsStatus::Fail,
+                    (true, true) => IdsStatus::Pass,
+                    (true, false) => match facet {
+                        IdsFacet::Unsupported { .. } => IdsStatus::Skipped,
+                        _ if applicable.is_empty() => IdsStatus::Pass,
+                        _ => IdsStatus::Skipped,
+                    },
+                };
+                return IdsRequirementResult {
+                    description: facet.to_string(),
+                    status,
+                    failures,
+                };
+            })
+            .collect();
+
+        let failed =
+            !messages.is_empty() || requirements.iter().any(|x| x.status == IdsStatus::Fail);
+        let skipped = !undecided.is_empty()
+            || (!requirements.is_empty()
+                && requirements.iter().all(|x| x.status == IdsStatus::Skipped));
+        if !undecided.is_empty() {
+            let names: Vec<&str> = undecided.iter().map(|x| x.name.as_str()).collect();
+            messages.push(format!(
+                "applicability cannot be evaluated on {}",
+                names.join(", ")
+            ));
+        }
+        let status = match (failed, skipped) {
+            (true, _) => IdsStatus::Fail,
+            (false, true) => IdsStatus::Skipped,
+            (false, false) => IdsStatus::Pass,
+        };
+        return IdsSpecificationResult {
+            name: self.name.clone(),
+            status,
+            applicable: applicable.iter().map(|x| x.name.clone()).collect(),
+            messages,
+            requirements,
+        };
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF IdsDocument
+// ----------------------------------------------------------------
+
+#[pymethods]
+impl IdsDocument {
+    #[staticmethod]
+    fn __class_name__() -> String {
+        "IdsDocument".to_string()
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "from_xml", signature = (text, /))]
+    fn py_from_xml(text: &str) -> PyResult<Self> {
+        return Self::from_xml(text).map_err(err_to_py_string);
+    }
+
+    #[getter]
+    fn title(&self) -> Option<String> {
+        self.title.clone()
+    }
+
+    /// Names of the specifications
+    #[getter]
+    fn specifications(&self) -> Vec<String> {
+        self.specifications.iter().map(|x| x.name.clone()).collect()
+    }
+
+    /// Checks a Psets value or a mapping of names onto Psets values,
+    /// with the entity class (and predefined type) given for all elements or by name
+    #[pyo3(name = "check", signature = (psets, /, *, entity_class=None))]
+    fn py_check(
+        &self,
+        psets: &Bound<'_, PyAny>,
+        entity_class: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<IdsReport> {
+        let entity_class = entity_class.filter(|x| !x.is_none());
+        let class_of = |name: &str| -> PyResult<Option<String>> {
+            let Some(x) = entity_class else {
+                return Ok(None);
+            };
+            if let Ok(x) = x.extract::<String>() {
+                return Ok(Some(x));
+            }
+            let x = x.extract::<IndexMap<String, String>>()?;
+            return Ok(x.get(name).cloned());
+        };
+        let elements: IndexMap<String, Psets> = match psets.extract::<Psets>() {
+            Ok(x) => IndexMap::from([("psets".to_string(), x)]),
+            Err(_) => psets.extract::<IndexMap<String, Psets>>()?,
+        };
+        let mut items: Vec<IdsElement> = vec![];
+        for (name, psets) in elements.iter() {
+            // NOTE: the predefined type follows the class, as in templates (`IfcWall/SHEAR`)
+            let class_ = class_of(name)?;
+            let (entity_class, predefined_type) = match class_.as_deref().map(|x| x.split_once('/'))
+            {
+                Some(Some((x, y))) => (Some(x.to_string()), Some(y.to_string())),
+                _ => (class_, None),
+            };
+            items.push(IdsElement {
+                name: name.clone(),
+                entity_class,
+                predefined_type,
+                psets,
+            });
+        }
+        return Ok(self.rust_check(&items));
+    }
+
+    fn __len__(&self) -> usize {
+        self.specifications.len()
+    }
+
+    pub fn __str__(&self) -> PyResult<String> {
+        Ok(self.to_string())
+    }
+}
+
+/// Rust implementation of struct
+impl IdsDocument {
+    pub fn from_xml(text: &str) -> Result<Self, String> {
+        let document = Document::parse(text).map_err(err_to_string)?;
+        let root = document.root_element();
+        if tag(root) != "ids" {
+            return Err(format!("expected an ids document, found '{}'", tag(root)));
+        }
+        let title = child(root, "info")
+            .and_then(|x| child(x, "title"))
+            .and_then(|x| x.text())
+            .map(|x| x.trim().to_string());
+        let specifications: Result<Vec<IdsSpecification>, String> = child(root, "specifications")
+            .into_iter()
+            .flat_map(|x| x.children().filter(|x| tag(*x) == "specification"))
+            .map(IdsSpecification::from_xml)
+            .collect();
+        let result = Self {
+            title,
+            specifications: specifications?,
+        };
+        return Ok(result);
+    }
+
+    pub fn rust_check(&self, elements: &[IdsElement]) -> IdsReport {
+        return IdsReport {
+            title: self.title.clone(),
+            specifications: self
+                .specifications
+                .iter()
+                .map(|x| x.rust_check(elements))
+                .collect(),
+        };
+    }
+}
+
+impl ToString for IdsDocument {
+    fn to_string(&self) -> String {
+        let title = self.title.clone().unwrap_or_default();
+        return format!(
+            "{}({:?}, {} specifications)",
+            Self::__class_name__(),
+            title,
+            self.specifications.len()
+        );
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF IdsStatus + results
+// ----------------------------------------------------------------
+
+/// Rust implementation of struct
+impl IdsStatus {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Pass => "PASS",
+            Self::Fail => "FAIL",
+            Self::Skipped => "SKIPPED",
+        }
+    }
+}
+
+#[pymethods]
+impl IdsReport {
+    #[getter]
+    fn passed(&self) -> bool {
+        self.specifications
+            .iter()
+            .all(|x| x.status != IdsStatus::Fail)
+    }
+
+    pub fn __str__(&self) -> PyResult<String> {
+        Ok(self.to_string())
+    }
+}
+
+impl ToString for IdsReport {
+    fn to_string(&self) -> String {
+        let mut lines: Vec<String> = vec![];
+        for x in self.specifications.iter() {
+            lines.push(format!(
+                "[{}] {} ({} applicable)",
+                x.status.name(),
+                x.name,
+                x.applicable.len()
+            ));
+            lines.extend(x.messages.iter().map(|message| format!("    {}", message)));
+            for requirement in x.requirements.iter() {
+                lines.push(format!(
+                    "    [{}] {}",
+                    requirement.status.name(),
+                    requirement.description
+                ));
+                for (name, reason) in requirement.failures.iter() {
+                    lines.push(format!("        {}: {}", name, reason));
+                }
+            }
+        }
+        return lines.join("\n");
+    }
+}
+
+// ----------------------------------------------------------------
+// AUXILIARY METHODS
+// ----------------------------------------------------------------
+
+/// Local name of an element, ignoring its namespace (`xs:`, IDS)
+fn tag<'a>(node: Node<'a, '_>) -> &'a str {
+    node.tag_name().name()
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|x| x.is_element() && tag(*x) == name)
+}
+
+/// Compares a simple value with a value of a property, by its json type
+fn simple_matches(expected: &str, value: &Value) -> bool {
+    match value {
+        Value::String(x) => x == expected,
+        Value::Number(x) => match (x.as_f64(), expected.trim().parse::<f64>()) {
+            (Some(x), Ok(y)) => (x - y).abs() <= 1e-6 * x.abs().max(y.abs()).max(1.0),
+            _ => false,
+        },
+        Value::Bool(x) => expected.eq_ignore_ascii_case(&x.to_string()),
+        _ => false,
+    }
+}
+
+/// Non-empty properties of an element matching the property set and base name
+fn find_properties(
+    psets: &Psets,
+    property_set: &IdsValue,
+    base_name: &IdsValue,
+) -> Vec<IdsPropertyValue> {
+    let mut result: Vec<IdsPropertyValue> = vec![];
+    for (name, pset) in psets.children().into_iter().flatten() {
+        let PsetNestedValue::Psets(pset) = pset else {
+            continue;
+        };
+        if !property_set.matches_name(name) {
+            continue;
+        }
+        let properties = pset.children().into_iter().flatten();
+        // NOTE: verbose psets store their id alongside the properties
+        collect_properties(
+            properties.filter(|(key, _)| *key != "id"),
+            base_name,
+            &mut result,
+        );
+    }
+    return result;
+}
+
+/// Properties matching a base name, looking into complex properties
+fn collect_properties<'a>(
+    properties: impl Iterator<Item = (&'a String, &'a PsetNestedValue)>,
+    base_name: &IdsValue,
+    result: &mut Vec<IdsPropertyValue>,
+) {
+    for (key, x) in properties {
+        if let PsetNestedValue::Psets(Psets::Complex(x)) = x {
+            collect_properties(x.properties.iter(), base_name, result);
+            continue;
+        }
+        if !base_name.matches_name(key) {
+            continue;
+        }
+        let (value_type, values) = match x {
+            PsetNestedValue::Psets(Psets::Pset(x)) => {
+                let ValueWrap(value) = &x.value;
+                (x.value_type.clone(), vec![value.clone()])
+            }
+            PsetNestedValue::Psets(Psets::Quantity(x)) => {
+                let ValueWrap(value) = &x.value;
+                let value_type = x.dimension().ok().map(|x| x.value_type().to_string());
+                (value_type, vec![value.clone()])
+            }
+            PsetNestedValue::Psets(Psets::Property(PsetProperty::Enumerated(x))) => {
+                let values = x.values.iter().map(|ValueWrap(x)| x.clone()).collect();
+                (x.value_type.clone(), values)
+            }
+            PsetNestedValue::Psets(Psets::Property(PsetProperty::List(x))) => {
+                let values = x.values.iter().map(|ValueWrap(x)| x.clone()).collect();
+                (x.value_type.clone(), values)
+            }
+            // NOTE: as in IDS, bounded values are checked by their bounds and set point,
+            // tables by their defined values
+            PsetNestedValue::Psets(Psets::Property(PsetProperty::Bounded(x))) => {
+                let bounds = [&x.lower_bound, &x.upper_bound, &x.set_point];
+                let values = bounds
+                    .into_iter()
+                    .flatten()
+                    .map(|ValueWrap(x)| x.clone())
+                    .collect();
+                let value_type = [&x.lower_bound_type, &x.upper_bound_type, &x.set_point_type]
+                    .into_iter()
+                    .find_map(|x| x.clone());
+                (value_type, values)
+            }
+            PsetNestedValue::Psets(Psets::Property(PsetProperty::Table(x))) => {
+                let values = x
+                    .defined_values
+                    .iter()
+                    .map(|ValueWrap(x)| x.clone())
+                    .collect();
+                (x.defined_value_type.clone(), values)
+            }
+            PsetNestedValue::Psets(Psets::Property(PsetProperty::Reference(x))) => {
+                let values = x.referenced_id.map(Value::from).into_iter().collect();
+                (None, values)
+            }
+            PsetNestedValue::Psets(_) => {
+                continue;
+            }
+            PsetNestedValue::Any(ValueWrap(value)) => (None, vec![value.clone()]),
+        };
+        // NOTE: empty values count as missing properties
+        let values: Vec<Value> = values.into_iter().filter(|x| !x.is_null()).collect();
+        if !values.is_empty() {
+            result.push(IdsPropertyValue { value_type, values });
+        }
+    }
+}
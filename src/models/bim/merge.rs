@@ -0,0 +1,147 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use serde_json::Value;
+use std::result::Result;
+
+use super::psets::PsetNestedValue;
+use super::psets::Psets;
+use crate::models::json::model::ValueWrap;
+
+// ----------------------------------------------------------------
+// STRUCTS
+// ----------------------------------------------------------------
+
+/// Resolves a conflict at a path, given the left and right entries
+pub type MergeCallback<'a> = dyn FnMut(&[String], &PsetNestedValue, &PsetNestedValue) -> Result<PsetNestedValue, String>
+    + 'a;
+
+/// Strategy to resolve entries that differ on both sides of a merge
+pub enum MergeStrategy<'a> {
+    PreferLeft,
+    PreferRight,
+    RaiseOnConflict,
+    Callback(&'a mut MergeCallback<'a>),
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF MergeStrategy
+// ----------------------------------------------------------------
+
+impl MergeStrategy<'_> {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "prefer-left" => Ok(Self::PreferLeft),
+            "prefer-right" => Ok(Self::PreferRight),
+            "raise" | "raise-on-conflict" => Ok(Self::RaiseOnConflict),
+            _ => Err(format!("unknown merge strategy '{}'", name)),
+        }
+    }
+
+    fn resolve(
+        &mut self,
+        path: &[String],
+        left: &PsetNestedValue,
+        right: &PsetNestedValue,
+    ) -> Result<PsetNestedValue, String> {
+        match self {
+            Self::PreferLeft => Ok(left.clone()),
+            Self::PreferRight => Ok(right.clone()),
+            Self::RaiseOnConflict => Err(format!("conflict at '{}'", path.join(":"))),
+            Self::Callback(callback) => callback(path, left, right),
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+// METHODS
+// ----------------------------------------------------------------
+
+/// Merges two Psets, recursing through nested entries present on both sides;
+/// with `ignore_ids`, entries differing only by their ids are not conflicts (the left ids are kept)
+pub fn merge_psets(
+    left: &Psets,
+    right: &Psets,
+    ignore_ids: bool,
+    strategy: &mut MergeStrategy,
+) -> Result<Psets, String> {
+    return merge_psets_at(left, right, &[], ignore_ids, strategy);
+}
+
+fn merge_psets_at(
+    left: &Psets,
+    right: &Psets,
+    path: &[String],
+    ignore_ids: bool,
+    strategy: &mut MergeStrategy,
+) -> Result<Psets, String> {
+    if is_mergeable(left, right) {
+        let (Some(left_elements), Some(right_elements)) = (left.children(), right.children())
+        else {
+            return Err("mergeable entries must have children".to_string());
+        };
+        let mut elements = left_elements.clone();
+        for (key, y) in right_elements.iter() {
+            let subpath = [path, std::slice::from_ref(key)].concat();
+            let value = match left_elements.get(key) {
+                Some(x) => merge_values_at(x, y, &subpath, ignore_ids, strategy)?,
+                None => y.clone(),
+            };
+            elements.insert(key.clone(), value);
+        }
+        let mut result = left.clone();
+        if let Some(children) = result.children_mut() {
+            *children = elements;
+        }
+        return Ok(result);
+    }
+    let x = PsetNestedValue::Psets(left.clone());
+    let y = PsetNestedValue::Psets(right.clone());
+    match merge_values_at(&x, &y, path, ignore_ids, strategy)? {
+        PsetNestedValue::Psets(result) => {
+            return Ok(result);
+        }
+        PsetNestedValue::Any(_) => {
+            return Err("merge at root must result in Psets".to_string());
+        }
+    }
+}
+
+fn merge_values_at(
+    left: &PsetNestedValue,
+    right: &PsetNestedValue,
+    path: &[String],
+    ignore_ids: bool,
+    strategy: &mut MergeStrategy,
+) -> Result<PsetNestedValue, String> {
+    if let (PsetNestedValue::Psets(x), PsetNestedValue::Psets(y)) = (left, right) {
+        if is_mergeable(x, y) {
+            let result = merge_psets_at(x, y, path, ignore_ids, strategy)?;
+            return Ok(PsetNestedValue::Psets(result));
+        }
+    }
+    if left == right {
+        return Ok(left.clone());
+    }
+    if ignore_ids && (left.eq_ignore_ids(right) || is_id_entry(path, left, right)) {
+        return Ok(left.clone());
+    }
+    return strategy.resolve(path, left, right);
+}
+
+/// Nested entries, or complex properties agreeing on everything but their children
+fn is_mergeable(left: &Psets, right: &Psets) -> bool {
+    match (left, right) {
+        (Psets::Nested { .. }, Psets::Nested { .. }) => true,
+        (Psets::Complex(x), Psets::Complex(y)) => x.header() == y.header(),
+        _ => false,
+    }
+}
+
+/// Verbose id entries of nested sets on both sides
+fn is_id_entry(path: &[String], left: &PsetNestedValue, right: &PsetNestedValue) -> bool {
+    let is_id =
+        |x: &PsetNestedValue| matches!(x, PsetNestedValue::Any(ValueWrap(Value::Number(_))));
+    return path.last().is_some_and(|key| key == "id") && is_id(left) && is_id(right);
+}
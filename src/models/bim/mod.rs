@@ -1 +1,28 @@
+pub mod collection;
+pub mod diff;
+pub mod ids;
+pub mod merge;
+pub mod properties;
+pub mod psd;
 pub mod psets;
+pub mod select;
+pub mod templates;
+pub mod units;
+pub mod values;
+
+#[cfg(test)]
+pub mod tests_collection;
+#[cfg(test)]
+pub mod tests_ids;
+#[cfg(test)]
+pub mod tests_properties;
+#[cfg(test)]
+pub mod tests_psd;
+#[cfg(test)]
+pub mod tests_psets;
+#[cfg(test)]
+pub mod tests_templates;
+#[cfg(test)]
+pub mod tests_units;
+#[cfg(test)]
+pub mod tests_values;
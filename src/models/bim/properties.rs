@@ -0,0 +1,1542 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use indexmap::IndexMap;
+use pyo3::Bound;
+use pyo3::FromPyObject; // NOTE: needed for ValueWrap::extract_bound
+use pyo3::PyRef;
+use pyo3::PyResult;
+use pyo3::Python;
+use pyo3::exceptions::PyKeyError;
+use pyo3::prelude::pyclass;
+use pyo3::prelude::pymethods;
+use pyo3::types::PyAny;
+use pyo3::types::PyAnyMethods;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Number;
+use serde_json::Value;
+use serde_json::json;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::result::Result;
+
+use super::psets::PsetNestedValue;
+use super::psets::Psets;
+use super::values::IfcValue;
+use crate::_core::errors::err_to_py_index_error;
+use crate::_core::errors::err_to_py_key_error;
+use crate::_core::errors::err_to_py_string;
+use crate::_core::errors::err_to_string;
+use crate::_core::hashing::hash_of;
+use crate::models::json::base::JsonConversion;
+use crate::models::json::model::ValueWrap;
+
+// ----------------------------------------------------------------
+// INTERFACES
+// ----------------------------------------------------------------
+
+/// Turns references to other entities into objects, e.g. by looking them up in a collection
+pub trait ReferenceResolver<T> {
+    /// Returns `None` if the referenced entity is unknown
+    fn resolve(&mut self, reference: &PsetReferenceValue) -> Result<Option<T>, String>;
+}
+
+// ----------------------------------------------------------------
+// STRUCTS
+// ----------------------------------------------------------------
+
+/// Allowed values of an enumerated property (IfcPropertyEnumeration)
+#[pyclass(get_all, set_all)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PsetEnumeration {
+    #[serde(alias = "id", default)]
+    pub id_: Option<i64>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(deserialize_with = "deserialize_values")]
+    pub values: Vec<ValueWrap>,
+}
+
+/// Property with values selected from an enumeration (IfcPropertyEnumeratedValue)
+#[pyclass(get_all, set_all)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PsetEnumeratedValue {
+    #[serde(alias = "id")]
+    pub id_: i64,
+    #[serde(alias = "class")]
+    pub class_: String,
+    /// the selected values (a single value is read as a list of one)
+    #[serde(alias = "value", deserialize_with = "deserialize_values")]
+    pub values: Vec<ValueWrap>,
+    #[serde(alias = "value-type", default)]
+    pub value_type: Option<String>,
+    #[serde(alias = "enumeration-reference", default)]
+    pub enumeration: Option<PsetEnumeration>,
+}
+
+/// Property with a range and/or set point (IfcPropertyBoundedValue), each with its own type
+#[pyclass(get_all, set_all)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PsetBoundedValue {
+    #[serde(alias = "id")]
+    pub id_: i64,
+    #[serde(alias = "class")]
+    pub class_: String,
+    #[serde(alias = "upper-bound", alias = "UpperBoundValue", default)]
+    pub upper_bound: Option<ValueWrap>,
+    #[serde(alias = "upper-bound-type", default)]
+    pub upper_bound_type: Option<String>,
+    #[serde(alias = "lower-bound", alias = "LowerBoundValue", default)]
+    pub lower_bound: Option<ValueWrap>,
+    #[serde(alias = "lower-bound-type", default)]
+    pub lower_bound_type: Option<String>,
+    #[serde(alias = "set-point", alias = "SetPointValue", default)]
+    pub set_point: Option<ValueWrap>,
+    #[serde(alias = "set-point-type", default)]
+    pub set_point_type: Option<String>,
+}
+
+/// Interpolation between the rows of a table property (IfcCurveInterpolationEnum)
+#[pyclass(eq, eq_int)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CurveInterpolation {
+    #[pyo3(name = "LINEAR")]
+    Linear,
+    #[pyo3(name = "LOG_LINEAR")]
+    LogLinear,
+    #[pyo3(name = "LOG_LOG")]
+    LogLog,
+    #[serde(rename = "NOTDEFINED")]
+    #[pyo3(name = "NOTDEFINED")]
+    NotDefined,
+}
+
+/// Property mapping defining values onto defined values (IfcPropertyTableValue)
+#[pyclass(get_all, set_all)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PsetTableValue {
+    #[serde(alias = "id")]
+    pub id_: i64,
+    #[serde(alias = "class")]
+    pub class_: String,
+    #[serde(alias = "defining-values", alias = "DefiningValues", default)]
+    pub defining_values: Vec<ValueWrap>,
+    #[serde(alias = "defined-values", alias = "DefinedValues", default)]
+    pub defined_values: Vec<ValueWrap>,
+    #[serde(alias = "defining-value-type", default)]
+    pub defining_value_type: Option<String>,
+    #[serde(alias = "defined-value-type", default)]
+    pub defined_value_type: Option<String>,
+    #[serde(alias = "expression", alias = "Expression", default)]
+    pub expression: Option<String>,
+    #[serde(alias = "curve-interpolation", alias = "CurveInterpolation", default)]
+    pub curve_interpolation: Option<CurveInterpolation>,
+}
+
+/// Property holding an ordered list of values of one type (IfcPropertyListValue)
+#[pyclass(get_all, set_all)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PsetListValue {
+    #[serde(alias = "id")]
+    pub id_: i64,
+    #[serde(alias = "class")]
+    pub class_: String,
+    #[serde(
+        alias = "value",
+        alias = "ListValues",
+        deserialize_with = "deserialize_values"
+    )]
+    pub values: Vec<ValueWrap>,
+    #[serde(alias = "value-type", default)]
+    pub value_type: Option<String>,
+}
+
+/// Property referring to another entity, e.g. a material or time series (IfcPropertyReferenceValue)
+#[pyclass(get_all, set_all)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PsetReferenceValue {
+    pub id_: i64,
+    pub class_: String,
+    pub usage_name: Option<String>,
+    pub referenced_id: Option<i64>,
+    pub referenced_class: Option<String>,
+}
+
+/// Group of child properties (IfcComplexProperty), e.g. the layers of a construction
+#[pyclass]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PsetComplexProperty {
+    #[pyo3(get, set)]
+    pub id_: i64,
+    #[pyo3(get, set)]
+    pub class_: String,
+    #[pyo3(get, set)]
+    pub usage_name: Option<String>,
+    pub properties: IndexMap<String, PsetNestedValue>,
+}
+
+/// Property of a type with a dedicated representation, chosen by its class
+#[pyclass(get_all, set_all)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PsetProperty {
+    Enumerated(PsetEnumeratedValue),
+    Bounded(PsetBoundedValue),
+    Table(PsetTableValue),
+    List(PsetListValue),
+    Reference(PsetReferenceValue),
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF PsetEnumeration
+// ----------------------------------------------------------------
+
+#[pymethods]
+impl PsetEnumeration {
+    #[new]
+    #[pyo3(signature = (/, *, values, name=None, id_=None))]
+    pub fn new(values: Vec<ValueWrap>, name: Option<String>, id_: Option<i64>) -> Self {
+        return Self { id_, name, values };
+    }
+}
+
+impl JsonConversion<Value> for PsetEnumeration {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        let result = Self::deserialize(value.clone()).map_err(err_to_string)?;
+        return Ok(result);
+    }
+
+    fn to_json(&self) -> Result<Value, String> {
+        let result = json!({
+            "id": self.id_,
+            "name": self.name,
+            "values": self.values,
+        });
+        return Ok(result);
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF PsetEnumeratedValue
+// ----------------------------------------------------------------
+
+#[pymethods]
+impl PsetEnumeratedValue {
+    #[new]
+    #[pyo3(signature = (/, *, id_, values, value_type=None, enumeration=None, class_="IfcPropertyEnumeratedValue".to_string()))]
+    pub fn new(
+        id_: i64,
+        values: Vec<ValueWrap>,
+        value_type: Option<String>,
+        enumeration: Option<PsetEnumeration>,
+        class_: String,
+    ) -> PyResult<Self> {
+        let result = Self {
+            id_,
+            class_,
+            values,
+            value_type,
+            enumeration,
+        };
+        result.rust_validate().map_err(err_to_py_string)?;
+        return Ok(result);
+    }
+
+    #[staticmethod]
+    fn __class_name__() -> String {
+        "PsetEnumeratedValue".to_string()
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (value, /))]
+    pub fn model_validate(value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let raw = ValueWrap::extract_bound(value)?;
+        let value = raw.to_json().map_err(err_to_py_string)?;
+        let result = Self::from_json(&value).map_err(err_to_py_string)?;
+        return Ok(result);
+    }
+
+    /// Values allowed by the enumeration, if one is referenced
+    #[getter]
+    fn allowed_values(&self) -> Option<Vec<ValueWrap>> {
+        self.enumeration.as_ref().map(|x| x.values.clone())
+    }
+
+    #[getter]
+    fn typed_values(&self) -> PyResult<Option<Vec<IfcValue>>> {
+        return self.rust_typed_values().map_err(err_to_py_string);
+    }
+
+    /// Checks that all selected values are allowed and match the value type
+    fn validate(&self) -> PyResult<()> {
+        return self.rust_validate().map_err(err_to_py_string);
+    }
+
+    fn __eq__(&self, other: PyRef<'_, Self>) -> bool {
+        self == &*other
+    }
+
+    pub fn __str__(&self) -> PyResult<String> {
+        Ok(self.to_string())
+    }
+}
+
+/// Rust implementation of struct
+impl PsetEnumeratedValue {
+    /// Selected values typed by the declared value type,
+    /// or `None` if the type is not declared or not modelled
+    pub fn rust_typed_values(&self) -> Result<Option<Vec<IfcValue>>, String> {
+        let Some(value_type) = &self.value_type else {
+            return Ok(None);
+        };
+        let mut result = vec![];
+        for ValueWrap(value) in self.values.iter() {
+            match IfcValue::from_json(value_type, value)? {
+                Some(x) => result.push(x),
+                None => return Ok(None),
+            }
+        }
+        return Ok(Some(result));
+    }
+
+    pub fn rust_validate(&self) -> Result<(), String> {
+        self.rust_typed_values()?;
+        if let Some(enumeration) = &self.enumeration {
+            for ValueWrap(value) in self.values.iter() {
+                if !enumeration.values.iter().any(|ValueWrap(x)| x == value) {
+                    let name = enumeration.name.as_deref().unwrap_or("enumeration");
+                    return Err(format!("value {} is not allowed by {}", value, name));
+                }
+            }
+        }
+        return Ok(());
+    }
+}
+
+impl ToString for PsetEnumeratedValue {
+    fn to_string(&self) -> String {
+        match self
+            .to_json()
+            .and_then(|x| serde_json::to_string(&x).map_err(err_to_string))
+        {
+            Ok(text) => {
+                let name = Self::__class_name__();
+                return format!("{}({})", name, text);
+            }
+            Err(err) => {
+                panic!("{:?}", err);
+            }
+        }
+    }
+}
+
+impl JsonConversion<Value> for PsetEnumeratedValue {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        let result = Self::deserialize(value.clone()).map_err(err_to_string)?;
+        result.rust_validate()?;
+        return Ok(result);
+    }
+
+    fn to_json(&self) -> Result<Value, String> {
+        let enumeration = match &self.enumeration {
+            Some(x) => x.to_json()?,
+            None => Value::Null,
+        };
+        let result = json!({
+            "id": self.id_,
+            "class": self.class_,
+            "value": self.values,
+            "value-type": self.value_type,
+            "enumeration": enumeration,
+        });
+        return Ok(result);
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF PsetBoundedValue
+// ----------------------------------------------------------------
+
+#[pymethods]
+impl PsetBoundedValue {
+    #[new]
+    #[pyo3(signature = (/, *, id_, upper_bound=None, lower_bound=None, set_point=None, upper_bound_type=None, lower_bound_type=None, set_point_type=None, class_="IfcPropertyBoundedValue".to_string()))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id_: i64,
+        upper_bound: Option<ValueWrap>,
+        lower_bound: Option<ValueWrap>,
+        set_point: Option<ValueWrap>,
+        upper_bound_type: Option<String>,
+        lower_bound_type: Option<String>,
+        set_point_type: Option<String>,
+        class_: String,
+    ) -> PyResult<Self> {
+        let result = Self {
+            id_,
+            class_,
+            upper_bound,
+            upper_bound_type,
+            lower_bound,
+            lower_bound_type,
+            set_point,
+            set_point_type,
+        };
+        result.rust_validate().map_err(err_to_py_string)?;
+        return Ok(result);
+    }
+
+    #[staticmethod]
+    fn __class_name__() -> String {
+        "PsetBoundedValue".to_string()
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (value, /))]
+    pub fn model_validate(value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let raw = ValueWrap::extract_bound(value)?;
+        let value = raw.to_json().map_err(err_to_py_string)?;
+        let result = Self::from_json(&value).map_err(err_to_py_string)?;
+        return Ok(result);
+    }
+
+    /// Checks whether a number lies within the bounds (a missing bound is unbounded)
+    #[pyo3(name = "contains")]
+    fn py_contains(&self, x: f64) -> PyResult<bool> {
+        return self.contains(x).map_err(err_to_py_string);
+    }
+
+    fn __contains__(&self, x: f64) -> PyResult<bool> {
+        return self.contains(x).map_err(err_to_py_string);
+    }
+
+    /// Checks that the bounds match their types and are ordered
+    fn validate(&self) -> PyResult<()> {
+        return self.rust_validate().map_err(err_to_py_string);
+    }
+
+    fn __eq__(&self, other: PyRef<'_, Self>) -> bool {
+        self == &*other
+    }
+
+    pub fn __str__(&self) -> PyResult<String> {
+        Ok(self.to_string())
+    }
+}
+
+/// Rust implementation of struct
+impl PsetBoundedValue {
+    pub fn contains(&self, x: f64) -> Result<bool, String> {
+        let lower = bound_as_f64("lower bound", self.lower_bound.as_ref())?;
+        let upper = bound_as_f64("upper bound", self.upper_bound.as_ref())?;
+        return Ok(lower.is_none_or(|lower| lower <= x) && upper.is_none_or(|upper| x <= upper));
+    }
+
+    pub fn rust_validate(&self) -> Result<(), String> {
+        let bounds = [
+            (&self.upper_bound, &self.upper_bound_type),
+            (&self.lower_bound, &self.lower_bound_type),
+            (&self.set_point, &self.set_point_type),
+        ];
+        for (value, value_type) in bounds {
+            if let (Some(ValueWrap(value)), Some(value_type)) = (value, value_type) {
+                if !value.is_null() {
+                    IfcValue::from_json(value_type, value)?;
+                }
+            }
+        }
+        let lower = self
+            .lower_bound
+            .as_ref()
+            .and_then(|ValueWrap(x)| x.as_f64());
+        let upper = self
+            .upper_bound
+            .as_ref()
+            .and_then(|ValueWrap(x)| x.as_f64());
+        if let (Some(lower), Some(upper)) = (lower, upper) {
+            if lower > upper {
+                return Err(format!(
+                    "lower bound {} exceeds upper bound {}",
+                    lower, upper
+                ));
+            }
+        }
+        return Ok(());
+    }
+}
+
+impl ToString for PsetBoundedValue {
+    fn to_string(&self) -> String {
+        let text = |x: &Option<ValueWrap>| match x {
+            Some(ValueWrap(x)) if !x.is_null() => x.to_string(),
+            _ => "..".to_string(),
+        };
+        let mut result = format!(
+            "{}([{}, {}]",
+            Self::__class_name__(),
+            text(&self.lower_bound),
+            text(&self.upper_bound)
+        );
+        if self
+            .set_point
+            .as_ref()
+            .is_some_and(|ValueWrap(x)| !x.is_null())
+        {
+            result.push_str(&format!(", set-point: {}", text(&self.set_point)));
+        }
+        let value_types: Vec<&String> = [
+            &self.lower_bound_type,
+            &self.upper_bound_type,
+            &self.set_point_type,
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if let Some(value_type) = value_types.first() {
+            result.push_str(&format!(", {}", value_type));
+        }
+        result.push(')');
+        return result;
+    }
+}
+
+impl JsonConversion<Value> for PsetBoundedValue {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        let result = Self::deserialize(value.clone()).map_err(err_to_string)?;
+        result.rust_validate()?;
+        return Ok(result);
+    }
+
+    fn to_json(&self) -> Result<Value, String> {
+        let result = json!({
+            "id": self.id_,
+            "class": self.class_,
+            "upper-bound": self.upper_bound,
+            "upper-bound-type": self.upper_bound_type,
+            "lower-bound": self.lower_bound,
+            "lower-bound-type": self.lower_bound_type,
+            "set-point": self.set_point,
+            "set-point-type": self.set_point_type,
+        });
+        return Ok(result);
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF CurveInterpolation
+// ----------------------------------------------------------------
+
+/// Rust implementation of struct
+impl CurveInterpolation {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Linear => "LINEAR",
+            Self::LogLinear => "LOG_LINEAR",
+            Self::LogLog => "LOG_LOG",
+            Self::NotDefined => "NOTDEFINED",
+        }
+    }
+
+    /// Interpolates between two rows, or `None` if interpolation is not defined
+    pub fn interpolate(
+        self,
+        x: f64,
+        (x0, y0): (f64, f64),
+        (x1, y1): (f64, f64),
+    ) -> Result<Option<f64>, String> {
+        let log = |x: f64| match x > 0.0 {
+            true => Ok(x.ln()),
+            false => Err(format!("cannot interpolate logarithmically at {}", x)),
+        };
+        match self {
+            Self::Linear => {
+                let t = (x - x0) / (x1 - x0);
+                return Ok(Some(y0 + t * (y1 - y0)));
+            }
+            Self::LogLinear => {
+                let t = (log(x)? - log(x0)?) / (log(x1)? - log(x0)?);
+                return Ok(Some(y0 + t * (y1 - y0)));
+            }
+            Self::LogLog => {
+                let t = (log(x)? - log(x0)?) / (log(x1)? - log(x0)?);
+                let y = log(y0)? + t * (log(y1)? - log(y0)?);
+                return Ok(Some(y.exp()));
+            }
+            Self::NotDefined => {
+                return Ok(None);
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF PsetTableValue
+// ----------------------------------------------------------------
+
+#[pymethods]
+impl PsetTableValue {
+    #[new]
+    #[pyo3(signature = (/, *, id_, defining_values, defined_values, defining_value_type=None, defined_value_type=None, expression=None, curve_interpolation=None, class_="IfcPropertyTableValue".to_string()))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id_: i64,
+        defining_values: Vec<ValueWrap>,
+        defined_values: Vec<ValueWrap>,
+        defining_value_type: Option<String>,
+        defined_value_type: Option<String>,
+        expression: Option<String>,
+        curve_interpolation: Option<CurveInterpolation>,
+        class_: String,
+    ) -> PyResult<Self> {
+        let result = Self {
+            id_,
+            class_,
+            defining_values,
+            defined_values,
+            defining_value_type,
+            defined_value_type,
+            expression,
+            curve_interpolation,
+        };
+        result.rust_validate().map_err(err_to_py_string)?;
+        return Ok(result);
+    }
+
+    #[staticmethod]
+    fn __class_name__() -> String {
+        "PsetTableValue".to_string()
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (value, /))]
+    pub fn model_validate(value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let raw = ValueWrap::extract_bound(value)?;
+        let value = raw.to_json().map_err(err_to_py_string)?;
+        let result = Self::from_json(&value).map_err(err_to_py_string)?;
+        return Ok(result);
+    }
+
+    /// Pairs of defining and defined values
+    fn rows(&self) -> Vec<(ValueWrap, ValueWrap)> {
+        self.rust_rows()
+    }
+
+    /// Defined value for a defining value,
+    /// interpolated between rows if the table declares a curve interpolation
+    #[pyo3(name = "lookup")]
+    fn py_lookup(&self, x: ValueWrap) -> PyResult<Option<ValueWrap>> {
+        let ValueWrap(x) = x;
+        let result = self.lookup(&x).map_err(err_to_py_string)?;
+        return Ok(result.map(ValueWrap));
+    }
+
+    fn __len__(&self) -> usize {
+        self.defining_values.len()
+    }
+
+    /// Checks that both columns have the same length and match their types
+    fn validate(&self) -> PyResult<()> {
+        return self.rust_validate().map_err(err_to_py_string);
+    }
+
+    fn __eq__(&self, other: PyRef<'_, Self>) -> bool {
+        self == &*other
+    }
+
+    pub fn __str__(&self) -> PyResult<String> {
+        Ok(self.to_string())
+    }
+}
+
+/// Rust implementation of struct
+impl PsetTableValue {
+    pub fn rust_rows(&self) -> Vec<(ValueWrap, ValueWrap)> {
+        return self
+            .defining_values
+            .iter()
+            .cloned()
+            .zip(self.defined_values.iter().cloned())
+            .collect();
+    }
+
+    pub fn lookup(&self, x: &Value) -> Result<Option<Value>, String> {
+        let rows = self.rust_rows();
+        if let Some((_, ValueWrap(y))) =
+            rows.iter().find(|(ValueWrap(key), _)| values_equal(key, x))
+        {
+            return Ok(Some(y.clone()));
+        }
+        let (Some(interpolation), Some(x)) = (self.curve_interpolation, x.as_f64()) else {
+            return Ok(None);
+        };
+
+        // NOTE: rows are not required to be ordered, so the closest neighbours are searched
+        let mut lower: Option<(f64, f64)> = None;
+        let mut upper: Option<(f64, f64)> = None;
+        for (ValueWrap(key), ValueWrap(value)) in rows.iter() {
+            let (Some(key), Some(value)) = (key.as_f64(), value.as_f64()) else {
+                return Err("interpolation requires numeric rows".to_string());
+            };
+            if key < x && lower.is_none_or(|(x0, _)| x0 < key) {
+                lower = Some((key, value));
+            }
+            if key > x && upper.is_none_or(|(x1, _)| key < x1) {
+                upper = Some((key, value));
+            }
+        }
+        let (Some(lower), Some(upper)) = (lower, upper) else {
+            return Ok(None);
+        };
+        let result = interpolation.interpolate(x, lower, upper)?;
+        return Ok(result.and_then(Number::from_f64).map(Value::Number));
+    }
+
+    pub fn rust_validate(&self) -> Result<(), String> {
+        if self.defining_values.len() != self.defined_values.len() {
+            return Err(format!(
+                "{} defining values but {} defined values",
+                self.defining_values.len(),
+                self.defined_values.len()
+            ));
+        }
+        let columns = [
+            (&self.defining_values, &self.defining_value_type),
+            (&self.defined_values, &self.defined_value_type),
+        ];
+        for (values, value_type) in columns {
+            let Some(value_type) = value_type else {
+                continue;
+            };
+            for ValueWrap(value) in values.iter() {
+                IfcValue::from_json(value_type, value)?;
+            }
+        }
+        for (index, x) in self.defining_values.iter().enumerate() {
+            let ValueWrap(x) = x;
+            if self.defining_values[..index]
+                .iter()
+                .any(|ValueWrap(y)| values_equal(x, y))
+            {
+                return Err(format!("duplicate defining value {}", x));
+            }
+        }
+        return Ok(());
+    }
+}
+
+impl ToString for PsetTableValue {
+    fn to_string(&self) -> String {
+        let rows: Vec<String> = self
+            .rust_rows()
+            .iter()
+            .map(|(ValueWrap(x), ValueWrap(y))| format!("{} -> {}", x, y))
+            .collect();
+        let mut result = format!("{}({}", Self::__class_name__(), rows.join(", "));
+        if let Some(interpolation) = &self.curve_interpolation {
+            result.push_str(&format!(", {}", interpolation.name()));
+        }
+        result.push(')');
+        return result;
+    }
+}
+
+impl JsonConversion<Value> for PsetTableValue {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        let result = Self::deserialize(value.clone()).map_err(err_to_string)?;
+        result.rust_validate()?;
+        return Ok(result);
+    }
+
+    fn to_json(&self) -> Result<Value, String> {
+        let result = json!({
+            "id": self.id_,
+            "class": self.class_,
+            "defining-values": self.defining_values,
+            "defined-values": self.defined_values,
+            "defining-value-type": self.defining_value_type,
+            "defined-value-type": self.defined_value_type,
+            "expression": self.expression,
+            "curve-interpolation": self.curve_interpolation,
+        });
+        return Ok(result);
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF PsetListValue
+// ----------------------------------------------------------------
+
+#[pymethods]
+impl PsetListValue {
+    #[new]
+    #[pyo3(signature = (/, *, id_, values, value_type=None, class_="IfcPropertyListValue".to_string()))]
+    pub fn new(
+        id_: i64,
+        values: Vec<ValueWrap>,
+        value_type: Option<String>,
+        class_: String,
+    ) -> PyResult<Self> {
+        let result = Self {
+            id_,
+            class_,
+            values,
+            value_type,
+        };
+        result.rust_typed_values().map_err(err_to_py_string)?;
+        return Ok(result);
+    }
+
+    #[staticmethod]
+    fn __class_name__() -> String {
+        "PsetListValue".to_string()
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (value, /))]
+    pub fn model_validate(value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let raw = ValueWrap::extract_bound(value)?;
+        let value = raw.to_json().map_err(err_to_py_string)?;
+        let result = Self::from_json(&value).map_err(err_to_py_string)?;
+        return Ok(result);
+    }
+
+    #[getter]
+    fn typed_values(&self) -> PyResult<Option<Vec<IfcValue>>> {
+        return self.rust_typed_values().map_err(err_to_py_string);
+    }
+
+    fn __len__(&self) -> usize {
+        self.values.len()
+    }
+
+    fn __getitem__(&self, index: isize) -> PyResult<ValueWrap> {
+        // NOTE: negative indices count from the end, as for python lists
+        let position = match index < 0 {
+            true => index + self.values.len() as isize,
+            false => index,
+        };
+        match usize::try_from(position)
+            .ok()
+            .and_then(|x| self.values.get(x))
+        {
+            Some(x) => {
+                return Ok(x.clone());
+            }
+            None => {
+                return Err(err_to_py_index_error(index));
+            }
+        }
+    }
+
+    fn __contains__(&self, value: ValueWrap) -> bool {
+        self.contains(&value)
+    }
+
+    fn __eq__(&self, other: PyRef<'_, Self>) -> bool {
+        self == &*other
+    }
+
+    pub fn __str__(&self) -> PyResult<String> {
+        Ok(self.to_string())
+    }
+}
+
+/// Rust implementation of struct
+impl PsetListValue {
+    /// Whether the list holds a value, comparing numbers by their value
+    pub fn contains(&self, value: &ValueWrap) -> bool {
+        let ValueWrap(value) = value;
+        return self
+            .values
+            .iter()
+            .any(|ValueWrap(x)| values_equal(x, value));
+    }
+
+    /// Values typed by the declared value type,
+    /// or `None` if the type is not declared or not modelled
+    pub fn rust_typed_values(&self) -> Result<Option<Vec<IfcValue>>, String> {
+        let Some(value_type) = &self.value_type else {
+            return Ok(None);
+        };
+        let mut result = vec![];
+        for (index, ValueWrap(value)) in self.values.iter().enumerate() {
+            match IfcValue::from_json(value_type, value)
+                .map_err(|err| format!("[{}]: {}", index, err))?
+            {
+                Some(x) => result.push(x),
+                None => return Ok(None),
+            }
+        }
+        return Ok(Some(result));
+    }
+}
+
+impl ToString for PsetListValue {
+    fn to_string(&self) -> String {
+        let values: Vec<String> = self
+            .values
+            .iter()
+            .map(|ValueWrap(x)| x.to_string())
+            .collect();
+        let mut result = format!("{}([{}]", Self::__class_name__(), values.join(", "));
+        if let Some(value_type) = &self.value_type {
+            result.push_str(&format!(", {}", value_type));
+        }
+        result.push(')');
+        return result;
+    }
+}
+
+impl JsonConversion<Value> for PsetListValue {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        let result = Self::deserialize(value.clone()).map_err(err_to_string)?;
+        result.rust_typed_values()?;
+        return Ok(result);
+    }
+
+    fn to_json(&self) -> Result<Value, String> {
+        let result = json!({
+            "id": self.id_,
+            "class": self.class_,
+            "value": self.values,
+            "value-type": self.value_type,
+        });
+        return Ok(result);
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF ReferenceResolver
+// ----------------------------------------------------------------
+
+/// Collection of entities by id
+impl<T: Clone> ReferenceResolver<T> for IndexMap<i64, T> {
+    fn resolve(&mut self, reference: &PsetReferenceValue) -> Result<Option<T>, String> {
+        let result = reference.referenced_id.and_then(|x| self.get(&x).cloned());
+        return Ok(result);
+    }
+}
+
+impl<T, F> ReferenceResolver<T> for F
+where
+    F: FnMut(&PsetReferenceValue) -> Result<Option<T>, String>,
+{
+    fn resolve(&mut self, reference: &PsetReferenceValue) -> Result<Option<T>, String> {
+        self(reference)
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF PsetReferenceValue
+// ----------------------------------------------------------------
+
+#[pymethods]
+impl PsetReferenceValue {
+    #[new]
+    #[pyo3(signature = (/, *, id_, referenced_id=None, referenced_class=None, usage_name=None, class_="IfcPropertyReferenceValue".to_string()))]
+    pub fn new(
+        id_: i64,
+        referenced_id: Option<i64>,
+        referenced_class: Option<String>,
+        usage_name: Option<String>,
+        class_: String,
+    ) -> Self {
+        return Self {
+            id_,
+            class_,
+            usage_name,
+            referenced_id,
+            referenced_class,
+        };
+    }
+
+    #[staticmethod]
+    fn __class_name__() -> String {
+        "PsetReferenceValue".to_string()
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (value, /))]
+    pub fn model_validate(value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let raw = ValueWrap::extract_bound(value)?;
+        let value = raw.to_json().map_err(err_to_py_string)?;
+        let result = Self::from_json(&value).map_err(err_to_py_string)?;
+        return Ok(result);
+    }
+
+    /// Resolves the referenced entity, either through a mapping from ids to objects
+    /// or a callable receiving the referenced id and class (`None` if unknown)
+    #[pyo3(name = "resolve", signature = (resolver, /))]
+    fn py_resolve<'a>(
+        &self,
+        py: Python<'a>,
+        resolver: &Bound<'a, PyAny>,
+    ) -> PyResult<Bound<'a, PyAny>> {
+        let result = resolve_with_python(resolver, self)?;
+        return Ok(result.unwrap_or_else(|| py.None().into_bound(py)));
+    }
+
+    fn __eq__(&self, other: PyRef<'_, Self>) -> bool {
+        self == &*other
+    }
+
+    pub fn __str__(&self) -> PyResult<String> {
+        Ok(self.to_string())
+    }
+}
+
+/// Rust implementation of struct
+impl PsetReferenceValue {
+    pub fn rust_resolve<T>(
+        &self,
+        resolver: &mut impl ReferenceResolver<T>,
+    ) -> Result<Option<T>, String> {
+        return resolver.resolve(self);
+    }
+}
+
+impl ToString for PsetReferenceValue {
+    fn to_string(&self) -> String {
+        let target = match (&self.referenced_class, self.referenced_id) {
+            (Some(class_), Some(id_)) => format!("{} #{}", class_, id_),
+            (None, Some(id_)) => format!("#{}", id_),
+            (Some(class_), None) => class_.clone(),
+            (None, None) => "None".to_string(),
+        };
+        return format!("{}(-> {})", Self::__class_name__(), target);
+    }
+}
+
+impl JsonConversion<Value> for PsetReferenceValue {
+    /// Reads the referenced entity from `value`, given as an object with an id and class
+    /// (or type, as in ifcopenshell's `get_info`) or as a bare id
+    fn from_json(value: &Value) -> Result<Self, String> {
+        let Value::Object(object) = value else {
+            return Err("reference property must be an object".to_string());
+        };
+        let id_ = object
+            .get("id")
+            .and_then(Value::as_i64)
+            .ok_or("reference property requires an integer id")?;
+        let class_ = object
+            .get("class")
+            .and_then(Value::as_str)
+            .ok_or("reference property requires a class")?
+            .to_string();
+        for key in object.keys() {
+            if !matches!(
+                key.as_str(),
+                "id" | "class" | "value" | "UsageName" | "usage-name"
+            ) {
+                return Err(format!("unknown field '{}' of reference property", key));
+            }
+        }
+        let usage_name = match object.get("UsageName").or(object.get("usage-name")) {
+            None | Some(Value::Null) => None,
+            Some(Value::String(x)) => Some(x.clone()),
+            Some(x) => return Err(format!("usage name must be a string, got {}", x)),
+        };
+        let (referenced_id, referenced_class) = match object.get("value") {
+            None | Some(Value::Null) => (None, None),
+            Some(Value::Number(x)) => (
+                Some(x.as_i64().ok_or("referenced id must be an integer")?),
+                None,
+            ),
+            Some(Value::Object(target)) => {
+                let referenced_id = target.get("id").and_then(Value::as_i64);
+                let referenced_class = target
+                    .get("class")
+                    .or(target.get("type"))
+                    .and_then(Value::as_str)
+                    .map(|x| x.to_string());
+                (referenced_id, referenced_class)
+            }
+            Some(x) => return Err(format!("invalid reference {}", x)),
+        };
+        let result = Self {
+            id_,
+            class_,
+            usage_name,
+            referenced_id,
+            referenced_class,
+        };
+        return Ok(result);
+    }
+
+    fn to_json(&self) -> Result<Value, String> {
+        let mut result = json!({
+            "id": self.id_,
+            "class": self.class_,
+            "value": {"id": self.referenced_id, "class": self.referenced_class},
+        });
+        if let Some(usage_name) = &self.usage_name {
+            result["UsageName"] = json!(usage_name);
+        }
+        return Ok(result);
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF PsetComplexProperty
+// ----------------------------------------------------------------
+
+#[pymethods]
+impl PsetComplexProperty {
+    #[new]
+    #[pyo3(signature = (/, *, id_, properties=None, usage_name=None, class_="IfcComplexProperty".to_string()))]
+    pub fn new(
+        id_: i64,
+        properties: Option<IndexMap<String, Bound<'_, PyAny>>>,
+        usage_name: Option<String>,
+        class_: String,
+    ) -> PyResult<Self> {
+        let mut elements = IndexMap::new();
+        for (key, x) in properties.unwrap_or_default().iter() {
+            elements.insert(key.clone(), PsetNestedValue::from_python(x)?);
+        }
+        let result = Self {
+            id_,
+            class_,
+            usage_name,
+            properties: elements,
+        };
+        return Ok(result);
+    }
+
+    #[staticmethod]
+    fn __class_name__() -> String {
+        "PsetComplexProperty".to_string()
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (value, /))]
+    pub fn model_validate(value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let raw = ValueWrap::extract_bound(value)?;
+        let value = raw.to_json().map_err(err_to_py_string)?;
+        let result = Self::from_json(&value).map_err(err_to_py_string)?;
+        return Ok(result);
+    }
+
+    /// Child properties, by name
+    #[getter]
+    #[pyo3(name = "properties")]
+    fn py_properties<'a>(&self, py: Python<'a>) -> PyResult<IndexMap<String, Bound<'a, PyAny>>> {
+        let mut result = IndexMap::new();
+        for (key, x) in self.properties.iter() {
+            result.insert(key.clone(), x.to_python(py)?);
+        }
+        return Ok(result);
+    }
+
+    fn __len__(&self) -> usize {
+        self.properties.len()
+    }
+
+    fn __contains__(&self, key: String) -> bool {
+        self.properties.contains_key(&key)
+    }
+
+    fn __getitem__<'a>(&self, py: Python<'a>, key: String) -> PyResult<Bound<'a, PyAny>> {
+        match self.properties.get(&key) {
+            Some(value) => {
+                return value.to_python(py);
+            }
+            None => {
+                return Err(err_to_py_key_error(key));
+            }
+        }
+    }
+
+    fn __eq__(&self, other: PyRef<'_, Self>) -> bool {
+        self == &*other
+    }
+
+    pub fn __str__(&self) -> PyResult<String> {
+        let psets = Psets::Complex(self.clone());
+        return Ok(psets.to_string());
+    }
+}
+
+/// Rust implementation of struct
+impl PsetComplexProperty {
+    /// Checks whether a JSON object is a complex property
+    pub fn is_complex_property(value: &Value) -> bool {
+        let class_ = value.get("class").and_then(Value::as_str);
+        return class_ == Some("IfcComplexProperty");
+    }
+
+    /// Copy without the child properties, as listed by flatten
+    pub fn header(&self) -> Self {
+        return Self {
+            properties: IndexMap::new(),
+            ..self.clone()
+        };
+    }
+
+    pub fn eq_ignore_ids(&self, other: &Self) -> bool {
+        self.class_ == other.class_
+            && self.usage_name == other.usage_name
+            && self.properties.len() == other.properties.len()
+            && self.properties.iter().all(|(key, value)| {
+                other
+                    .properties
+                    .get(key)
+                    .is_some_and(|x| value.eq_ignore_ids(x))
+            })
+    }
+}
+
+impl Hash for PsetComplexProperty {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id_.hash(state);
+        self.class_.hash(state);
+        self.usage_name.hash(state);
+        // NOTE: sorted, consistent with equality which ignores the order of entries
+        let mut items: Vec<_> = self.properties.iter().collect();
+        items.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        items.hash(state);
+    }
+}
+
+impl ToString for PsetComplexProperty {
+    fn to_string(&self) -> String {
+        let mut header = json!({"id": self.id_, "class": self.class_});
+        if let Some(usage_name) = &self.usage_name {
+            header["UsageName"] = json!(usage_name);
+        }
+        return format!("{}({})", Self::__class_name__(), header);
+    }
+}
+
+impl JsonConversion<Value> for PsetComplexProperty {
+    /// Reads the child properties either from `properties` or from the remaining keys
+    fn from_json(value: &Value) -> Result<Self, String> {
+        let Value::Object(object) = value else {
+            return Err("complex property must be an object".to_string());
+        };
+        let id_ = object
+            .get("id")
+            .and_then(Value::as_i64)
+            .ok_or("complex property requires an integer id")?;
+        let class_ = object
+            .get("class")
+            .and_then(Value::as_str)
+            .ok_or("complex property requires a class")?
+            .to_string();
+        let mut usage_name: Option<String> = None;
+        for key in ["UsageName", "usage-name", "usage_name"] {
+            if let Some(x) = object.get(key).filter(|x| !x.is_null()) {
+                usage_name = Some(x.as_str().ok_or("usage name must be a string")?.to_string());
+            }
+        }
+
+        let mut properties = IndexMap::new();
+        let children = match object.get("properties") {
+            Some(Value::Object(children)) => children.iter().collect::<Vec<_>>(),
+            _ => object
+                .iter()
+                .filter(|(key, _)| {
+                    !matches!(
+                        key.as_str(),
+                        "id" | "class" | "UsageName" | "usage-name" | "usage_name"
+                    )
+                })
+                .collect(),
+        };
+        for (key, x) in children {
+            let x = PsetNestedValue::from_json(x).map_err(|err| format!("{}: {}", key, err))?;
+            properties.insert(key.clone(), x);
+        }
+        let result = Self {
+            id_,
+            class_,
+            usage_name,
+            properties,
+        };
+        return Ok(result);
+    }
+
+    fn to_json(&self) -> Result<Value, String> {
+        let mut result = Map::new();
+        result.insert("id".to_string(), json!(self.id_));
+        result.insert("class".to_string(), json!(self.class_));
+        if let Some(usage_name) = &self.usage_name {
+            result.insert("UsageName".to_string(), json!(usage_name));
+        }
+        for (key, x) in self.properties.iter() {
+            result.insert(key.clone(), x.to_json()?);
+        }
+        return Ok(Value::Object(result));
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF PsetProperty
+// ----------------------------------------------------------------
+
+/// Rust implementation of struct
+impl PsetProperty {
+    /// Checks whether a JSON object is a property of a class with a dedicated representation
+    pub fn is_typed_property(value: &Value) -> bool {
+        let class_ = value.get("class").and_then(Value::as_str);
+        return matches!(
+            class_,
+            Some("IfcPropertyEnumeratedValue")
+                | Some("IfcPropertyBoundedValue")
+                | Some("IfcPropertyTableValue")
+                | Some("IfcPropertyListValue")
+                | Some("IfcPropertyReferenceValue")
+        );
+    }
+
+    pub fn id_(&self) -> i64 {
+        match self {
+            Self::Enumerated(x) => x.id_,
+            Self::Bounded(x) => x.id_,
+            Self::Table(x) => x.id_,
+            Self::List(x) => x.id_,
+            Self::Reference(x) => x.id_,
+        }
+    }
+
+    pub fn class_(&self) -> &String {
+        match self {
+            Self::Enumerated(x) => &x.class_,
+            Self::Bounded(x) => &x.class_,
+            Self::Table(x) => &x.class_,
+            Self::List(x) => &x.class_,
+            Self::Reference(x) => &x.class_,
+        }
+    }
+
+    pub fn value_type(&self) -> Option<&String> {
+        match self {
+            Self::Enumerated(x) => x.value_type.as_ref(),
+            Self::Bounded(x) => x
+                .lower_bound_type
+                .as_ref()
+                .or(x.upper_bound_type.as_ref())
+                .or(x.set_point_type.as_ref()),
+            Self::Table(x) => x.defined_value_type.as_ref(),
+            Self::List(x) => x.value_type.as_ref(),
+            Self::Reference(_) => None,
+        }
+    }
+
+    /// Main value of the property, as compared by diff and select
+    pub fn value(&self) -> Value {
+        match self {
+            Self::Enumerated(x) => json!(x.values),
+            Self::Bounded(x) => json!({
+                "lower-bound": x.lower_bound,
+                "upper-bound": x.upper_bound,
+                "set-point": x.set_point,
+            }),
+            Self::Table(x) => json!({
+                "defining-values": x.defining_values,
+                "defined-values": x.defined_values,
+            }),
+            Self::List(x) => json!(x.values),
+            Self::Reference(x) => json!({"id": x.referenced_id, "class": x.referenced_class}),
+        }
+    }
+
+    pub fn eq_ignore_ids(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Enumerated(x), Self::Enumerated(y)) => {
+                x.class_ == y.class_
+                    && x.values == y.values
+                    && x.value_type == y.value_type
+                    && x.enumeration.as_ref().map(|x| &x.values)
+                        == y.enumeration.as_ref().map(|x| &x.values)
+            }
+            (Self::Bounded(x), Self::Bounded(y)) => {
+                let x = PsetBoundedValue {
+                    id_: y.id_,
+                    ..x.clone()
+                };
+                x == *y
+            }
+            (Self::Table(x), Self::Table(y)) => {
+                let x = PsetTableValue {
+                    id_: y.id_,
+                    ..x.clone()
+                };
+                x == *y
+            }
+            (Self::List(x), Self::List(y)) => {
+                x.class_ == y.class_ && x.values == y.values && x.value_type == y.value_type
+            }
+            (Self::Reference(x), Self::Reference(y)) => {
+                let x = PsetReferenceValue {
+                    id_: y.id_,
+                    ..x.clone()
+                };
+                x == *y
+            }
+            _ => false,
+        }
+    }
+
+    /// Converts to the typed python object
+    pub fn to_python<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        match self {
+            Self::Enumerated(x) => {
+                let result = Bound::new(py, x.clone())?.into_any();
+                return Ok(result);
+            }
+            Self::Bounded(x) => {
+                let result = Bound::new(py, x.clone())?.into_any();
+                return Ok(result);
+            }
+            Self::Table(x) => {
+                let result = Bound::new(py, x.clone())?.into_any();
+                return Ok(result);
+            }
+            Self::List(x) => {
+                let result = Bound::new(py, x.clone())?.into_any();
+                return Ok(result);
+            }
+            Self::Reference(x) => {
+                let result = Bound::new(py, x.clone())?.into_any();
+                return Ok(result);
+            }
+        }
+    }
+
+    /// Converts from a typed python object, if it is one
+    pub fn from_python(value: &Bound<'_, PyAny>) -> Option<Self> {
+        if let Ok(x) = value.extract::<PsetEnumeratedValue>() {
+            return Some(Self::Enumerated(x));
+        } else if let Ok(x) = value.extract::<PsetBoundedValue>() {
+            return Some(Self::Bounded(x));
+        } else if let Ok(x) = value.extract::<PsetTableValue>() {
+            return Some(Self::Table(x));
+        } else if let Ok(x) = value.extract::<PsetListValue>() {
+            return Some(Self::List(x));
+        } else if let Ok(x) = value.extract::<PsetReferenceValue>() {
+            return Some(Self::Reference(x));
+        }
+        return None;
+    }
+}
+
+impl ToString for PsetProperty {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Enumerated(x) => x.to_string(),
+            Self::Bounded(x) => x.to_string(),
+            Self::Table(x) => x.to_string(),
+            Self::List(x) => x.to_string(),
+            Self::Reference(x) => x.to_string(),
+        }
+    }
+}
+
+impl JsonConversion<Value> for PsetProperty {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        match value.get("class").and_then(Value::as_str) {
+            Some("IfcPropertyEnumeratedValue") => {
+                let x = PsetEnumeratedValue::from_json(value)?;
+                return Ok(Self::Enumerated(x));
+            }
+            Some("IfcPropertyBoundedValue") => {
+                let x = PsetBoundedValue::from_json(value)?;
+                return Ok(Self::Bounded(x));
+            }
+            Some("IfcPropertyTableValue") => {
+                let x = PsetTableValue::from_json(value)?;
+                return Ok(Self::Table(x));
+            }
+            Some("IfcPropertyListValue") => {
+                let x = PsetListValue::from_json(value)?;
+                return Ok(Self::List(x));
+            }
+            Some("IfcPropertyReferenceValue") => {
+                let x = PsetReferenceValue::from_json(value)?;
+                return Ok(Self::Reference(x));
+            }
+            _ => {
+                return Err("unsupported property class".to_string());
+            }
+        }
+    }
+
+    fn to_json(&self) -> Result<Value, String> {
+        match self {
+            Self::Enumerated(x) => x.to_json(),
+            Self::Bounded(x) => x.to_json(),
+            Self::Table(x) => x.to_json(),
+            Self::List(x) => x.to_json(),
+            Self::Reference(x) => x.to_json(),
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+// AUXILIARY METHODS
+// ----------------------------------------------------------------
+
+/// Reads either a list of values or a single value (as a list of one)
+fn deserialize_values<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<ValueWrap>, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::Array(elements) => Ok(elements.into_iter().map(ValueWrap).collect()),
+        Value::Null => Ok(vec![]),
+        value => Ok(vec![ValueWrap(value)]),
+    }
+}
+
+/// Equality of JSON values, comparing numbers by their value (so that `2` equals `2.0`)
+fn values_equal(x: &Value, y: &Value) -> bool {
+    match (x, y) {
+        (Value::Number(x), Value::Number(y)) => x.as_f64() == y.as_f64(),
+        _ => x == y,
+    }
+}
+
+/// Numeric value of a bound, `None` if it is missing
+fn bound_as_f64(name: &str, value: Option<&ValueWrap>) -> Result<Option<f64>, String> {
+    match value {
+        None | Some(ValueWrap(Value::Null)) => Ok(None),
+        Some(ValueWrap(x)) => match x.as_f64() {
+            Some(x) => Ok(Some(x)),
+            None => Err(format!("{} {} is not numeric", name, x)),
+        },
+    }
+}
+
+/// Resolves a reference through a python mapping (from ids to objects) or callable
+pub fn resolve_with_python<'a>(
+    resolver: &Bound<'a, PyAny>,
+    reference: &PsetReferenceValue,
+) -> PyResult<Option<Bound<'a, PyAny>>> {
+    let result = match resolver.is_callable() {
+        true => resolver.call1((reference.referenced_id, reference.referenced_class.clone()))?,
+        false => match reference.referenced_id {
+            Some(id_) => match resolver.get_item(id_) {
+                Ok(x) => x,
+                Err(err) if err.is_instance_of::<PyKeyError>(resolver.py()) => {
+                    return Ok(None);
+                }
+                Err(err) => {
+                    return Err(err);
+                }
+            },
+            None => {
+                return Ok(None);
+            }
+        },
+    };
+    return Ok(Some(result).filter(|x| !x.is_none()));
+}
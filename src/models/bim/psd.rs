@@ -0,0 +1,177 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use indexmap::IndexMap;
+use roxmltree::Document;
+use roxmltree::Node;
+use std::result::Result;
+
+use super::templates::PropertyTemplate;
+use super::templates::PsetTemplate;
+use crate::_core::errors::err_to_string;
+
+// ----------------------------------------------------------------
+// CONSTANTS
+// ----------------------------------------------------------------
+
+/// Quantity types of `QtoDef` and the classes they stand for
+const QTO_TYPES: [(&str, &str); 6] = [
+    ("Q_LENGTH", "IfcQuantityLength"),
+    ("Q_AREA", "IfcQuantityArea"),
+    ("Q_VOLUME", "IfcQuantityVolume"),
+    ("Q_COUNT", "IfcQuantityCount"),
+    ("Q_WEIGHT", "IfcQuantityWeight"),
+    ("Q_TIME", "IfcQuantityTime"),
+];
+
+// ----------------------------------------------------------------
+// STRUCTS
+// ----------------------------------------------------------------
+
+/// Set definition read from a PSD file (`PropertySetDef` or `QtoSetDef`)
+#[derive(Clone, Debug, PartialEq)]
+pub struct PsdDefinition {
+    pub name: String,
+    pub template: PsetTemplate,
+    /// allowed values of the enumerated properties, by the name of their list
+    pub enumerations: IndexMap<String, Vec<String>>,
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF PsdDefinition
+// ----------------------------------------------------------------
+
+/// Rust implementation of struct
+impl PsdDefinition {
+    /// Parses a buildingSMART PSD file, where properties are optional
+    /// unless marked with `required="true"` (an extension for custom sets)
+    pub fn from_xml(text: &str) -> Result<Self, String> {
+        let document = Document::parse(text).map_err(err_to_string)?;
+        let root = document.root_element();
+        let (definitions, definition) = match tag(root) {
+            "PropertySetDef" => ("PropertyDefs", "PropertyDef"),
+            "QtoSetDef" => ("QtoDefs", "QtoDef"),
+            other => {
+                return Err(format!(
+                    "expected a PropertySetDef or QtoSetDef, found '{}'",
+                    other
+                ));
+            }
+        };
+        let name = text_of(root, "Name").ok_or("set definition requires a name")?;
+        let mut applicable: Vec<String> = child(root, "ApplicableClasses")
+            .into_iter()
+            .flat_map(|x| x.children().filter(|x| tag(*x) == "ClassName"))
+            .filter_map(|x| x.text())
+            .map(|x| x.trim().to_string())
+            .collect();
+        // NOTE: some definitions only give the applicable type value (`IfcWall/SHEAR`)
+        if applicable.is_empty() {
+            if let Some(x) = text_of(root, "ApplicableTypeValue") {
+                applicable.push(x);
+            }
+        }
+
+        let mut result = Self {
+            template: PsetTemplate {
+                applicable,
+                properties: IndexMap::new(),
+            },
+            enumerations: IndexMap::new(),
+            name,
+        };
+        let nodes = child(root, definitions)
+            .into_iter()
+            .flat_map(|x| x.children().filter(|x| tag(*x) == definition));
+        for node in nodes {
+            let key = text_of(node, "Name")
+                .ok_or_else(|| format!("{} of {} requires a name", definition, result.name))?;
+            let property = match definition {
+                "QtoDef" => result.quantity_from_xml(node, &key)?,
+                _ => result.property_from_xml(node, &key)?,
+            };
+            result.template.properties.insert(key, property);
+        }
+        return Ok(result);
+    }
+
+    fn quantity_from_xml(&self, node: Node, key: &str) -> Result<PropertyTemplate, String> {
+        let qto_type = text_of(node, "QtoType").unwrap_or_default();
+        let Some((_, class_)) = QTO_TYPES.iter().find(|(x, _)| *x == qto_type) else {
+            return Err(format!(
+                "invalid quantity type '{}' of {}:{}",
+                qto_type, self.name, key
+            ));
+        };
+        let result = PropertyTemplate {
+            data_type: None,
+            class_: Some(class_.to_string()),
+            enumeration: None,
+            required: is_required(node),
+        };
+        return Ok(result);
+    }
+
+    fn property_from_xml(&mut self, node: Node, key: &str) -> Result<PropertyTemplate, String> {
+        let property_type = child(node, "PropertyType")
+            .and_then(|x| x.children().find(Node::is_element))
+            .ok_or_else(|| format!("{}:{} requires a property type", self.name, key))?;
+        // NOTE: bounded, list and table values give their data type(s) the same way
+        let data_type = property_type
+            .descendants()
+            .find(|x| tag(*x) == "DataType")
+            .and_then(|x| x.attribute("type"))
+            .map(|x| x.to_string());
+        let mut result = PropertyTemplate {
+            data_type,
+            class_: None,
+            enumeration: None,
+            required: is_required(node),
+        };
+        if let Some(list) = child(property_type, "EnumList") {
+            // NOTE: enumerations of custom sets are not always named
+            let name = match list.attribute("name") {
+                Some(x) if !x.is_empty() => x.to_string(),
+                _ => format!("{}.{}", self.name, key),
+            };
+            let values = list
+                .children()
+                .filter(|x| tag(*x) == "EnumItem")
+                .filter_map(|x| x.text())
+                .map(|x| x.trim().to_string())
+                .collect();
+            self.enumerations.insert(name.clone(), values);
+            result.enumeration = Some(name);
+            // NOTE: enumerated values are labels unless stated otherwise
+            result.data_type = result.data_type.or(Some("IfcLabel".to_string()));
+        }
+        return Ok(result);
+    }
+}
+
+// ----------------------------------------------------------------
+// AUXILIARY METHODS
+// ----------------------------------------------------------------
+
+fn tag<'a>(node: Node<'a, '_>) -> &'a str {
+    node.tag_name().name()
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|x| x.is_element() && tag(*x) == name)
+}
+
+/// Trimmed, non-empty text of a child element
+fn text_of(node: Node, name: &str) -> Option<String> {
+    let text = child(node, name)?.text()?.trim();
+    if text.is_empty() {
+        return None;
+    }
+    return Some(text.to_string());
+}
+
+fn is_required(node: Node) -> bool {
+    node.attribute("required")
+        .is_some_and(|x| x.eq_ignore_ascii_case("true"))
+}
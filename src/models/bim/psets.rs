@@ -2,38 +2,68 @@
 // IMPORTS
 // ----------------------------------------------------------------
 
+use indexmap::IndexMap;
 use pyo3::Bound;
 use pyo3::FromPyObject; // NOTE: needed, in order to "import" the FromPyObject implementations of other structs
 use pyo3::IntoPyObject;
+use pyo3::IntoPyObjectExt;
+use pyo3::Py;
+use pyo3::PyErr;
 use pyo3::PyRef;
 use pyo3::PyRefMut;
 use pyo3::PyResult;
 use pyo3::Python;
 use pyo3::prelude::pyclass;
 use pyo3::prelude::pymethods;
+use pyo3::pyclass::CompareOp;
 use pyo3::types::PyAny;
+use pyo3::types::PyAnyMethods;
+use pyo3::types::PyString;
 use pyo3::types::PyTuple;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_json::Map;
 use serde_json::Value;
 use serde_json::json;
-use std::collections::HashMap;
+use std::cmp::Ordering;
 use std::fmt::Debug;
+use std::hash::Hash;
+use std::hash::Hasher;
 use std::result::Result;
 
+use super::diff::PsetsDiff;
+use super::merge::MergeStrategy;
+use super::merge::merge_psets;
+use super::properties::PsetComplexProperty;
+use super::properties::PsetProperty;
+use super::properties::PsetReferenceValue;
+use super::properties::ReferenceResolver;
+use super::properties::resolve_with_python;
+use super::select::PathPattern;
+use super::select::PsetsSelector;
+use super::select::entity_parts;
+use super::templates::PsetTemplateLibrary;
+use super::templates::PsetsValidation;
+use super::units::UnitAssignment;
+use super::values::IfcValue;
+use crate::_core::errors::err_to_py_key_error;
 use crate::_core::errors::err_to_py_string;
 use crate::_core::errors::err_to_string;
+use crate::_core::hashing::hash_of;
 use crate::models::json::base::JsonConversion;
 use crate::models::json::model::ValueWrap;
+use crate::models::json::patch::JsonPatch;
+use crate::models::step::writer::StepIdAllocator;
+use crate::models::step::writer::StepPsetsWriter;
 use crate::models::tree::base::GenericTree;
+use crate::models::tree::base::TraversalOrder;
 
 // ----------------------------------------------------------------
 // STRUCTS
 // ----------------------------------------------------------------
 
 #[pyclass(get_all, set_all)]
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct PsetId {
     #[serde(alias = "id")]
@@ -41,7 +71,7 @@ pub struct PsetId {
 }
 
 #[pyclass(get_all, set_all)]
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Pset {
     #[serde(alias = "id")]
@@ -54,11 +84,47 @@ pub struct Pset {
     pub value_type: Option<String>,
 }
 
+/// Physical dimension of a base quantity
+#[pyclass(eq, eq_int)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum QuantityDimension {
+    #[pyo3(name = "LENGTH")]
+    Length,
+    #[pyo3(name = "AREA")]
+    Area,
+    #[pyo3(name = "VOLUME")]
+    Volume,
+    #[pyo3(name = "COUNT")]
+    Count,
+    #[pyo3(name = "WEIGHT")]
+    Weight,
+    #[pyo3(name = "TIME")]
+    Time,
+}
+
+/// Base quantity of a quantity set (IfcQuantityLength, IfcQuantityArea, ...)
+#[pyclass(get_all, set_all)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Quantity {
+    #[serde(alias = "id")]
+    pub id_: i64,
+    #[serde(alias = "class")]
+    pub class_: String,
+    pub value: ValueWrap,
+    #[serde(alias = "Formula", default)]
+    pub formula: Option<String>,
+}
+
 #[pyclass(get_all, set_all)]
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum PsetFlattenedValue {
     Pset(Pset),
     PsetId(PsetId),
+    Quantity(Quantity),
+    Property(PsetProperty),
+    /// a complex property without its children, which are listed separately
+    Complex(PsetComplexProperty),
     Value(ValueWrap),
 }
 
@@ -68,28 +134,72 @@ pub struct PsetFlattenedValueWithAddress {
     entity: Option<PsetFlattenedValue>,
 }
 
+// NOTE: variants cannot be boxed, as pyo3 needs the fields to be python classes
+#[allow(clippy::large_enum_variant)]
 #[pyclass(get_all, set_all)]
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum PsetNestedValue {
     Psets(Psets),
     Any(ValueWrap),
 }
 
 #[pyclass(get_all, set_all)]
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub enum Psets {
     Pset(Pset),
     PsetId(PsetId),
-    Nested(HashMap<String, PsetNestedValue>),
+    Quantity(Quantity),
+    Property(PsetProperty),
+    Complex(PsetComplexProperty),
+    Nested {
+        elements: IndexMap<String, PsetNestedValue>,
+    },
 }
 
 #[pyclass]
 struct PsetsIterator {
-    entity: Psets,
+    entity: Py<Psets>,
     index: usize,
 }
 
+/// Node visited by walk
+#[derive(Clone, Debug)]
+pub struct PsetsWalkStep {
+    pub path: Vec<String>,
+    pub depth: usize,
+    /// the entity of a leaf (or the header of a complex property), or `None` for nested entries
+    pub entity: Option<PsetFlattenedValue>,
+}
+
+/// State of a lazy walk, which keeps the path of each pending node instead of the node itself
+#[derive(Clone, Debug)]
+pub struct PsetsWalk {
+    order: TraversalOrder,
+    max_depth: Option<usize>,
+    leaves_only: bool,
+    /// path of each node of the current lineage, whether it was entered, and its next child
+    stack: Vec<(Vec<String>, bool, usize)>,
+}
+
+/// Iterator over the nodes of a Psets, cf. rust_walk
+pub struct PsetsWalkIter<'a> {
+    entity: &'a Psets,
+    walk: PsetsWalk,
+}
+
+/// Node resolved during a walk
+enum PsetsWalkNode<'a> {
+    Psets(&'a Psets),
+    Value(&'a ValueWrap),
+}
+
+#[pyclass]
+struct PsetsWalkIterator {
+    entity: Py<Psets>,
+    walk: PsetsWalk,
+}
+
 // ----------------------------------------------------------------
 // IMPLEMENTATIONS OF PsetId
 // ----------------------------------------------------------------
@@ -117,6 +227,18 @@ impl PsetId {
         return Ok(result);
     }
 
+    fn __eq__(&self, other: PyRef<'_, Self>) -> bool {
+        self == &*other
+    }
+
+    #[pyo3(signature = (other, /, *, ignore_ids=false))]
+    fn equals(&self, other: PyRef<'_, Self>, ignore_ids: bool) -> bool {
+        match ignore_ids {
+            true => self.eq_ignore_ids(&other),
+            false => self == &*other,
+        }
+    }
+
     pub fn __str__(&self) -> PyResult<String> {
         Ok(self.to_string())
     }
@@ -127,6 +249,14 @@ impl PsetId {
     }
 }
 
+/// Rust implementation of struct
+impl PsetId {
+    /// Ids are the only content, so any two agree when ignoring ids
+    pub fn eq_ignore_ids(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
 impl ToString for PsetId {
     fn to_string(&self) -> String {
         match self.to_json() {
@@ -179,6 +309,7 @@ impl Pset {
             value,
             value_type,
         };
+        result.rust_typed_value().map_err(err_to_py_string)?;
         return Ok(result);
     }
 
@@ -196,6 +327,38 @@ impl Pset {
         return Ok(result);
     }
 
+    fn __eq__(&self, other: PyRef<'_, Self>) -> bool {
+        self == &*other
+    }
+
+    #[pyo3(signature = (other, /, *, ignore_ids=false))]
+    fn equals(&self, other: PyRef<'_, Self>, ignore_ids: bool) -> bool {
+        match ignore_ids {
+            true => self.eq_ignore_ids(&other),
+            false => self == &*other,
+        }
+    }
+
+    #[getter]
+    fn typed_value(&self) -> PyResult<Option<IfcValue>> {
+        return self.rust_typed_value().map_err(err_to_py_string);
+    }
+
+    /// Symbol of the unit of the value, as assigned by `units` or else the SI unit
+    #[pyo3(signature = (units=None, /))]
+    fn unit(&self, units: Option<UnitAssignment>) -> Option<String> {
+        let units = units.unwrap_or_default();
+        let value_type = self.value_type.as_ref()?;
+        return units.unit_of(value_type).map(|x| x.symbol);
+    }
+
+    /// Converts the value from the unit assigned by `units` (or else the SI unit) into `unit`
+    #[pyo3(signature = (unit, /, *, units=None))]
+    fn convert_to(&self, unit: &str, units: Option<UnitAssignment>) -> PyResult<Self> {
+        let units = units.unwrap_or_default();
+        return self.rust_convert_to(unit, &units).map_err(err_to_py_string);
+    }
+
     pub fn __str__(&self) -> PyResult<String> {
         Ok(self.to_string())
     }
@@ -206,6 +369,42 @@ impl Pset {
     }
 }
 
+/// Rust implementation of struct
+impl Pset {
+    pub fn eq_ignore_ids(&self, other: &Self) -> bool {
+        self.class_ == other.class_
+            && self.value == other.value
+            && self.value_type == other.value_type
+    }
+
+    /// Value typed by the declared value type,
+    /// or `None` if the value is unset or the type is not modelled
+    pub fn rust_typed_value(&self) -> Result<Option<IfcValue>, String> {
+        let ValueWrap(value) = &self.value;
+        match (&self.value_type, value) {
+            (Some(value_type), value) if !value.is_null() => {
+                return IfcValue::from_json(value_type, value);
+            }
+            _ => {
+                return Ok(None);
+            }
+        }
+    }
+
+    pub fn rust_convert_to(&self, unit: &str, units: &UnitAssignment) -> Result<Self, String> {
+        let value_type = self
+            .value_type
+            .as_ref()
+            .ok_or("cannot convert a value without value type")?;
+        let ValueWrap(value) = &self.value;
+        let result = Self {
+            value: ValueWrap(units.convert_json(value, value_type, unit)?),
+            ..self.clone()
+        };
+        return Ok(result);
+    }
+}
+
 impl ToString for Pset {
     fn to_string(&self) -> String {
         match self.to_json() {
@@ -228,12 +427,14 @@ impl ToString for Pset {
 impl JsonConversion<Value> for Pset {
     fn from_json(value: &Value) -> Result<Self, String> {
         let raw = Self::deserialize(value.clone()).map_err(err_to_string)?;
-        return Ok(Self {
+        let result = Self {
             id_: raw.id_,
             class_: raw.class_,
             value: raw.value,
             value_type: raw.value_type,
-        });
+        };
+        result.rust_typed_value()?;
+        return Ok(result);
     }
 
     fn to_json(&self) -> Result<Value, String> {
@@ -247,6 +448,299 @@ impl JsonConversion<Value> for Pset {
     }
 }
 
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF QuantityDimension
+// ----------------------------------------------------------------
+
+/// Rust implementation of struct
+impl QuantityDimension {
+    pub fn from_class(class_: &str) -> Option<Self> {
+        match class_ {
+            "IfcQuantityLength" => Some(Self::Length),
+            "IfcQuantityArea" => Some(Self::Area),
+            "IfcQuantityVolume" => Some(Self::Volume),
+            "IfcQuantityCount" => Some(Self::Count),
+            "IfcQuantityWeight" => Some(Self::Weight),
+            "IfcQuantityTime" => Some(Self::Time),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Length => "LENGTH",
+            Self::Area => "AREA",
+            Self::Volume => "VOLUME",
+            Self::Count => "COUNT",
+            Self::Weight => "WEIGHT",
+            Self::Time => "TIME",
+        }
+    }
+
+    /// IFC measure type of the values
+    pub fn value_type(self) -> &'static str {
+        match self {
+            Self::Length => "IfcLengthMeasure",
+            Self::Area => "IfcAreaMeasure",
+            Self::Volume => "IfcVolumeMeasure",
+            Self::Count => "IfcCountMeasure",
+            Self::Weight => "IfcMassMeasure",
+            Self::Time => "IfcTimeMeasure",
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF Quantity
+// ----------------------------------------------------------------
+
+#[pymethods]
+impl Quantity {
+    #[new]
+    #[pyo3(signature = (/, *, id_, class_, value, formula=None))]
+    pub fn new(
+        id_: i64,
+        class_: String,
+        value: &Bound<'_, PyAny>,
+        formula: Option<String>,
+    ) -> PyResult<Self> {
+        let value = ValueWrap::extract_bound(value)?;
+        let result = Self {
+            id_,
+            class_,
+            value,
+            formula,
+        };
+        result.rust_typed_value().map_err(err_to_py_string)?;
+        return Ok(result);
+    }
+
+    #[staticmethod]
+    fn __class_name__() -> String {
+        "Quantity".to_string()
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (value, /))]
+    pub fn model_validate<'a>(value: &Bound<'a, PyAny>) -> PyResult<Self> {
+        let raw = ValueWrap::extract_bound(value)?;
+        let value = raw.to_json().map_err(err_to_py_string)?;
+        let result = Self::from_json(&value).map_err(err_to_py_string)?;
+        return Ok(result);
+    }
+
+    /// Equality by value (as for Psets holding the quantities),
+    /// ordering by magnitude within the same dimension
+    fn __richcmp__(&self, other: PyRef<'_, Self>, op: CompareOp) -> PyResult<bool> {
+        match op {
+            CompareOp::Eq => Ok(self == &*other),
+            CompareOp::Ne => Ok(self != &*other),
+            _ => {
+                let ordering = self.rust_compare(&other).map_err(err_to_py_string)?;
+                return Ok(op.matches(ordering));
+            }
+        }
+    }
+
+    #[pyo3(signature = (other, /, *, ignore_ids=false))]
+    fn equals(&self, other: PyRef<'_, Self>, ignore_ids: bool) -> bool {
+        match ignore_ids {
+            true => self.eq_ignore_ids(&other),
+            false => self == &*other,
+        }
+    }
+
+    /// Whether the magnitudes agree, refusing to compare quantities of different dimensions
+    fn same_magnitude(&self, other: PyRef<'_, Self>) -> PyResult<bool> {
+        let ordering = self.rust_compare(&other).map_err(err_to_py_string)?;
+        return Ok(ordering == Ordering::Equal);
+    }
+
+    fn __add__(&self, other: PyRef<'_, Self>) -> PyResult<Self> {
+        return self.rust_add(&other).map_err(err_to_py_string);
+    }
+
+    /// Supports the builtin `sum`, which starts from `0`
+    fn __radd__(&self, other: &Bound<'_, PyAny>) -> PyResult<Self> {
+        match other.extract::<i64>() {
+            Ok(0) => Ok(self.clone()),
+            _ => Err(err_to_py_string(format!(
+                "cannot add {} to a quantity",
+                other.str()?
+            ))),
+        }
+    }
+
+    #[getter]
+    #[pyo3(name = "dimension")]
+    fn py_dimension(&self) -> PyResult<QuantityDimension> {
+        return self.dimension().map_err(err_to_py_string);
+    }
+
+    #[getter]
+    fn typed_value(&self) -> PyResult<IfcValue> {
+        return self.rust_typed_value().map_err(err_to_py_string);
+    }
+
+    /// Symbol of the unit of the value, as assigned by `units` or else the SI unit
+    #[pyo3(signature = (units=None, /))]
+    fn unit(&self, units: Option<UnitAssignment>) -> PyResult<Option<String>> {
+        let units = units.unwrap_or_default();
+        let dimension = self.dimension().map_err(err_to_py_string)?;
+        return Ok(units.unit_of(dimension.value_type()).map(|x| x.symbol));
+    }
+
+    /// Converts the value from the unit assigned by `units` (or else the SI unit) into `unit`
+    #[pyo3(signature = (unit, /, *, units=None))]
+    fn convert_to(&self, unit: &str, units: Option<UnitAssignment>) -> PyResult<Self> {
+        let units = units.unwrap_or_default();
+        return self.rust_convert_to(unit, &units).map_err(err_to_py_string);
+    }
+
+    pub fn __str__(&self) -> PyResult<String> {
+        Ok(self.to_string())
+    }
+
+    fn into_pyobject<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let raw = self.to_json().map_err(err_to_py_string)?;
+        return ValueWrap(raw).into_pyobject(py);
+    }
+}
+
+/// Rust implementation of struct
+impl Quantity {
+    /// Whether the value is a base quantity, recognised by its class
+    pub fn is_quantity(value: &Value) -> bool {
+        return value
+            .get("class")
+            .and_then(Value::as_str)
+            .and_then(QuantityDimension::from_class)
+            .is_some();
+    }
+
+    pub fn eq_ignore_ids(&self, other: &Self) -> bool {
+        self.class_ == other.class_ && self.value == other.value && self.formula == other.formula
+    }
+
+    pub fn dimension(&self) -> Result<QuantityDimension, String> {
+        return QuantityDimension::from_class(&self.class_)
+            .ok_or_else(|| format!("unknown quantity class '{}'", self.class_));
+    }
+
+    /// Value typed by the measure type of the dimension
+    pub fn rust_typed_value(&self) -> Result<IfcValue, String> {
+        let ValueWrap(value) = &self.value;
+        let value_type = self.dimension()?.value_type();
+        return IfcValue::from_json(value_type, value)?
+            .ok_or_else(|| format!("unsupported measure type {}", value_type));
+    }
+
+    pub fn magnitude(&self) -> Result<f64, String> {
+        let value = self.rust_typed_value()?;
+        return value
+            .as_f64()
+            .ok_or_else(|| format!("{} is not numeric", value.to_string()));
+    }
+
+    pub fn rust_convert_to(&self, unit: &str, units: &UnitAssignment) -> Result<Self, String> {
+        let value_type = self.dimension()?.value_type();
+        let ValueWrap(value) = &self.value;
+        let result = Self {
+            value: ValueWrap(units.convert_json(value, value_type, unit)?),
+            ..self.clone()
+        };
+        return Ok(result);
+    }
+
+    /// Orders by magnitude, refusing to compare quantities of different dimensions
+    pub fn rust_compare(&self, other: &Self) -> Result<Ordering, String> {
+        self.check_dimension(other)?;
+        let (x, y) = (self.magnitude()?, other.magnitude()?);
+        return x
+            .partial_cmp(&y)
+            .ok_or_else(|| format!("cannot compare {} and {}", x, y));
+    }
+
+    /// Sums two quantities of the same dimension into a new quantity of the class of the left one
+    pub fn rust_add(&self, other: &Self) -> Result<Self, String> {
+        self.check_dimension(other)?;
+        let value = match (&self.value, &other.value) {
+            (ValueWrap(Value::Number(x)), ValueWrap(Value::Number(y)))
+                if x.is_i64() && y.is_i64() =>
+            {
+                let (x, y) = (
+                    x.as_i64().unwrap_or_default(),
+                    y.as_i64().unwrap_or_default(),
+                );
+                let sum = x
+                    .checked_add(y)
+                    .ok_or_else(|| format!("the sum of {} and {} overflows", x, y))?;
+                json!(sum)
+            }
+            _ => json!(self.magnitude()? + other.magnitude()?),
+        };
+        // NOTE: the sum is not an instance of the file, so it has no id yet
+        let result = Self {
+            id_: 0,
+            class_: self.class_.clone(),
+            value: ValueWrap(value),
+            formula: None,
+        };
+        return Ok(result);
+    }
+
+    fn check_dimension(&self, other: &Self) -> Result<(), String> {
+        let (x, y) = (self.dimension()?, other.dimension()?);
+        if x != y {
+            return Err(format!(
+                "quantities of different dimensions ({} and {})",
+                x.name(),
+                y.name()
+            ));
+        }
+        return Ok(());
+    }
+}
+
+impl ToString for Quantity {
+    fn to_string(&self) -> String {
+        match self.to_json() {
+            Ok(value) => match serde_json::to_string(&value) {
+                Ok(text) => {
+                    let name = Self::__class_name__();
+                    return format!("{}({})", name, text);
+                }
+                Err(err) => {
+                    panic!("{:?}", err);
+                }
+            },
+            Err(err) => {
+                panic!("{:?}", err);
+            }
+        }
+    }
+}
+
+impl JsonConversion<Value> for Quantity {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        let result = Self::deserialize(value.clone()).map_err(err_to_string)?;
+        result.rust_typed_value()?;
+        return Ok(result);
+    }
+
+    fn to_json(&self) -> Result<Value, String> {
+        let mut result = json!({
+            "id": self.id_,
+            "class": self.class_,
+            "value": self.value,
+        });
+        if let Some(formula) = &self.formula {
+            result["formula"] = json!(formula);
+        }
+        return Ok(result);
+    }
+}
+
 // ----------------------------------------------------------------
 // IMPLEMENTATIONS OF PsetNestedValue
 // ----------------------------------------------------------------
@@ -276,6 +770,71 @@ impl PsetNestedValue {
     }
 }
 
+/// Rust implementation of struct
+impl PsetNestedValue {
+    pub fn eq_ignore_ids(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Psets(x), Self::Psets(y)) => x.eq_ignore_ids(y),
+            (Self::Any(x), Self::Any(y)) => x == y,
+            _ => false,
+        }
+    }
+
+    /// Converts to the typed python object (Pset, PsetId, typed property, Psets or plain value)
+    pub fn to_python<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        match self {
+            Self::Psets(Psets::Pset(x)) => {
+                let result = Bound::new(py, x.clone())?.into_any();
+                return Ok(result);
+            }
+            Self::Psets(Psets::PsetId(x)) => {
+                let result = Bound::new(py, x.clone())?.into_any();
+                return Ok(result);
+            }
+            Self::Psets(Psets::Quantity(x)) => {
+                let result = Bound::new(py, x.clone())?.into_any();
+                return Ok(result);
+            }
+            Self::Psets(Psets::Property(x)) => {
+                return x.to_python(py);
+            }
+            Self::Psets(Psets::Complex(x)) => {
+                let result = Bound::new(py, x.clone())?.into_any();
+                return Ok(result);
+            }
+            Self::Psets(x) => {
+                let result = Bound::new(py, x.clone())?.into_any();
+                return Ok(result);
+            }
+            Self::Any(x) => {
+                return x.clone().to_python(py);
+            }
+        }
+    }
+
+    /// Converts from a typed python object (Pset, PsetId, typed property, Psets) or a plain value
+    pub fn from_python(value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        if let Ok(x) = value.extract::<Pset>() {
+            return Ok(Self::Psets(Psets::Pset(x)));
+        } else if let Ok(x) = value.extract::<PsetId>() {
+            return Ok(Self::Psets(Psets::PsetId(x)));
+        } else if let Ok(x) = value.extract::<Quantity>() {
+            return Ok(Self::Psets(Psets::Quantity(x)));
+        } else if let Some(x) = PsetProperty::from_python(value) {
+            return Ok(Self::Psets(Psets::Property(x)));
+        } else if let Ok(x) = value.extract::<PsetComplexProperty>() {
+            return Ok(Self::Psets(Psets::Complex(x)));
+        } else if let Ok(x) = value.extract::<Psets>() {
+            return Ok(Self::Psets(x));
+        } else {
+            let raw = ValueWrap::extract_bound(value)?;
+            let value = raw.to_json().map_err(err_to_py_string)?;
+            let result = Self::from_json(&value).map_err(err_to_py_string)?;
+            return Ok(result);
+        }
+    }
+}
+
 impl ToString for PsetNestedValue {
     fn to_string(&self) -> String {
         match self {
@@ -291,12 +850,12 @@ impl ToString for PsetNestedValue {
 
 impl JsonConversion<Value> for PsetNestedValue {
     fn from_json(value: &Value) -> Result<Self, String> {
-        if let Ok(x) = Psets::from_json(value) {
+        if let Value::Object(_) = value {
+            let x = Psets::from_json(value)?;
             return Ok(Self::Psets(x));
-        } else if let Ok(x) = ValueWrap::deserialize(value) {
-            return Ok(Self::Any(x));
         } else {
-            return Err("parse error".to_string());
+            let x = ValueWrap::from_json(value)?;
+            return Ok(Self::Any(x));
         }
     }
 
@@ -321,42 +880,348 @@ impl ToString for PsetFlattenedValue {
         match self {
             PsetFlattenedValue::Pset(x) => x.to_string(),
             PsetFlattenedValue::PsetId(x) => x.to_string(),
+            PsetFlattenedValue::Quantity(x) => x.to_string(),
+            PsetFlattenedValue::Property(x) => x.to_string(),
+            PsetFlattenedValue::Complex(x) => x.to_string(),
             PsetFlattenedValue::Value(ValueWrap(x)) => x.to_string(),
         }
     }
 }
 
-impl ToString for PsetFlattenedValueWithAddress {
-    fn to_string(&self) -> String {
-        match (&self.addr, &self.entity) {
-            (None, None) => {
-                format!("Psets")
-            }
-            (Some(addr), None) => {
-                format!("{}", addr)
-            }
-            (None, Some(entity)) => {
-                format!("Psets: {}", entity.to_string())
-            }
-            (Some(addr), Some(entity)) => {
-                format!("{}: {}", addr, entity.to_string())
+/// Rust implementation of struct
+impl PsetFlattenedValue {
+    /// Converts a flattened entry back into an entry of a nested structure
+    pub fn to_nested(&self) -> PsetNestedValue {
+        match self {
+            Self::Pset(x) => PsetNestedValue::Psets(Psets::Pset(x.clone())),
+            Self::PsetId(x) => PsetNestedValue::Psets(Psets::PsetId(x.clone())),
+            Self::Quantity(x) => PsetNestedValue::Psets(Psets::Quantity(x.clone())),
+            Self::Property(x) => PsetNestedValue::Psets(Psets::Property(x.clone())),
+            Self::Complex(x) => PsetNestedValue::Psets(Psets::Complex(x.clone())),
+            Self::Value(ValueWrap(Value::Object(x))) if x.is_empty() => {
+                PsetNestedValue::Psets(Psets::Nested {
+                    elements: IndexMap::new(),
+                })
             }
+            Self::Value(x) => PsetNestedValue::Any(x.clone()),
         }
     }
-}
 
-// ----------------------------------------------------------------
-// IMPLEMENTATIONS OF Psets
-// ----------------------------------------------------------------
+    /// Converts to the typed python object (Pset, PsetId, typed property or plain value)
+    pub fn to_python<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        return self.to_nested().to_python(py);
+    }
+
+    /// Converts from a typed python object (Pset, PsetId, typed property) or a plain value
+    pub fn from_python(value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        if let Ok(x) = value.extract::<Pset>() {
+            return Ok(Self::Pset(x));
+        } else if let Ok(x) = value.extract::<PsetId>() {
+            return Ok(Self::PsetId(x));
+        } else if let Ok(x) = value.extract::<Quantity>() {
+            return Ok(Self::Quantity(x));
+        } else if let Some(x) = PsetProperty::from_python(value) {
+            return Ok(Self::Property(x));
+        } else if let Ok(x) = value.extract::<PsetComplexProperty>() {
+            return Ok(Self::Complex(x));
+        } else {
+            let raw = ValueWrap::extract_bound(value)?;
+            let value = raw.to_json().map_err(err_to_py_string)?;
+            let result = Self::from_json(&value).map_err(err_to_py_string)?;
+            return Ok(result);
+        }
+    }
+}
+
+impl JsonConversion<Value> for PsetFlattenedValue {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        if PsetComplexProperty::is_complex_property(value) {
+            let x = PsetComplexProperty::from_json(value)?;
+            return Ok(Self::Complex(x));
+        } else if Quantity::is_quantity(value) {
+            let x = Quantity::from_json(value)?;
+            return Ok(Self::Quantity(x));
+        } else if PsetProperty::is_typed_property(value) {
+            let x = PsetProperty::from_json(value)?;
+            return Ok(Self::Property(x));
+        } else if is_property_like(value) {
+            let x = Pset::from_json(value)?;
+            return Ok(Self::Pset(x));
+        } else if let Ok(x) = PsetId::from_json(value) {
+            return Ok(Self::PsetId(x));
+        } else {
+            let x = ValueWrap::from_json(value)?;
+            return Ok(Self::Value(x));
+        }
+    }
+
+    fn to_json(&self) -> Result<Value, String> {
+        match self {
+            Self::Pset(x) => {
+                return x.to_json();
+            }
+            Self::PsetId(x) => {
+                return x.to_json();
+            }
+            Self::Quantity(x) => {
+                return x.to_json();
+            }
+            Self::Property(x) => {
+                return x.to_json();
+            }
+            Self::Complex(x) => {
+                return x.to_json();
+            }
+            Self::Value(x) => {
+                return x.to_json();
+            }
+        }
+    }
+}
+
+impl ToString for PsetFlattenedValueWithAddress {
+    fn to_string(&self) -> String {
+        match (&self.addr, &self.entity) {
+            (None, None) => {
+                format!("Psets")
+            }
+            (Some(addr), None) => {
+                format!("{}", addr)
+            }
+            (None, Some(entity)) => {
+                format!("Psets: {}", entity.to_string())
+            }
+            (Some(addr), Some(entity)) => {
+                format!("{}: {}", addr, entity.to_string())
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF Psets
+// ----------------------------------------------------------------
 
 /// Rust implementation of struct
 impl Psets {
-    fn rust_flatten(
+    /// Compares ignoring ids, including the ids stored as plain entries of (verbose) psets
+    pub fn eq_ignore_ids(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Pset(x), Self::Pset(y)) => x.eq_ignore_ids(y),
+            (Self::PsetId(x), Self::PsetId(y)) => x.eq_ignore_ids(y),
+            (Self::Quantity(x), Self::Quantity(y)) => x.eq_ignore_ids(y),
+            (Self::Property(x), Self::Property(y)) => x.eq_ignore_ids(y),
+            (Self::Complex(x), Self::Complex(y)) => x.eq_ignore_ids(y),
+            (Self::Nested { elements: x }, Self::Nested { elements: y }) => {
+                let is_id = |key: &String, value: &PsetNestedValue| {
+                    key == "id"
+                        && matches!(value, PsetNestedValue::Any(ValueWrap(Value::Number(_))))
+                };
+                let x: Vec<_> = x.iter().filter(|(key, value)| !is_id(key, value)).collect();
+                let y: IndexMap<_, _> =
+                    y.iter().filter(|(key, value)| !is_id(key, value)).collect();
+                return x.len() == y.len()
+                    && x.iter().all(|(key, value)| {
+                        y.get(key).is_some_and(|other| value.eq_ignore_ids(other))
+                    });
+            }
+            _ => false,
+        }
+    }
+
+    /// Splits an address (as produced by flatten) into its keys,
+    /// where a backslash escapes the delimiter or a backslash within a key
+    pub fn split_path(path: &str, delimiter: Option<&String>) -> Vec<String> {
+        let delimiter = delimiter.map_or_else(|| ":".to_string(), |x| x.clone());
+        let mut result: Vec<String> = vec![];
+        let mut key = String::new();
+        let mut rest = path;
+        while let Some(c) = rest.chars().next() {
+            if !delimiter.is_empty() && rest.starts_with(&delimiter) {
+                result.push(std::mem::take(&mut key));
+                rest = &rest[delimiter.len()..];
+            } else if c == '\\' && rest.len() > 1 {
+                let escaped = &rest[1..];
+                if !delimiter.is_empty() && escaped.starts_with(&delimiter) {
+                    key.push_str(&delimiter);
+                    rest = &escaped[delimiter.len()..];
+                } else {
+                    let next = escaped.chars().next().unwrap_or(c);
+                    key.push(next);
+                    rest = &escaped[next.len_utf8()..];
+                }
+            } else {
+                key.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+        result.push(key);
+        return result;
+    }
+
+    /// Escapes a key for use in an address, cf. split_path
+    pub fn escape_key(key: &str, delimiter: &str) -> String {
+        let result = key.replace('\\', "\\\\");
+        if delimiter.is_empty() {
+            return result;
+        }
+        return result.replace(delimiter, &format!("\\{}", delimiter));
+    }
+
+    /// Child entries, if the entity is nested or a complex property
+    pub fn children(&self) -> Option<&IndexMap<String, PsetNestedValue>> {
+        match self {
+            Self::Nested { elements } => Some(elements),
+            Self::Complex(x) => Some(&x.properties),
+            _ => None,
+        }
+    }
+
+    /// Mutable child entries, if the entity is nested or a complex property
+    pub fn children_mut(&mut self) -> Option<&mut IndexMap<String, PsetNestedValue>> {
+        match self {
+            Self::Nested { elements } => Some(elements),
+            Self::Complex(x) => Some(&mut x.properties),
+            _ => None,
+        }
+    }
+
+    /// Number of top-level entries (a single Pset or PsetId counts as one entry without key)
+    pub fn len(&self) -> usize {
+        match self.children() {
+            Some(elements) => elements.len(),
+            None => 1,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Converts the top-level entry at a position to a python (key, value) pair
+    fn item_to_python<'a>(
+        &self,
+        py: Python<'a>,
+        index: usize,
+    ) -> PyResult<Option<(Bound<'a, PyAny>, Bound<'a, PyAny>)>> {
+        match self.children() {
+            Some(elements) => match elements.get_index(index) {
+                Some((key, x)) => {
+                    let key = PyString::new(py, key).into_any();
+                    let value = x.to_python(py)?;
+                    return Ok(Some((key, value)));
+                }
+                None => {
+                    return Ok(None);
+                }
+            },
+            None => match index {
+                0 => {
+                    let key = py.None().into_bound(py);
+                    let value = PsetNestedValue::Psets(self.clone()).to_python(py)?;
+                    return Ok(Some((key, value)));
+                }
+                _ => {
+                    return Ok(None);
+                }
+            },
+        }
+    }
+
+    /// Gets the entry at an address
+    pub fn rust_get(&self, path: &str, delimiter: Option<&String>) -> Option<&PsetNestedValue> {
+        let keys = Self::split_path(path, delimiter);
+        return self.rust_get_keys(&keys);
+    }
+
+    /// Gets the entry at a sequence of keys
+    pub fn rust_get_keys(&self, keys: &[String]) -> Option<&PsetNestedValue> {
+        let (key, rest) = keys.split_first()?;
+        let value = self.children()?.get(key)?;
+        if rest.is_empty() {
+            return Some(value);
+        }
+        match value {
+            PsetNestedValue::Psets(psets) => psets.rust_get_keys(rest),
+            PsetNestedValue::Any(_) => None,
+        }
+    }
+
+    /// Sets the entry at an address, creating intermediate nested entries if missing
+    pub fn rust_set(
+        &mut self,
+        path: &str,
+        value: PsetNestedValue,
+        delimiter: Option<&String>,
+    ) -> Result<(), String> {
+        let keys = Self::split_path(path, delimiter);
+        return self.rust_set_keys(&keys, value);
+    }
+
+    /// Sets the entry at a sequence of keys, creating intermediate nested entries if missing
+    pub fn rust_set_keys(&mut self, keys: &[String], value: PsetNestedValue) -> Result<(), String> {
+        let (key, rest) = keys.split_first().ok_or("empty path".to_string())?;
+        let children = self
+            .children_mut()
+            .ok_or_else(|| format!("cannot set key '{}' on a non-nested entry", key))?;
+        if rest.is_empty() {
+            children.insert(key.clone(), value);
+            return Ok(());
+        }
+        let child = children.entry(key.clone()).or_insert_with(|| {
+            PsetNestedValue::Psets(Psets::Nested {
+                elements: IndexMap::new(),
+            })
+        });
+        match child {
+            PsetNestedValue::Psets(psets) => psets.rust_set_keys(rest, value),
+            PsetNestedValue::Any(_) => Err(format!("cannot set below key '{}' on a value", key)),
+        }
+    }
+
+    /// Removes and returns the entry at an address
+    pub fn rust_delete(
+        &mut self,
+        path: &str,
+        delimiter: Option<&String>,
+    ) -> Result<PsetNestedValue, String> {
+        let keys = Self::split_path(path, delimiter);
+        return self
+            .rust_delete_keys(&keys)
+            .ok_or_else(|| format!("no entry at '{}'", path));
+    }
+
+    /// Removes and returns the entry at a sequence of keys
+    pub fn rust_delete_keys(&mut self, keys: &[String]) -> Option<PsetNestedValue> {
+        let (key, rest) = keys.split_first()?;
+        let children = self.children_mut()?;
+        if rest.is_empty() {
+            return children.shift_remove(key);
+        }
+        match children.get_mut(key)? {
+            PsetNestedValue::Psets(psets) => psets.rust_delete_keys(rest),
+            PsetNestedValue::Any(_) => None,
+        }
+    }
+
+    /// Recursively sorts nested entries by key (entries otherwise keep insertion order)
+    pub fn sort_keys(&mut self) {
+        if let Some(children) = self.children_mut() {
+            children.sort_keys();
+            for x in children.values_mut() {
+                if let PsetNestedValue::Psets(psets) = x {
+                    psets.sort_keys();
+                }
+            }
+        }
+    }
+
+    pub fn rust_flatten(
         &self,
         delimiter: Option<&String>,
         addr: Option<&String>,
-    ) -> HashMap<String, PsetFlattenedValue> {
-        let mut result = HashMap::<String, PsetFlattenedValue>::new();
+    ) -> IndexMap<String, PsetFlattenedValue> {
+        let mut result = IndexMap::<String, PsetFlattenedValue>::new();
         let delimiter = delimiter.map_or_else(|| ":".to_string(), |x| x.clone());
         let prefix = addr.map_or_else(|| "".to_string(), |x| format!("{}{}", x, delimiter));
         let addr = addr.map_or_else(|| "".to_string(), |x| x.clone());
@@ -369,9 +1234,26 @@ impl Psets {
             Psets::PsetId(x) => {
                 result.insert(addr, PsetFlattenedValue::PsetId(x.clone()));
             }
-            Psets::Nested(object) => {
-                for (key, x) in object.iter() {
-                    let subaddr = format!("{}{}", prefix, key);
+            Psets::Quantity(x) => {
+                result.insert(addr, PsetFlattenedValue::Quantity(x.clone()));
+            }
+            Psets::Property(x) => {
+                result.insert(addr, PsetFlattenedValue::Property(x.clone()));
+            }
+            Psets::Complex(_) | Psets::Nested { .. } => {
+                match self {
+                    Psets::Complex(x) => {
+                        result.insert(addr, PsetFlattenedValue::Complex(x.header()));
+                    }
+                    // NOTE: empty sets are kept as empty maps, so that unflatten restores them
+                    Psets::Nested { elements } if elements.is_empty() && !addr.is_empty() => {
+                        let value = ValueWrap(Value::Object(Map::new()));
+                        result.insert(addr, PsetFlattenedValue::Value(value));
+                    }
+                    _ => {}
+                }
+                for (key, x) in self.children().into_iter().flatten() {
+                    let subaddr = format!("{}{}", prefix, Self::escape_key(key, &delimiter));
                     match x {
                         PsetNestedValue::Psets(psets) => {
                             let subresult = psets.rust_flatten(Some(&delimiter), Some(&subaddr));
@@ -387,6 +1269,115 @@ impl Psets {
         return result;
     }
 
+    /// Rebuilds a nested structure from the output of flatten
+    pub fn rust_unflatten(
+        elements: &IndexMap<String, PsetFlattenedValue>,
+        delimiter: Option<&String>,
+    ) -> Result<Self, String> {
+        // a single entry without an address is the root itself,
+        // unless it is a complex property whose children follow
+        if let Some(PsetFlattenedValue::Complex(x)) = elements.get("") {
+            let mut result = Self::Complex(x.clone());
+            for (addr, x) in elements.iter().filter(|(addr, _)| !addr.is_empty()) {
+                result.rust_set(addr, x.to_nested(), delimiter)?;
+            }
+            return Ok(result);
+        }
+        if let Some(x) = elements.get("") {
+            if elements.len() > 1 {
+                return Err("root address cannot be combined with other addresses".to_string());
+            }
+            match x {
+                PsetFlattenedValue::Pset(x) => {
+                    return Ok(Self::Pset(x.clone()));
+                }
+                PsetFlattenedValue::PsetId(x) => {
+                    return Ok(Self::PsetId(x.clone()));
+                }
+                PsetFlattenedValue::Quantity(x) => {
+                    return Ok(Self::Quantity(x.clone()));
+                }
+                PsetFlattenedValue::Property(x) => {
+                    return Ok(Self::Property(x.clone()));
+                }
+                PsetFlattenedValue::Complex(_) | PsetFlattenedValue::Value(_) => {
+                    return Err(
+                        "root address must hold a Pset, PsetId, quantity or property".to_string(),
+                    );
+                }
+            }
+        }
+
+        let mut result = Self::Nested {
+            elements: IndexMap::new(),
+        };
+        for (addr, x) in elements.iter() {
+            result.rust_set(addr, x.to_nested(), delimiter)?;
+        }
+        return Ok(result);
+    }
+
+    /// Visits all nodes (including the root) lazily with their path and depth;
+    /// leaves are entries without children, except for (empty) nested entries
+    pub fn rust_walk(
+        &self,
+        order: TraversalOrder,
+        max_depth: Option<usize>,
+        leaves_only: bool,
+    ) -> PsetsWalkIter<'_> {
+        let walk = PsetsWalk::new(order, max_depth, leaves_only);
+        return PsetsWalkIter { entity: self, walk };
+    }
+
+    /// Converts the values of single-value properties and quantities with a unit
+    /// from the units of `units` into those of `target`
+    pub fn rust_convert_units(
+        &self,
+        units: &UnitAssignment,
+        target: &UnitAssignment,
+    ) -> Result<Self, String> {
+        let symbol_of = |value_type: &str| target.unit_of(value_type).map(|x| x.symbol);
+        match self {
+            Self::Pset(x) => match x.value_type.as_deref().and_then(symbol_of) {
+                Some(symbol) => Ok(Self::Pset(x.rust_convert_to(&symbol, units)?)),
+                None => Ok(self.clone()),
+            },
+            Self::Quantity(x) => match x.dimension().ok().and_then(|x| symbol_of(x.value_type())) {
+                Some(symbol) => Ok(Self::Quantity(x.rust_convert_to(&symbol, units)?)),
+                None => Ok(self.clone()),
+            },
+            Self::PsetId(_) | Self::Property(_) => Ok(self.clone()),
+            Self::Complex(_) | Self::Nested { .. } => {
+                let mut result = self.clone();
+                for x in result
+                    .children_mut()
+                    .into_iter()
+                    .flat_map(|x| x.values_mut())
+                {
+                    if let PsetNestedValue::Psets(psets) = x {
+                        *psets = psets.rust_convert_units(units, target)?;
+                    }
+                }
+                return Ok(result);
+            }
+        }
+    }
+
+    /// Resolves all reference properties, keyed by their flattened path
+    pub fn rust_resolve_references<T>(
+        &self,
+        resolver: &mut impl ReferenceResolver<T>,
+        delimiter: Option<&String>,
+    ) -> Result<IndexMap<String, Option<T>>, String> {
+        let mut result = IndexMap::<String, Option<T>>::new();
+        for (path, x) in self.rust_flatten(delimiter, None) {
+            if let PsetFlattenedValue::Property(PsetProperty::Reference(x)) = x {
+                result.insert(path, x.rust_resolve(resolver)?);
+            }
+        }
+        return Ok(result);
+    }
+
     fn as_tree(&self, addr: Option<String>) -> GenericTree<PsetFlattenedValueWithAddress> {
         match self {
             Self::Pset(x) => {
@@ -403,10 +1394,28 @@ impl Psets {
                 };
                 return GenericTree::new(node, None);
             }
-            Self::Nested(elements) => {
-                let node = PsetFlattenedValueWithAddress { addr, entity: None };
+            Self::Quantity(x) => {
+                let node = PsetFlattenedValueWithAddress {
+                    addr,
+                    entity: Some(PsetFlattenedValue::Quantity(x.clone())),
+                };
+                return GenericTree::new(node, None);
+            }
+            Self::Property(x) => {
+                let node = PsetFlattenedValueWithAddress {
+                    addr,
+                    entity: Some(PsetFlattenedValue::Property(x.clone())),
+                };
+                return GenericTree::new(node, None);
+            }
+            Self::Complex(_) | Self::Nested { .. } => {
+                let entity = match self {
+                    Self::Complex(x) => Some(PsetFlattenedValue::Complex(x.header())),
+                    _ => None,
+                };
+                let node = PsetFlattenedValueWithAddress { addr, entity };
                 let mut t = GenericTree::new(node, None);
-                for (subaddr, x) in elements.iter() {
+                for (subaddr, x) in self.children().into_iter().flatten() {
                     match x {
                         PsetNestedValue::Psets(psets) => {
                             let child = psets.as_tree(Some(subaddr.clone()));
@@ -440,8 +1449,268 @@ impl Psets {
         return Ok(result);
     }
 
-    fn __iter__(&self) -> PsetsIterator {
-        return PsetsIterator::new(self).unwrap();
+    fn __iter__(slf: PyRef<'_, Self>) -> PsetsIterator {
+        return PsetsIterator::new(slf.into());
+    }
+
+    #[pyo3(signature = (/, *, order="pre".to_string(), max_depth=None, leaves_only=false))]
+    fn walk(
+        slf: PyRef<'_, Self>,
+        order: String,
+        max_depth: Option<usize>,
+        leaves_only: bool,
+    ) -> PyResult<PsetsWalkIterator> {
+        let order = TraversalOrder::from_name(&order).map_err(err_to_py_string)?;
+        let result = PsetsWalkIterator {
+            entity: slf.into(),
+            walk: PsetsWalk::new(order, max_depth, leaves_only),
+        };
+        return Ok(result);
+    }
+
+    #[pyo3(signature = (other, /, *, ignore_ids=false, delimiter=":".to_string()))]
+    fn diff(&self, other: &Self, ignore_ids: bool, delimiter: String) -> PsetsDiff {
+        PsetsDiff::new(self, other, ignore_ids, Some(&delimiter))
+    }
+
+    #[pyo3(signature = (other, /, *, strategy=None, ignore_ids=false))]
+    fn merge(
+        &self,
+        py: Python<'_>,
+        other: &Self,
+        strategy: Option<&Bound<'_, PyAny>>,
+        ignore_ids: bool,
+    ) -> PyResult<Self> {
+        let Some(strategy) = strategy else {
+            let result = merge_psets(self, other, ignore_ids, &mut MergeStrategy::PreferRight)
+                .map_err(err_to_py_string)?;
+            return Ok(result);
+        };
+        if let Ok(name) = strategy.extract::<String>() {
+            let mut strategy = MergeStrategy::from_name(&name).map_err(err_to_py_string)?;
+            let result =
+                merge_psets(self, other, ignore_ids, &mut strategy).map_err(err_to_py_string)?;
+            return Ok(result);
+        }
+
+        // NOTE: keep the python error raised by the callback, so that it can be re-raised
+        let mut error: Option<PyErr> = None;
+        let mut callback = |path: &[String], x: &PsetNestedValue, y: &PsetNestedValue| {
+            let outcome = PyTuple::new(py, path)
+                .and_then(|path| strategy.call1((path, x.to_python(py)?, y.to_python(py)?)))
+                .and_then(|value| PsetNestedValue::from_python(&value));
+            return outcome.map_err(|err| {
+                let message = err.to_string();
+                error = Some(err);
+                return message;
+            });
+        };
+        let outcome = merge_psets(
+            self,
+            other,
+            ignore_ids,
+            &mut MergeStrategy::Callback(&mut callback),
+        );
+        match (outcome, error) {
+            (_, Some(err)) => Err(err),
+            (Ok(result), None) => Ok(result),
+            (Err(err), None) => Err(err_to_py_string(err)),
+        }
+    }
+
+    #[pyo3(signature = (other, /))]
+    fn to_patch<'a>(&self, py: Python<'a>, other: &Self) -> PyResult<Bound<'a, PyAny>> {
+        let old = self.to_json().map_err(err_to_py_string)?;
+        let new = other.to_json().map_err(err_to_py_string)?;
+        let patch = JsonPatch::new(&old, &new);
+        let raw = patch.to_json().map_err(err_to_py_string)?;
+        return ValueWrap(raw).into_pyobject(py);
+    }
+
+    #[pyo3(signature = (patch, /))]
+    fn apply_patch(&self, patch: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let raw = ValueWrap::extract_bound(patch)?;
+        let patch = JsonPatch::from_json(&raw.to_json().map_err(err_to_py_string)?)
+            .map_err(err_to_py_string)?;
+        let value = self.to_json().map_err(err_to_py_string)?;
+        let value = patch.apply(&value).map_err(err_to_py_string)?;
+        let result = Self::from_json(&value).map_err(err_to_py_string)?;
+        return Ok(result);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (pattern, /, *, regex=false, class_=None, value_type=None, value=None, delimiter=":".to_string()))]
+    fn select<'a>(
+        &self,
+        py: Python<'a>,
+        pattern: String,
+        regex: bool,
+        class_: Option<Bound<'a, PyAny>>,
+        value_type: Option<Bound<'a, PyAny>>,
+        value: Option<Bound<'a, PyAny>>,
+        delimiter: String,
+    ) -> PyResult<Vec<Bound<'a, PyTuple>>> {
+        let pattern = match regex {
+            true => PathPattern::regex(&pattern),
+            false => PathPattern::glob(&pattern, &delimiter),
+        }
+        .map_err(err_to_py_string)?;
+        let mut selector = PsetsSelector::new(pattern);
+
+        // NOTE: callables are applied as predicates in python, anything else is compared in rust
+        let class_ = class_.filter(|x| !x.is_none());
+        let value_type = value_type.filter(|x| !x.is_none());
+        let value = value.filter(|x| !x.is_none());
+        if let Some(x) = class_.as_ref().filter(|x| !x.is_callable()) {
+            selector.class_ = Some(x.extract::<String>()?);
+        }
+        if let Some(x) = value_type.as_ref().filter(|x| !x.is_callable()) {
+            selector.value_type = Some(x.extract::<String>()?);
+        }
+        if let Some(x) = value.as_ref().filter(|x| !x.is_callable()) {
+            selector.value = Some(
+                ValueWrap::extract_bound(x)?
+                    .to_json()
+                    .map_err(err_to_py_string)?,
+            );
+        }
+        let predicates = [class_, value_type, value].map(|x| x.filter(|x| x.is_callable()));
+
+        let mut result = vec![];
+        for (path, x) in selector.select(self, Some(&delimiter)).iter() {
+            let (class_, value_type, value) = entity_parts(x);
+            let arguments = [
+                class_.map(|x| ValueWrap(Value::String(x))),
+                value_type.map(|x| ValueWrap(Value::String(x))),
+                value.map(ValueWrap),
+            ];
+            let mut matched = true;
+            for (predicate, argument) in predicates.iter().zip(arguments) {
+                let Some(predicate) = predicate else {
+                    continue;
+                };
+                let argument = argument.unwrap_or(ValueWrap(Value::Null)).to_python(py)?;
+                if !predicate.call1((argument,))?.is_truthy()? {
+                    matched = false;
+                    break;
+                }
+            }
+            if matched {
+                let key = PyString::new(py, path).into_any();
+                result.push(PyTuple::new(py, vec![key, x.to_python(py)?])?);
+            }
+        }
+        return Ok(result);
+    }
+
+    /// Checks the sets against a template library (the bundled one of `schema` by default),
+    /// and that they apply to the entity class (if given)
+    #[pyo3(signature = (entity_class=None, *, schema="IFC4X3".to_string(), library=None, delimiter=":".to_string()))]
+    fn validate_against_templates(
+        &self,
+        entity_class: Option<String>,
+        schema: String,
+        library: Option<PyRef<'_, PsetTemplateLibrary>>,
+        delimiter: String,
+    ) -> PyResult<PsetsValidation> {
+        let library = match &library {
+            Some(x) => x,
+            None => PsetTemplateLibrary::bundled(&schema).map_err(err_to_py_string)?,
+        };
+        let result = library.rust_validate(self, entity_class.as_deref(), Some(&delimiter));
+        return Ok(result);
+    }
+
+    /// Converts measure values from the units of `units` (or else SI units) into those of `target`
+    #[pyo3(signature = (target, /, *, units=None))]
+    fn convert_units(
+        &self,
+        target: UnitAssignment,
+        units: Option<UnitAssignment>,
+    ) -> PyResult<Self> {
+        let units = units.unwrap_or_default();
+        return self
+            .rust_convert_units(&units, &target)
+            .map_err(err_to_py_string);
+    }
+
+    /// Resolves all reference properties through a mapping from ids to objects or a callable
+    #[pyo3(signature = (resolver, /, *, delimiter=":".to_string()))]
+    fn resolve_references<'a>(
+        &self,
+        py: Python<'a>,
+        resolver: &Bound<'a, PyAny>,
+        delimiter: String,
+    ) -> PyResult<IndexMap<String, Bound<'a, PyAny>>> {
+        // NOTE: keep the python error raised by the resolver, so that it can be re-raised
+        let mut error: Option<PyErr> = None;
+        let mut callback = |x: &PsetReferenceValue| {
+            return resolve_with_python(resolver, x).map_err(|err| {
+                let message = err.to_string();
+                error = Some(err);
+                return message;
+            });
+        };
+        let outcome = self.rust_resolve_references(&mut callback, Some(&delimiter));
+        let result = match (outcome, error) {
+            (_, Some(err)) => Err(err),
+            (Ok(result), None) => Ok(result),
+            (Err(err), None) => Err(err_to_py_string(err)),
+        }?;
+        let result = result
+            .into_iter()
+            .map(|(path, x)| (path, x.unwrap_or_else(|| py.None().into_bound(py))))
+            .collect();
+        return Ok(result);
+    }
+
+    fn __len__(&self) -> usize {
+        self.len()
+    }
+
+    fn __contains__(&self, key: String) -> bool {
+        self.children().is_some_and(|x| x.contains_key(&key))
+    }
+
+    fn __getitem__<'a>(&self, py: Python<'a>, key: String) -> PyResult<Bound<'a, PyAny>> {
+        match self.children().and_then(|x| x.get(&key)) {
+            Some(value) => {
+                return value.to_python(py);
+            }
+            None => {
+                return Err(err_to_py_key_error(key));
+            }
+        }
+    }
+
+    fn keys<'a>(&self, py: Python<'a>) -> PyResult<Vec<Bound<'a, PyAny>>> {
+        let mut result = vec![];
+        for index in 0..self.len() {
+            if let Some((key, _)) = self.item_to_python(py, index)? {
+                result.push(key);
+            }
+        }
+        return Ok(result);
+    }
+
+    fn values<'a>(&self, py: Python<'a>) -> PyResult<Vec<Bound<'a, PyAny>>> {
+        let mut result = vec![];
+        for index in 0..self.len() {
+            if let Some((_, value)) = self.item_to_python(py, index)? {
+                result.push(value);
+            }
+        }
+        return Ok(result);
+    }
+
+    fn items<'a>(&self, py: Python<'a>) -> PyResult<Vec<Bound<'a, PyTuple>>> {
+        let mut result = vec![];
+        for index in 0..self.len() {
+            if let Some((key, value)) = self.item_to_python(py, index)? {
+                result.push(PyTuple::new(py, vec![key, value])?);
+            }
+        }
+        return Ok(result);
     }
 
     #[staticmethod]
@@ -458,16 +1727,32 @@ impl Psets {
         return Ok(result);
     }
 
+    fn __eq__(&self, other: PyRef<'_, Self>) -> bool {
+        self == &*other
+    }
+
+    fn __hash__(&self) -> u64 {
+        hash_of(self)
+    }
+
+    #[pyo3(signature = (other, /, *, ignore_ids=false))]
+    fn equals(&self, other: PyRef<'_, Self>, ignore_ids: bool) -> bool {
+        match ignore_ids {
+            true => self.eq_ignore_ids(&other),
+            false => self == &*other,
+        }
+    }
+
     pub fn __str__(&self) -> PyResult<String> {
         Ok(self.to_string())
     }
 
     #[pyo3(signature = (/, *, delimiter=":".to_string()))]
-    fn flatten<'a>(
+    pub fn flatten<'a>(
         &self,
         py: Python<'a>,
         delimiter: String,
-    ) -> PyResult<HashMap<String, Bound<'a, PyAny>>> {
+    ) -> PyResult<IndexMap<String, Bound<'a, PyAny>>> {
         let elements = self
             .rust_flatten(Some(&delimiter), None)
             .iter()
@@ -480,6 +1765,20 @@ impl Psets {
                     let value = x.into_pyobject(py).unwrap();
                     return (key.clone(), value);
                 }
+                PsetFlattenedValue::Quantity(x) => {
+                    let value = x.into_pyobject(py).unwrap();
+                    return (key.clone(), value);
+                }
+                PsetFlattenedValue::Property(x) => {
+                    let value = ValueWrap(x.to_json().unwrap());
+                    let value = value.into_pyobject(py).unwrap();
+                    return (key.clone(), value);
+                }
+                PsetFlattenedValue::Complex(x) => {
+                    let value = ValueWrap(x.to_json().unwrap());
+                    let value = value.into_pyobject(py).unwrap();
+                    return (key.clone(), value);
+                }
                 PsetFlattenedValue::Value(value) => {
                     let value = value.clone();
                     let value = value.into_pyobject(py).unwrap();
@@ -490,29 +1789,117 @@ impl Psets {
         return Ok(elements);
     }
 
-    // #[pyo3(signature = ())]
-    // fn walk<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
-    //     match self {
-    //         Psets::Pset(x) => {
-    //             let key = ValueWrap(Value::Null).into_pyobject(py)?;
-    //             let value = x.into_pyobject(py)?;
-    //             let pair = PyTuple::new(py, &vec![key, value]);
-    //             return PyList::new(py, &vec![pair]);
-    //         }
-    //         Psets::PsetId(x) => {
-    //             let key = ValueWrap(Value::Null).into_pyobject(py)?;
-    //             let value = x.into_pyobject(py)?;
-    //             let pair = PyTuple::new(py, &vec![key, value]);
-    //             return pair;
-    //         }
-    //         Psets::Nested(elements) => {
-    //             let key = ValueWrap(Value::Null).into_pyobject(py)?;
-    //             let value = x.into_pyobject(py)?;
-    //             let pair = PyTuple::new(py, &vec![key, value]);
-    //             return pair;
-    //         }
-    //     }
-    // }
+    fn sorted(&self) -> Self {
+        let mut result = self.clone();
+        result.sort_keys();
+        return result;
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (elements, /, *, delimiter=":".to_string()))]
+    fn unflatten(
+        elements: IndexMap<String, Bound<'_, PyAny>>,
+        delimiter: String,
+    ) -> PyResult<Self> {
+        let mut values = IndexMap::<String, PsetFlattenedValue>::new();
+        for (key, x) in elements.iter() {
+            values.insert(key.clone(), PsetFlattenedValue::from_python(x)?);
+        }
+        let result = Self::rust_unflatten(&values, Some(&delimiter)).map_err(err_to_py_string)?;
+        return Ok(result);
+    }
+
+    /// Entry at a path, split on the delimiter (unlike `[]`, which takes a single key)
+    #[pyo3(signature = (path, default=None, /, *, delimiter=":".to_string()))]
+    fn get<'a>(
+        &self,
+        py: Python<'a>,
+        path: String,
+        default: Option<Bound<'a, PyAny>>,
+        delimiter: String,
+    ) -> PyResult<Bound<'a, PyAny>> {
+        match self.rust_get(&path, Some(&delimiter)) {
+            Some(value) => {
+                return value.to_python(py);
+            }
+            None => {
+                let result = default.unwrap_or_else(|| py.None().into_bound(py));
+                return Ok(result);
+            }
+        }
+    }
+
+    #[pyo3(signature = (path, value, /, *, delimiter=":".to_string()))]
+    fn with_value(
+        &self,
+        path: String,
+        value: &Bound<'_, PyAny>,
+        delimiter: String,
+    ) -> PyResult<Self> {
+        let value = PsetNestedValue::from_python(value)?;
+        let mut result = self.clone();
+        result
+            .rust_set(&path, value, Some(&delimiter))
+            .map_err(err_to_py_string)?;
+        return Ok(result);
+    }
+
+    #[pyo3(signature = (path, /, *, delimiter=":".to_string()))]
+    fn without(&self, path: String, delimiter: String) -> PyResult<Self> {
+        let mut result = self.clone();
+        result
+            .rust_delete(&path, Some(&delimiter))
+            .map_err(err_to_py_key_error)?;
+        return Ok(result);
+    }
+
+    /// Same as `with_value`, Psets being immutable the updated copy is returned
+    #[pyo3(signature = (path, value, /, *, delimiter=":".to_string()))]
+    fn set(&self, path: String, value: &Bound<'_, PyAny>, delimiter: String) -> PyResult<Self> {
+        return self.with_value(path, value, delimiter);
+    }
+
+    /// Same as `without`, Psets being immutable the updated copy is returned
+    #[pyo3(signature = (path, /, *, delimiter=":".to_string()))]
+    fn delete(&self, path: String, delimiter: String) -> PyResult<Self> {
+        return self.without(path, delimiter);
+    }
+
+    /// Entity lines (`#n=...;`) of the sets and their relationships to an element
+    #[pyo3(signature = (element_id, allocator, /, *, schema="IFC4".to_string(), owner_history=None))]
+    fn to_step(
+        &self,
+        element_id: i64,
+        mut allocator: PyRefMut<'_, StepIdAllocator>,
+        schema: String,
+        owner_history: Option<i64>,
+    ) -> PyResult<Vec<String>> {
+        let mut writer = StepPsetsWriter::new(&mut allocator, &schema, owner_history)
+            .map_err(err_to_py_string)?;
+        writer
+            .write(self, &[element_id])
+            .map_err(err_to_py_string)?;
+        return Ok(writer.lines);
+    }
+}
+
+impl Hash for Psets {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Self::Pset(x) => x.hash(state),
+            Self::PsetId(x) => x.hash(state),
+            Self::Quantity(x) => x.hash(state),
+            Self::Property(x) => x.hash(state),
+            Self::Complex(x) => x.hash(state),
+            Self::Nested { elements } => {
+                // NOTE: sorted, consistent with equality which ignores the order of entries
+                let mut items: Vec<_> = elements.iter().collect();
+                items.sort_unstable_by(|a, b| a.0.cmp(b.0));
+                items.hash(state);
+            }
+        }
+    }
 }
 
 impl ToString for Psets {
@@ -524,23 +1911,31 @@ impl ToString for Psets {
 
 impl JsonConversion<Value> for Psets {
     fn from_json(value: &Value) -> Result<Self, String> {
-        if let Ok(x) = Pset::from_json(value) {
+        if PsetComplexProperty::is_complex_property(value) {
+            let x = PsetComplexProperty::from_json(value)?;
+            return Ok(Self::Complex(x));
+        } else if Quantity::is_quantity(value) {
+            let x = Quantity::from_json(value)?;
+            return Ok(Self::Quantity(x));
+        } else if PsetProperty::is_typed_property(value) {
+            let x = PsetProperty::from_json(value)?;
+            return Ok(Self::Property(x));
+        } else if is_property_like(value) {
+            let x = Pset::from_json(value)?;
             return Ok(Self::Pset(x));
         } else if let Ok(x) = PsetId::from_json(value) {
             return Ok(Self::PsetId(x));
         } else if let Value::Object(object) = value {
-            let elements: HashMap<String, PsetNestedValue> = object
+            let elements: Result<IndexMap<String, PsetNestedValue>, String> = object
                 .iter()
                 .map(|(key, x)| {
-                    if let Ok(value) = PsetNestedValue::from_json(x) {
-                        return (key.clone(), value);
-                    }
-                    let value = ValueWrap::from_json(x).unwrap();
-                    let value = PsetNestedValue::Any(value);
-                    return (key.clone(), value);
+                    let value = PsetNestedValue::from_json(x)?;
+                    return Ok((key.clone(), value));
                 })
                 .collect();
-            return Ok(Self::Nested(elements));
+            return Ok(Self::Nested {
+                elements: elements?,
+            });
         } else {
             return Err("invalid type".to_string());
         }
@@ -554,7 +1949,16 @@ impl JsonConversion<Value> for Psets {
             Self::PsetId(x) => {
                 return x.to_json();
             }
-            Self::Nested(elements) => {
+            Self::Quantity(x) => {
+                return x.to_json();
+            }
+            Self::Property(x) => {
+                return x.to_json();
+            }
+            Self::Complex(x) => {
+                return x.to_json();
+            }
+            Self::Nested { elements } => {
                 let items: Map<String, Value> = elements
                     .iter()
                     .map(|(key, x)| return (key.clone(), x.to_json().unwrap()))
@@ -566,60 +1970,176 @@ impl JsonConversion<Value> for Psets {
     }
 }
 
-#[pymethods]
 impl PsetsIterator {
-    #[new]
-    #[pyo3(signature = (entity, /))]
-    fn new(entity: &Psets) -> PyResult<Self> {
-        let entity = entity.clone();
-        let result = Self { index: 0, entity };
-        return Ok(result);
+    fn new(entity: Py<Psets>) -> Self {
+        return Self { entity, index: 0 };
     }
+}
 
-    fn __iter__<'py>(slf: PyRef<Self>) -> PyRef<Self> {
+#[pymethods]
+impl PsetsIterator {
+    fn __iter__(slf: PyRef<Self>) -> PyRef<Self> {
         slf
     }
 
-    fn __next__<'py>(mut slf: PyRefMut<'py, Self>, py: Python<'py>) -> Option<Bound<'py, PyTuple>> {
-        let result;
+    fn __next__<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        py: Python<'py>,
+    ) -> PyResult<Option<Bound<'py, PyTuple>>> {
         let index = slf.index;
-        match &slf.entity {
-            Psets::Pset(x) => match index {
-                0 => {
-                    let key = ValueWrap(Value::Null).into_pyobject(py).unwrap().into_any();
-                    let value = x.clone().into_pyobject(py).unwrap().into_any();
-                    let pair = PyTuple::new(py, vec![key, value]).unwrap();
-                    result = Some(pair);
-                }
-                _ => {
-                    result = None;
-                }
-            },
-            Psets::PsetId(x) => match index {
-                0 => {
-                    let key = ValueWrap(Value::Null).into_pyobject(py).unwrap().into_any();
-                    let value = x.clone().into_pyobject(py).unwrap().into_any();
-                    let pair = PyTuple::new(py, vec![key, value]).unwrap();
-                    result = Some(pair);
+        slf.index += 1;
+        // NOTE: Psets is frozen, so the entity can be read without cloning
+        match slf.entity.get().item_to_python(py, index)? {
+            Some((key, value)) => {
+                let pair = PyTuple::new(py, vec![key, value])?;
+                return Ok(Some(pair));
+            }
+            None => {
+                return Ok(None);
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF PsetsWalk
+// ----------------------------------------------------------------
+
+/// Rust implementation of struct
+impl PsetsWalk {
+    pub fn new(order: TraversalOrder, max_depth: Option<usize>, leaves_only: bool) -> Self {
+        return Self {
+            order,
+            max_depth,
+            leaves_only,
+            stack: vec![(vec![], false, 0)],
+        };
+    }
+
+    /// Next node of the walk over `root` (which must be the same entity on every call)
+    pub fn next_step(&mut self, root: &Psets) -> Option<PsetsWalkStep> {
+        loop {
+            let (path, entered, index) = self.stack.last()?.clone();
+            let node = match root.rust_get_keys(&path) {
+                // NOTE: the root has no path, so it cannot be resolved as an entry
+                None if path.is_empty() => PsetsWalkNode::Psets(root),
+                Some(PsetNestedValue::Psets(x)) => PsetsWalkNode::Psets(x),
+                Some(PsetNestedValue::Any(x)) => PsetsWalkNode::Value(x),
+                None => {
+                    self.stack.pop();
+                    continue;
                 }
-                _ => {
-                    result = None;
+            };
+            if !entered {
+                self.stack.last_mut()?.1 = true;
+                if self.order == TraversalOrder::PreOrder && self.includes(&node) {
+                    return Some(Self::step(path, &node));
                 }
-            },
-            Psets::Nested(elements) => {
-                result = elements.iter().nth(index).map(|(key, x)| {
-                    let key = ValueWrap(Value::String(key.clone()))
-                        .into_pyobject(py)
-                        .unwrap()
-                        .into_any();
-                    let value = x.clone().into_pyobject(py).unwrap().into_any();
-                    let pair = PyTuple::new(py, vec![key, value]).unwrap();
-                    return pair;
-                });
+            }
+            let descend = self.max_depth.is_none_or(|n| path.len() < n);
+            let child = match node {
+                PsetsWalkNode::Psets(x) if descend => x
+                    .children()
+                    .and_then(|x| x.get_index(index))
+                    .map(|(key, _)| key.clone()),
+                _ => None,
+            };
+            if let Some(key) = child {
+                self.stack.last_mut()?.2 += 1;
+                let mut subpath = path;
+                subpath.push(key);
+                self.stack.push((subpath, false, 0));
+                continue;
+            }
+            self.stack.pop();
+            if self.order == TraversalOrder::PostOrder && self.includes(&node) {
+                return Some(Self::step(path, &node));
             }
         }
-        slf.index += 1;
+    }
 
-        return result;
+    fn includes(&self, node: &PsetsWalkNode) -> bool {
+        if !self.leaves_only {
+            return true;
+        }
+        match node {
+            PsetsWalkNode::Psets(Psets::Nested { .. }) => false,
+            PsetsWalkNode::Psets(x) => x.children().is_none_or(|x| x.is_empty()),
+            PsetsWalkNode::Value(_) => true,
+        }
+    }
+
+    fn step(path: Vec<String>, node: &PsetsWalkNode) -> PsetsWalkStep {
+        let entity = match node {
+            PsetsWalkNode::Psets(Psets::Pset(x)) => Some(PsetFlattenedValue::Pset(x.clone())),
+            PsetsWalkNode::Psets(Psets::PsetId(x)) => Some(PsetFlattenedValue::PsetId(x.clone())),
+            PsetsWalkNode::Psets(Psets::Quantity(x)) => {
+                Some(PsetFlattenedValue::Quantity(x.clone()))
+            }
+            PsetsWalkNode::Psets(Psets::Property(x)) => {
+                Some(PsetFlattenedValue::Property(x.clone()))
+            }
+            PsetsWalkNode::Psets(Psets::Complex(x)) => {
+                Some(PsetFlattenedValue::Complex(x.header()))
+            }
+            PsetsWalkNode::Psets(Psets::Nested { .. }) => None,
+            PsetsWalkNode::Value(x) => Some(PsetFlattenedValue::Value((*x).clone())),
+        };
+        let depth = path.len();
+        return PsetsWalkStep {
+            path,
+            depth,
+            entity,
+        };
+    }
+}
+
+impl Iterator for PsetsWalkIter<'_> {
+    type Item = PsetsWalkStep;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.walk.next_step(self.entity)
+    }
+}
+
+#[pymethods]
+impl PsetsWalkIterator {
+    fn __iter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    fn __next__<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        py: Python<'py>,
+    ) -> PyResult<Option<Bound<'py, PyTuple>>> {
+        // NOTE: Psets is frozen, so the entity can be read without cloning
+        let this = &mut *slf;
+        let Some(step) = this.walk.next_step(this.entity.get()) else {
+            return Ok(None);
+        };
+        let node = match &step.entity {
+            Some(x) => x.to_python(py)?,
+            None => match slf.entity.get().rust_get_keys(&step.path) {
+                Some(x) => x.to_python(py)?,
+                None => Bound::new(py, slf.entity.get().clone())?.into_any(),
+            },
+        };
+        let path = PyTuple::new(py, step.path)?.into_any();
+        let depth = step.depth.into_bound_py_any(py)?;
+        let result = PyTuple::new(py, vec![path, depth, node])?;
+        return Ok(Some(result));
+    }
+}
+
+// ----------------------------------------------------------------
+// AUXILIARY METHODS
+// ----------------------------------------------------------------
+
+/// Objects with both an id and a class are parsed strictly as properties,
+/// so that invalid properties are reported rather than read as nested entries
+fn is_property_like(value: &Value) -> bool {
+    match value {
+        Value::Object(object) => object.contains_key("id") && object.contains_key("class"),
+        _ => false,
     }
 }
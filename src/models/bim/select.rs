@@ -0,0 +1,168 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use regex::Regex;
+use serde_json::Value;
+use std::result::Result;
+
+use super::psets::PsetFlattenedValue;
+use super::psets::Psets;
+use crate::_core::errors::err_to_string;
+use crate::models::json::model::ValueWrap;
+
+// ----------------------------------------------------------------
+// STRUCTS
+// ----------------------------------------------------------------
+
+/// Part of a glob pattern between two delimiters
+pub enum GlobSegment {
+    /// `**`, matching any number of segments
+    AnyDepth,
+    /// pattern of a single segment, where `*` matches any characters and `?` a single one
+    Segment(Regex),
+}
+
+/// Pattern matched against flattened addresses
+pub enum PathPattern {
+    Glob(Vec<GlobSegment>),
+    Regex(Regex),
+}
+
+/// Query over the flattened entries of Psets
+pub struct PsetsSelector {
+    pub pattern: PathPattern,
+    pub class_: Option<String>,
+    pub value_type: Option<String>,
+    pub value: Option<Value>,
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF PathPattern
+// ----------------------------------------------------------------
+
+impl PathPattern {
+    pub fn glob(pattern: &str, delimiter: &str) -> Result<Self, String> {
+        let segments: Result<Vec<GlobSegment>, String> = pattern
+            .split(delimiter)
+            .map(|part| {
+                if part == "**" {
+                    return Ok(GlobSegment::AnyDepth);
+                }
+                let expr: String = part
+                    .chars()
+                    .map(|c| match c {
+                        '*' => ".*".to_string(),
+                        '?' => ".".to_string(),
+                        _ => regex::escape(&c.to_string()),
+                    })
+                    .collect();
+                let expr = Regex::new(&format!("^{}$", expr)).map_err(err_to_string)?;
+                return Ok(GlobSegment::Segment(expr));
+            })
+            .collect();
+        return Ok(Self::Glob(segments?));
+    }
+
+    pub fn regex(pattern: &str) -> Result<Self, String> {
+        let expr = Regex::new(pattern).map_err(err_to_string)?;
+        return Ok(Self::Regex(expr));
+    }
+
+    pub fn matches(&self, path: &str, delimiter: &str) -> bool {
+        match self {
+            Self::Glob(segments) => {
+                let parts: Vec<&str> = path.split(delimiter).collect();
+                return match_segments(segments, &parts);
+            }
+            Self::Regex(expr) => expr.is_match(path),
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF PsetsSelector
+// ----------------------------------------------------------------
+
+impl PsetsSelector {
+    pub fn new(pattern: PathPattern) -> Self {
+        return Self {
+            pattern,
+            class_: None,
+            value_type: None,
+            value: None,
+        };
+    }
+
+    /// Checks the predicates on class, value type and value of an entry
+    pub fn matches_entity(&self, entity: &PsetFlattenedValue) -> bool {
+        let (class_, value_type, value) = entity_parts(entity);
+        let ok_class = self.class_.is_none() || self.class_ == class_;
+        let ok_value_type = self.value_type.is_none() || self.value_type == value_type;
+        let ok_value = self.value.is_none() || self.value == value;
+        return ok_class && ok_value_type && ok_value;
+    }
+
+    /// Lists all flattened entries (in order) matching the pattern and predicates
+    pub fn select(
+        &self,
+        psets: &Psets,
+        delimiter: Option<&String>,
+    ) -> Vec<(String, PsetFlattenedValue)> {
+        let delimiter = delimiter.map_or_else(|| ":".to_string(), |x| x.clone());
+        return psets
+            .rust_flatten(Some(&delimiter), None)
+            .into_iter()
+            .filter(|(path, x)| self.pattern.matches(path, &delimiter) && self.matches_entity(x))
+            .collect();
+    }
+}
+
+// ----------------------------------------------------------------
+// AUXILIARY METHODS
+// ----------------------------------------------------------------
+
+/// Class, value type and value of an entry (if present), as used by the predicates
+pub fn entity_parts(
+    entity: &PsetFlattenedValue,
+) -> (Option<String>, Option<String>, Option<Value>) {
+    match entity {
+        PsetFlattenedValue::Pset(x) => {
+            let ValueWrap(value) = &x.value;
+            (
+                Some(x.class_.clone()),
+                x.value_type.clone(),
+                Some(value.clone()),
+            )
+        }
+        PsetFlattenedValue::PsetId(_) => (None, None, None),
+        PsetFlattenedValue::Quantity(x) => {
+            let ValueWrap(value) = &x.value;
+            (
+                Some(x.class_.clone()),
+                x.dimension().ok().map(|x| x.value_type().to_string()),
+                Some(value.clone()),
+            )
+        }
+        PsetFlattenedValue::Complex(x) => (Some(x.class_.clone()), None, None),
+        PsetFlattenedValue::Property(x) => (
+            Some(x.class_().clone()),
+            x.value_type().cloned(),
+            Some(x.value()),
+        ),
+        PsetFlattenedValue::Value(ValueWrap(value)) => (None, None, Some(value.clone())),
+    }
+}
+
+fn match_segments(segments: &[GlobSegment], parts: &[&str]) -> bool {
+    match segments.split_first() {
+        None => parts.is_empty(),
+        Some((GlobSegment::AnyDepth, rest)) => {
+            (0..=parts.len()).any(|k| match_segments(rest, &parts[k..]))
+        }
+        Some((GlobSegment::Segment(expr), rest)) => match parts.split_first() {
+            Some((part, parts)) => expr.is_match(part) && match_segments(rest, parts),
+            None => false,
+        },
+    }
+}
@@ -0,0 +1,640 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use indexmap::IndexMap;
+use pyo3::Bound;
+use pyo3::FromPyObject; // NOTE: needed for ValueWrap::extract_bound
+use pyo3::IntoPyObject;
+use pyo3::PyResult;
+use pyo3::Python;
+use pyo3::prelude::pyclass;
+use pyo3::prelude::pymethods;
+use pyo3::types::PyAny;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::result::Result;
+use std::sync::OnceLock;
+
+use super::properties::PsetProperty;
+use super::psd::PsdDefinition;
+use super::psets::PsetNestedValue;
+use super::psets::Psets;
+use super::psets::QuantityDimension;
+use crate::_core::errors::err_to_py_key_error;
+use crate::_core::errors::err_to_py_string;
+use crate::_core::errors::err_to_string;
+use crate::models::json::base::JsonConversion;
+use crate::models::json::model::ValueWrap;
+
+// ----------------------------------------------------------------
+// CONSTANTS
+// ----------------------------------------------------------------
+
+/// Common sets of the buildingSMART property and quantity set definitions,
+/// NOTE: not the complete library, sets without a template are reported as unchecked
+const IFC4_TEMPLATES: &str = include_str!("templates/ifc4.json");
+const IFC4X3_TEMPLATES: &str = include_str!("templates/ifc4x3.json");
+
+/// Direct supertypes of the classes the bundled sets apply to, and of the spatial classes,
+/// NOTE: not the complete hierarchy, other classes leave the applicability of sets undecided
+/// (the built elements are listed under both their IFC4 and IFC4X3 supertypes)
+const SUPERTYPES: [(&str, &str); 40] = [
+    ("IfcObjectDefinition", "IfcRoot"),
+    ("IfcObject", "IfcObjectDefinition"),
+    ("IfcContext", "IfcObjectDefinition"),
+    ("IfcProject", "IfcContext"),
+    ("IfcProduct", "IfcObject"),
+    ("IfcSpatialElement", "IfcProduct"),
+    ("IfcSpatialStructureElement", "IfcSpatialElement"),
+    ("IfcSite", "IfcSpatialStructureElement"),
+    ("IfcBuilding", "IfcSpatialStructureElement"),
+    ("IfcBuildingStorey", "IfcSpatialStructureElement"),
+    ("IfcSpace", "IfcSpatialStructureElement"),
+    ("IfcElement", "IfcProduct"),
+    ("IfcBuildingElement", "IfcElement"),
+    ("IfcBuiltElement", "IfcElement"),
+    ("IfcBeam", "IfcBuildingElement"),
+    ("IfcBeam", "IfcBuiltElement"),
+    ("IfcColumn", "IfcBuildingElement"),
+    ("IfcColumn", "IfcBuiltElement"),
+    ("IfcCovering", "IfcBuildingElement"),
+    ("IfcCovering", "IfcBuiltElement"),
+    ("IfcDoor", "IfcBuildingElement"),
+    ("IfcDoor", "IfcBuiltElement"),
+    ("IfcRoof", "IfcBuildingElement"),
+    ("IfcRoof", "IfcBuiltElement"),
+    ("IfcSlab", "IfcBuildingElement"),
+    ("IfcSlab", "IfcBuiltElement"),
+    ("IfcWall", "IfcBuildingElement"),
+    ("IfcWall", "IfcBuiltElement"),
+    ("IfcWindow", "IfcBuildingElement"),
+    ("IfcWindow", "IfcBuiltElement"),
+    ("IfcBeamStandardCase", "IfcBeam"),
+    ("IfcColumnStandardCase", "IfcColumn"),
+    ("IfcDoorStandardCase", "IfcDoor"),
+    ("IfcSlabStandardCase", "IfcSlab"),
+    ("IfcSlabElementedCase", "IfcSlab"),
+    ("IfcWallStandardCase", "IfcWall"),
+    ("IfcWallElementedCase", "IfcWall"),
+    ("IfcWindowStandardCase", "IfcWindow"),
+    ("IfcSpatialZone", "IfcSpatialElement"),
+    ("IfcExternalSpatialElement", "IfcSpatialElement"),
+];
+
+// ----------------------------------------------------------------
+// STRUCTS
+// ----------------------------------------------------------------
+
+/// Expected form of a property of a template
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PropertyTemplate {
+    /// data type of single values, e.g. `IfcLabel`
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub data_type: Option<String>,
+    /// class of quantities, e.g. `IfcQuantityLength`
+    #[serde(rename = "class", default, skip_serializing_if = "Option::is_none")]
+    pub class_: Option<String>,
+    /// name of the allowed values, e.g. `PEnum_ElementStatus`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enumeration: Option<String>,
+    /// whether the property must be present in every instance of the set
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub required: bool,
+}
+
+/// Definition of a property or quantity set
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PsetTemplate {
+    /// entity classes the set applies to, optionally with a predefined type (`IfcWall/SHEAR`)
+    pub applicable: Vec<String>,
+    pub properties: IndexMap<String, PropertyTemplate>,
+}
+
+/// Library of property set templates of a schema
+#[pyclass]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PsetTemplateLibrary {
+    pub schema: String,
+    #[serde(default)]
+    pub enumerations: IndexMap<String, Vec<String>>,
+    pub templates: IndexMap<String, PsetTemplate>,
+}
+
+/// Deviation of a property from its template
+#[pyclass(get_all)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TemplateIssue {
+    pub path: String,
+    pub expected: String,
+    pub found: String,
+}
+
+/// Outcome of validating Psets against a template library
+#[pyclass(get_all)]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PsetsValidation {
+    /// sets without a template in the library, which are not checked
+    pub unchecked_psets: Vec<String>,
+    pub misapplied_psets: Vec<String>,
+    /// sets which may or may not apply, the entity class being outside the known hierarchy
+    pub undecided_psets: Vec<String>,
+    pub unknown_properties: Vec<String>,
+    pub missing_properties: Vec<String>,
+    pub wrong_types: Vec<TemplateIssue>,
+    pub invalid_values: Vec<TemplateIssue>,
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF PsetTemplate
+// ----------------------------------------------------------------
+
+/// Rust implementation of struct
+impl PsetTemplate {
+    /// Whether the set applies to an entity class or one of its supertypes (ignoring predefined types),
+    /// or `None` if undecided, the class being unknown while the set applies to a class with subtypes
+    pub fn applies_to(&self, entity_class: &str) -> Option<bool> {
+        let classes: Vec<&str> = self
+            .applicable
+            .iter()
+            .map(|x| x.split('/').next().unwrap_or_default())
+            .collect();
+        if classes.iter().any(|x| x.eq_ignore_ascii_case(entity_class)) {
+            return Some(true);
+        }
+        if let Some(supertypes) = supertypes_of(entity_class) {
+            let result = classes
+                .iter()
+                .any(|x| supertypes.iter().any(|y| y.eq_ignore_ascii_case(x)));
+            return Some(result);
+        }
+        if classes.iter().any(|x| has_subtypes(x)) {
+            return None;
+        }
+        return Some(false);
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF PsetTemplateLibrary
+// ----------------------------------------------------------------
+
+#[pymethods]
+impl PsetTemplateLibrary {
+    #[staticmethod]
+    fn __class_name__() -> String {
+        "PsetTemplateLibrary".to_string()
+    }
+
+    /// Library shipped with the package, for `IFC4` or `IFC4X3`
+    #[staticmethod]
+    #[pyo3(name = "bundled", signature = (schema="IFC4X3".to_string(), /))]
+    fn py_bundled(schema: String) -> PyResult<Self> {
+        let result = Self::bundled(&schema).map_err(err_to_py_string)?;
+        return Ok(result.clone());
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (value, /))]
+    pub fn model_validate(value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let raw = ValueWrap::extract_bound(value)?;
+        let value = raw.to_json().map_err(err_to_py_string)?;
+        let result = Self::from_json(&value).map_err(err_to_py_string)?;
+        return Ok(result);
+    }
+
+    #[getter]
+    fn schema(&self) -> String {
+        self.schema.clone()
+    }
+
+    fn names(&self) -> Vec<String> {
+        self.templates.keys().cloned().collect()
+    }
+
+    /// Names of the sets applying to an entity class (leaving out the undecided ones)
+    #[pyo3(signature = (entity_class, /))]
+    fn applicable_to(&self, entity_class: &str) -> Vec<String> {
+        return self
+            .templates
+            .iter()
+            .filter(|(_, x)| x.applies_to(entity_class) == Some(true))
+            .map(|(name, _)| name.clone())
+            .collect();
+    }
+
+    /// Names of the properties which must be present in a set
+    #[pyo3(signature = (name, /))]
+    fn required_properties(&self, name: &str) -> PyResult<Vec<String>> {
+        return self.properties_of(name, true).map_err(err_to_py_key_error);
+    }
+
+    /// Names of the properties which may be left out of a set
+    #[pyo3(signature = (name, /))]
+    fn optional_properties(&self, name: &str) -> PyResult<Vec<String>> {
+        return self.properties_of(name, false).map_err(err_to_py_key_error);
+    }
+
+    /// Library of the sets of PSD documents (given as XML text)
+    #[staticmethod]
+    #[pyo3(name = "from_psd", signature = (documents, /, *, schema="IFC4X3".to_string()))]
+    fn py_from_psd(documents: Vec<String>, schema: String) -> PyResult<Self> {
+        let mut result = Self {
+            schema,
+            enumerations: IndexMap::new(),
+            templates: IndexMap::new(),
+        };
+        for text in documents.iter() {
+            result.rust_add_psd(text).map_err(err_to_py_string)?;
+        }
+        return Ok(result);
+    }
+
+    /// Adds (or replaces) the set of a PSD document, returning its name
+    #[pyo3(name = "add_psd", signature = (text, /))]
+    fn py_add_psd(&mut self, text: &str) -> PyResult<String> {
+        return self.rust_add_psd(text).map_err(err_to_py_string);
+    }
+
+    /// Adds the sets of a PSD file or of all XML files of a directory, returning their names
+    #[pyo3(name = "load_psd", signature = (path, /))]
+    fn py_load_psd(&mut self, path: String) -> PyResult<Vec<String>> {
+        return self
+            .rust_load_psd(Path::new(&path))
+            .map_err(err_to_py_string);
+    }
+
+    #[pyo3(name = "validate", signature = (psets, entity_class=None, /, *, delimiter=":".to_string()))]
+    fn py_validate(
+        &self,
+        psets: &Psets,
+        entity_class: Option<String>,
+        delimiter: String,
+    ) -> PsetsValidation {
+        return self.rust_validate(psets, entity_class.as_deref(), Some(&delimiter));
+    }
+
+    fn __len__(&self) -> usize {
+        self.templates.len()
+    }
+
+    fn __contains__(&self, name: String) -> bool {
+        self.templates.contains_key(&name)
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self == other
+    }
+
+    fn into_pyobject<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let raw = self.to_json().map_err(err_to_py_string)?;
+        return ValueWrap(raw).into_pyobject(py);
+    }
+
+    pub fn __str__(&self) -> PyResult<String> {
+        Ok(self.to_string())
+    }
+}
+
+/// Rust implementation of struct
+impl PsetTemplateLibrary {
+    /// Library shipped with the package, parsed once per schema
+    pub fn bundled(schema: &str) -> Result<&'static Self, String> {
+        static IFC4: OnceLock<PsetTemplateLibrary> = OnceLock::new();
+        static IFC4X3: OnceLock<PsetTemplateLibrary> = OnceLock::new();
+        let (cell, text) = match schema.to_uppercase().as_str() {
+            "IFC4" => (&IFC4, IFC4_TEMPLATES),
+            "IFC4X3" => (&IFC4X3, IFC4X3_TEMPLATES),
+            _ => {
+                return Err(format!("no bundled templates for schema '{}'", schema));
+            }
+        };
+        let result = cell.get_or_init(|| {
+            // NOTE: the bundled files are checked by the tests, so parsing cannot fail
+            let value: Value = serde_json::from_str(text).unwrap_or_default();
+            return Self::from_json(&value).unwrap_or_else(|err| panic!("{}", err));
+        });
+        return Ok(result);
+    }
+
+    pub fn rust_add_psd(&mut self, text: &str) -> Result<String, String> {
+        let definition = PsdDefinition::from_xml(text)?;
+        self.enumerations.extend(definition.enumerations);
+        self.templates
+            .insert(definition.name.clone(), definition.template);
+        return Ok(definition.name);
+    }
+
+    pub fn rust_load_psd(&mut self, path: &Path) -> Result<Vec<String>, String> {
+        let mut files = vec![path.to_path_buf()];
+        if path.is_dir() {
+            files = fs::read_dir(path)
+                .map_err(err_to_string)?
+                .filter_map(|x| x.ok().map(|x| x.path()))
+                .filter(|x| x.extension().is_some_and(|x| x.eq_ignore_ascii_case("xml")))
+                .collect();
+            // NOTE: later files replace earlier sets of the same name
+            files.sort();
+        }
+        let mut result: Vec<String> = vec![];
+        for file in files.iter() {
+            let text = fs::read_to_string(file).map_err(err_to_string)?;
+            let name = self
+                .rust_add_psd(&text)
+                .map_err(|err| format!("{}: {}", file.display(), err))?;
+            result.push(name);
+        }
+        return Ok(result);
+    }
+
+    fn properties_of(&self, name: &str, required: bool) -> Result<Vec<String>, String> {
+        let template = self
+            .templates
+            .get(name)
+            .ok_or_else(|| format!("no template named '{}'", name))?;
+        return Ok(template
+            .properties
+            .iter()
+            .filter(|(_, x)| x.required == required)
+            .map(|(key, _)| key.clone())
+            .collect());
+    }
+
+    /// Checks the properties of all sets with a template,
+    /// and that the sets apply to the entity class (if given)
+    pub fn rust_validate(
+        &self,
+        psets: &Psets,
+        entity_class: Option<&str>,
+        delimiter: Option<&String>,
+    ) -> PsetsValidation {
+        let delimiter = delimiter.map_or_else(|| ":".to_string(), |x| x.clone());
+        let mut result = PsetsValidation::default();
+        for (name, x) in psets.children().into_iter().flatten() {
+            let Some(template) = self.templates.get(name) else {
+                result.unchecked_psets.push(name.clone());
+                continue;
+            };
+            match entity_class.map(|x| template.applies_to(x)) {
+                Some(Some(false)) => result.misapplied_psets.push(name.clone()),
+                Some(None) => result.undecided_psets.push(name.clone()),
+                _ => {}
+            }
+            let PsetNestedValue::Psets(pset) = x else {
+                continue;
+            };
+            for (key, value) in pset.children().into_iter().flatten() {
+                // NOTE: verbose psets store their id alongside the properties
+                if key == "id" {
+                    continue;
+                }
+                let path = format!("{}{}{}", name, delimiter, key);
+                match template.properties.get(key) {
+                    Some(property) => self.check_property(&path, property, value, &mut result),
+                    None => result.unknown_properties.push(path),
+                }
+            }
+            let children = pset.children();
+            for (key, _) in template.properties.iter().filter(|(_, x)| x.required) {
+                if children.is_none_or(|x| !x.contains_key(key)) {
+                    let path = format!("{}{}{}", name, delimiter, key);
+                    result.missing_properties.push(path);
+                }
+            }
+        }
+        return result;
+    }
+
+    fn check_property(
+        &self,
+        path: &str,
+        template: &PropertyTemplate,
+        value: &PsetNestedValue,
+        result: &mut PsetsValidation,
+    ) {
+        let (class_, value_type, values) = match value {
+            PsetNestedValue::Psets(Psets::Pset(x)) => {
+                let ValueWrap(value) = &x.value;
+                (Some(&x.class_), x.value_type.as_ref(), vec![value])
+            }
+            PsetNestedValue::Psets(Psets::Quantity(x)) => (Some(&x.class_), None, vec![]),
+            PsetNestedValue::Psets(Psets::Property(PsetProperty::Enumerated(x))) => {
+                let values = x.values.iter().map(|ValueWrap(x)| x).collect();
+                (Some(&x.class_), x.value_type.as_ref(), values)
+            }
+            PsetNestedValue::Psets(Psets::Property(x)) => {
+                (Some(x.class_()), x.value_type(), vec![])
+            }
+            PsetNestedValue::Psets(_) => (None, None, vec![]),
+            PsetNestedValue::Any(ValueWrap(value)) => (None, None, vec![value]),
+        };
+        let mut wrong_type = |expected: &String, found: &String| {
+            result
+                .wrong_types
+                .push(TemplateIssue::new(path, expected, found));
+        };
+
+        // quantities are told apart by their class, properties by their data type
+        let is_quantity = class_.is_some_and(|x| QuantityDimension::from_class(x).is_some());
+        match (&template.class_, class_) {
+            (Some(expected), Some(found)) if expected != found => wrong_type(expected, found),
+            (None, Some(found)) if is_quantity => {
+                let expected = template.data_type.clone().unwrap_or_default();
+                wrong_type(&expected, found);
+            }
+            _ => {}
+        }
+        if let (Some(expected), Some(found)) = (&template.data_type, value_type) {
+            if expected != found {
+                wrong_type(expected, found);
+            }
+        }
+
+        let Some(name) = &template.enumeration else {
+            return;
+        };
+        let Some(allowed) = self.enumerations.get(name) else {
+            return;
+        };
+        for value in values {
+            let valid = match value {
+                Value::Null => true,
+                Value::String(x) => allowed.contains(x),
+                _ => false,
+            };
+            if !valid {
+                let issue = TemplateIssue::new(path, name, &value.to_string());
+                result.invalid_values.push(issue);
+            }
+        }
+    }
+}
+
+impl ToString for PsetTemplateLibrary {
+    fn to_string(&self) -> String {
+        return format!(
+            "{}({}, {} templates)",
+            Self::__class_name__(),
+            self.schema,
+            self.templates.len()
+        );
+    }
+}
+
+impl JsonConversion<Value> for PsetTemplateLibrary {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        let result = Self::deserialize(value).map_err(err_to_string)?;
+        for (name, template) in result.templates.iter() {
+            for (key, property) in template.properties.iter() {
+                let enumeration = property.enumeration.as_ref();
+                if enumeration.is_some_and(|x| !result.enumerations.contains_key(x)) {
+                    return Err(format!(
+                        "unknown enumeration {:?} of {}:{}",
+                        enumeration, name, key
+                    ));
+                }
+            }
+        }
+        return Ok(result);
+    }
+
+    fn to_json(&self) -> Result<Value, String> {
+        return serde_json::to_value(self).map_err(err_to_string);
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF TemplateIssue + PsetsValidation
+// ----------------------------------------------------------------
+
+#[pymethods]
+impl TemplateIssue {
+    pub fn __str__(&self) -> PyResult<String> {
+        Ok(self.to_string())
+    }
+}
+
+/// Rust implementation of struct
+impl TemplateIssue {
+    fn new(path: &str, expected: &str, found: &str) -> Self {
+        return Self {
+            path: path.to_string(),
+            expected: expected.to_string(),
+            found: found.to_string(),
+        };
+    }
+}
+
+impl ToString for TemplateIssue {
+    fn to_string(&self) -> String {
+        format!(
+            "{} (expected {}, found {})",
+            self.path, self.expected, self.found
+        )
+    }
+}
+
+#[pymethods]
+impl PsetsValidation {
+    /// Whether no issue was found in the checked sets
+    #[getter]
+    fn is_valid(&self) -> bool {
+        self.misapplied_psets.is_empty()
+            && self.unknown_properties.is_empty()
+            && self.missing_properties.is_empty()
+            && self.wrong_types.is_empty()
+            && self.invalid_values.is_empty()
+    }
+
+    /// Whether all sets had a template to be checked against, and a decided applicability
+    #[getter]
+    fn is_complete(&self) -> bool {
+        self.unchecked_psets.is_empty() && self.undecided_psets.is_empty()
+    }
+
+    pub fn __str__(&self) -> PyResult<String> {
+        Ok(self.to_string())
+    }
+}
+
+impl ToString for PsetsValidation {
+    fn to_string(&self) -> String {
+        let mut lines: Vec<String> = vec![];
+        lines.extend(
+            self.unchecked_psets
+                .iter()
+                .map(|x| format!("unchecked pset: {}", x)),
+        );
+        lines.extend(
+            self.misapplied_psets
+                .iter()
+                .map(|x| format!("misapplied pset: {}", x)),
+        );
+        lines.extend(
+            self.undecided_psets
+                .iter()
+                .map(|x| format!("undecided pset: {}", x)),
+        );
+        lines.extend(
+            self.unknown_properties
+                .iter()
+                .map(|x| format!("unknown property: {}", x)),
+        );
+        lines.extend(
+            self.missing_properties
+                .iter()
+                .map(|x| format!("missing property: {}", x)),
+        );
+        lines.extend(
+            self.wrong_types
+                .iter()
+                .map(|x| format!("wrong type: {}", x.to_string())),
+        );
+        lines.extend(
+            self.invalid_values
+                .iter()
+                .map(|x| format!("invalid value: {}", x.to_string())),
+        );
+        return lines.join("\n");
+    }
+}
+
+// ----------------------------------------------------------------
+// AUXILIARY METHODS
+// ----------------------------------------------------------------
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+/// The class followed by all its supertypes, or `None` if the class is not in the table
+fn supertypes_of(entity_class: &str) -> Option<Vec<&'static str>> {
+    let class_ = SUPERTYPES
+        .iter()
+        .flat_map(|(x, y)| [*x, *y])
+        .find(|x| x.eq_ignore_ascii_case(entity_class))?;
+    let mut result = vec![class_];
+    let mut index = 0;
+    while index < result.len() {
+        let class_ = result[index];
+        for (_, y) in SUPERTYPES.iter().filter(|(x, _)| *x == class_) {
+            if !result.contains(y) {
+                result.push(y);
+            }
+        }
+        index += 1;
+    }
+    return Some(result);
+}
+
+fn has_subtypes(class_: &str) -> bool {
+    SUPERTYPES
+        .iter()
+        .any(|(_, x)| x.eq_ignore_ascii_case(class_))
+}
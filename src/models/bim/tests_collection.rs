@@ -0,0 +1,86 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use serde_json::Value;
+use serde_json::json;
+
+use super::collection::PsetsCollection;
+use super::psets::PsetFlattenedValue;
+use crate::models::json::base::JsonConversion;
+
+// ----------------------------------------------------------------
+// TESTS
+// ----------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collection_case1() {
+        let collection = PsetsCollection::from_json(&dummy_collection()).unwrap();
+        assert_eq!(collection.len(), 2);
+        assert!(!collection.is_empty());
+        let psets = collection.rust_get("2O2Fr$t4X7Zf8NOew3FLOH").unwrap();
+        assert_eq!(
+            psets.to_json().unwrap()["Pset_WallCommon"]["IsExternal"]["value"],
+            json!(true)
+        );
+        assert!(collection.rust_get("42").is_some());
+        assert!(collection.rust_get("missing").is_none());
+        assert_eq!(collection.to_json().unwrap(), dummy_collection());
+    }
+
+    #[test]
+    fn test_collection_case2() {
+        let collection = PsetsCollection::from_json(&dummy_collection()).unwrap();
+        let flattened = collection.rust_flatten(None);
+        let paths: Vec<&String> = flattened.keys().collect();
+        assert_eq!(
+            paths,
+            vec![
+                "2O2Fr$t4X7Zf8NOew3FLOH:Pset_WallCommon:id",
+                "2O2Fr$t4X7Zf8NOew3FLOH:Pset_WallCommon:IsExternal",
+                "42:Pset_SlabCommon:id",
+                "42:Pset_SlabCommon:LoadBearing",
+            ]
+        );
+        let value = &flattened["42:Pset_SlabCommon:LoadBearing"];
+        assert!(matches!(value, PsetFlattenedValue::Pset(x) if x.id_ == 21));
+        let delimiter = "/".to_string();
+        let flattened = collection.rust_flatten(Some(&delimiter));
+        assert!(flattened.contains_key("42/Pset_SlabCommon/id"));
+    }
+
+    #[test]
+    fn test_collection_case3() {
+        assert!(PsetsCollection::from_json(&json!([])).is_err());
+        let result = PsetsCollection::from_json(&json!({"42": 1}));
+        assert!(result.is_err_and(|x| x.starts_with("42:")));
+        let collection = PsetsCollection::from_json(&json!({})).unwrap();
+        assert!(collection.is_empty());
+        assert_eq!(collection.to_string(), "PsetsCollection(elements=0)");
+    }
+}
+
+// ----------------------------------------------------------------
+// AUXILIARY
+// ----------------------------------------------------------------
+
+fn dummy_collection() -> Value {
+    return json!({
+        "2O2Fr$t4X7Zf8NOew3FLOH": {
+            "Pset_WallCommon": {
+                "id": 10,
+                "IsExternal": {"id": 11, "class": "IfcPropertySingleValue", "value": true, "value-type": "IfcBoolean"},
+            },
+        },
+        "42": {
+            "Pset_SlabCommon": {
+                "id": 20,
+                "LoadBearing": {"id": 21, "class": "IfcPropertySingleValue", "value": false, "value-type": "IfcBoolean"},
+            },
+        },
+    });
+}
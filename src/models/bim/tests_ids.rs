@@ -0,0 +1,308 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use serde_json::json;
+
+use super::ids::IdsDocument;
+use super::ids::IdsElement;
+use super::ids::IdsStatus;
+use super::psets::Psets;
+use crate::models::json::base::JsonConversion;
+
+// ----------------------------------------------------------------
+// TESTS
+// ----------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_case1() {
+        let ids = IdsDocument::from_xml(DUMMY_IDS).unwrap();
+        assert_eq!(ids.title, Some("Walls".to_string()));
+        assert_eq!(ids.specifications.len(), 4);
+        assert_eq!(
+            ids.specifications[0].ifc_versions,
+            vec!["IFC4", "IFC4X3_ADD2"]
+        );
+        assert_eq!(ids.specifications[0].min_occurs, 1);
+        assert_eq!(ids.specifications[0].max_occurs, None);
+        assert_eq!(ids.specifications[0].requirements.len(), 3);
+        assert!(IdsDocument::from_xml("<ids><specifications/>").is_err());
+        assert!(IdsDocument::from_xml("<other/>").is_err());
+    }
+
+    #[test]
+    fn test_ids_case2() {
+        let ids = IdsDocument::from_xml(DUMMY_IDS).unwrap();
+        let valid = dummy_psets("EXTERNAL", 200);
+        let invalid = dummy_psets("UNKNOWN", 5000);
+        let elements = [
+            IdsElement {
+                name: "wall-1".to_string(),
+                entity_class: Some("IfcWall".to_string()),
+                predefined_type: None,
+                psets: &valid,
+            },
+            IdsElement {
+                name: "wall-2".to_string(),
+                entity_class: Some("IfcWall".to_string()),
+                predefined_type: None,
+                psets: &invalid,
+            },
+            IdsElement {
+                name: "slab-1".to_string(),
+                entity_class: Some("IfcSlab".to_string()),
+                predefined_type: None,
+                psets: &invalid,
+            },
+        ];
+        let report = ids.rust_check(&elements);
+        let spec = &report.specifications[0];
+        assert_eq!(spec.status, IdsStatus::Fail);
+        assert_eq!(spec.applicable, vec!["wall-1", "wall-2"]);
+        let statuses: Vec<IdsStatus> = spec.requirements.iter().map(|x| x.status).collect();
+        assert_eq!(
+            statuses,
+            vec![IdsStatus::Pass, IdsStatus::Fail, IdsStatus::Fail]
+        );
+        assert_eq!(spec.requirements[1].failures.len(), 1);
+        assert_eq!(spec.requirements[1].failures[0].0, "wall-2");
+        // prohibited specification: no slabs allowed
+        assert_eq!(report.specifications[1].status, IdsStatus::Fail);
+        // attribute facets are not evaluated
+        assert_eq!(report.specifications[2].status, IdsStatus::Skipped);
+    }
+
+    #[test]
+    fn test_ids_case3() {
+        let ids = IdsDocument::from_xml(DUMMY_IDS).unwrap();
+        let valid = dummy_psets("EXTERNAL", 200);
+        let elements = [IdsElement {
+            name: "psets".to_string(),
+            entity_class: None,
+            predefined_type: None,
+            psets: &valid,
+        }];
+        let report = ids.rust_check(&elements);
+        // without entity class the applicability of entity facets cannot be evaluated
+        assert_eq!(report.specifications[0].status, IdsStatus::Skipped);
+        assert!(report.specifications[0].applicable.is_empty());
+        assert_eq!(
+            report.specifications[0].messages,
+            vec!["applicability cannot be evaluated on psets"]
+        );
+        assert_eq!(report.specifications[1].status, IdsStatus::Skipped);
+        let empty = IdsDocument::from_xml(DUMMY_IDS).unwrap().rust_check(&[]);
+        assert_eq!(empty.specifications[0].status, IdsStatus::Fail);
+        assert_eq!(empty.specifications[1].status, IdsStatus::Pass);
+    }
+
+    #[test]
+    fn test_ids_case4() {
+        let ids = IdsDocument::from_xml(DUMMY_IDS).unwrap();
+        let valid = dummy_psets("EXTERNAL", 200);
+        let invalid = dummy_psets("UNKNOWN", 5000);
+        let elements = [
+            IdsElement {
+                name: "wall-1".to_string(),
+                entity_class: Some("IfcWall".to_string()),
+                predefined_type: Some("SHEAR".to_string()),
+                psets: &valid,
+            },
+            IdsElement {
+                name: "wall-2".to_string(),
+                entity_class: Some("IfcWall".to_string()),
+                predefined_type: Some("STANDARD".to_string()),
+                psets: &invalid,
+            },
+        ];
+        let report = ids.rust_check(&elements);
+        let spec = &report.specifications[3];
+        assert_eq!(spec.status, IdsStatus::Pass);
+        assert_eq!(spec.applicable, vec!["wall-1"]);
+        let elements = [IdsElement {
+            predefined_type: None,
+            ..elements.into_iter().next().unwrap()
+        }];
+        let report = ids.rust_check(&elements);
+        assert_eq!(report.specifications[3].status, IdsStatus::Skipped);
+    }
+
+    #[test]
+    fn test_ids_case5() {
+        let ids = IdsDocument::from_xml(DUMMY_IDS_PROPERTIES).unwrap();
+        let psets = Psets::from_json(&dummy_typed_psets(24.0)).unwrap();
+        let elements = [IdsElement {
+            name: "space-1".to_string(),
+            entity_class: None,
+            predefined_type: None,
+            psets: &psets,
+        }];
+        let report = ids.rust_check(&elements);
+        let spec = &report.specifications[0];
+        let statuses: Vec<IdsStatus> = spec.requirements.iter().map(|x| x.status).collect();
+        assert_eq!(statuses, vec![IdsStatus::Pass; 4]);
+        assert_eq!(spec.status, IdsStatus::Pass);
+        // all bounds are checked
+        let psets = Psets::from_json(&dummy_typed_psets(40.0)).unwrap();
+        let elements = [IdsElement {
+            psets: &psets,
+            ..elements.into_iter().next().unwrap()
+        }];
+        let report = ids.rust_check(&elements);
+        let spec = &report.specifications[0];
+        assert_eq!(spec.requirements[0].status, IdsStatus::Fail);
+        assert_eq!(spec.requirements[1].status, IdsStatus::Pass);
+    }
+}
+
+// ----------------------------------------------------------------
+// AUXILIARY
+// ----------------------------------------------------------------
+
+const DUMMY_IDS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ids xmlns="http://standards.buildingsmart.org/IDS" xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <info><title>Walls</title></info>
+  <specifications>
+    <specification name="Wall properties" ifcVersion="IFC4 IFC4X3_ADD2">
+      <applicability minOccurs="1" maxOccurs="unbounded">
+        <entity><name><simpleValue>IFCWALL</simpleValue></name></entity>
+      </applicability>
+      <requirements>
+        <property dataType="IFCBOOLEAN">
+          <propertySet><simpleValue>Pset_WallCommon</simpleValue></propertySet>
+          <baseName><simpleValue>IsExternal</simpleValue></baseName>
+          <value><simpleValue>TRUE</simpleValue></value>
+        </property>
+        <property>
+          <propertySet><xs:restriction base="xs:string"><xs:pattern value="Pset_.*Common"/></xs:restriction></propertySet>
+          <baseName><simpleValue>Status</simpleValue></baseName>
+          <value>
+            <xs:restriction base="xs:string">
+              <xs:enumeration value="NEW"/>
+              <xs:enumeration value="EXISTING"/>
+              <xs:enumeration value="EXTERNAL"/>
+            </xs:restriction>
+          </value>
+        </property>
+        <property dataType="IfcLengthMeasure" cardinality="optional">
+          <propertySet><simpleValue>Qto_WallBaseQuantities</simpleValue></propertySet>
+          <baseName><simpleValue>Width</simpleValue></baseName>
+          <value><xs:restriction base="xs:double"><xs:maxInclusive value="1000"/></xs:restriction></value>
+        </property>
+      </requirements>
+    </specification>
+    <specification name="No slabs" ifcVersion="IFC4">
+      <applicability minOccurs="0" maxOccurs="0">
+        <entity><name><simpleValue>IFCSLAB</simpleValue></name></entity>
+      </applicability>
+    </specification>
+    <specification name="Named" ifcVersion="IFC4">
+      <applicability><entity><name><simpleValue>IFCWALL</simpleValue></name></entity></applicability>
+      <requirements>
+        <attribute><name><simpleValue>Name</simpleValue></name></attribute>
+      </requirements>
+    </specification>
+    <specification name="Shear walls" ifcVersion="IFC4">
+      <applicability>
+        <entity>
+          <name><simpleValue>IFCWALL</simpleValue></name>
+          <predefinedType><simpleValue>SHEAR</simpleValue></predefinedType>
+        </entity>
+      </applicability>
+      <requirements>
+        <property>
+          <propertySet><simpleValue>Qto_WallBaseQuantities</simpleValue></propertySet>
+          <baseName><simpleValue>Width</simpleValue></baseName>
+          <value><xs:restriction base="xs:double"><xs:maxInclusive value="1000"/></xs:restriction></value>
+        </property>
+      </requirements>
+    </specification>
+  </specifications>
+</ids>"#;
+
+fn dummy_psets(status: &str, width: i64) -> Psets {
+    let value = json!({
+        "Pset_WallCommon": {
+            "id": 1,
+            "IsExternal": {"id": 2, "class": "IfcPropertySingleValue", "value": true, "value-type": "IfcBoolean"},
+            "Status": {"id": 3, "class": "IfcPropertySingleValue", "value": status, "value-type": "IfcLabel"},
+        },
+        "Qto_WallBaseQuantities": {
+            "id": 4,
+            "Width": {"id": 5, "class": "IfcQuantityLength", "value": width},
+        },
+    });
+    return Psets::from_json(&value).unwrap();
+}
+
+const DUMMY_IDS_PROPERTIES: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ids xmlns="http://standards.buildingsmart.org/IDS" xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <info><title>Spaces</title></info>
+  <specifications>
+    <specification name="Space properties" ifcVersion="IFC4">
+      <applicability/>
+      <requirements>
+        <property dataType="IFCTHERMODYNAMICTEMPERATUREMEASURE">
+          <propertySet><simpleValue>Pset_SpaceThermalRequirements</simpleValue></propertySet>
+          <baseName><simpleValue>SpaceTemperature</simpleValue></baseName>
+          <value><xs:restriction base="xs:double"><xs:maxInclusive value="30"/></xs:restriction></value>
+        </property>
+        <property dataType="IFCPOWERMEASURE">
+          <propertySet><simpleValue>Pset_SpaceThermalRequirements</simpleValue></propertySet>
+          <baseName><simpleValue>HeatingCurve</simpleValue></baseName>
+          <value><xs:restriction base="xs:double"><xs:minInclusive value="100"/></xs:restriction></value>
+        </property>
+        <property>
+          <propertySet><simpleValue>ACME_Layers</simpleValue></propertySet>
+          <baseName><simpleValue>Thickness</simpleValue></baseName>
+        </property>
+        <property>
+          <propertySet><simpleValue>ACME_Layers</simpleValue></propertySet>
+          <baseName><simpleValue>Material</simpleValue></baseName>
+        </property>
+      </requirements>
+    </specification>
+  </specifications>
+</ids>"#;
+
+fn dummy_typed_psets(upper: f64) -> serde_json::Value {
+    return json!({
+        "Pset_SpaceThermalRequirements": {
+            "id": 1,
+            "SpaceTemperature": {
+                "id": 2,
+                "class": "IfcPropertyBoundedValue",
+                "upper-bound": upper,
+                "upper-bound-type": "IfcThermodynamicTemperatureMeasure",
+                "lower-bound": 18.0,
+                "lower-bound-type": "IfcThermodynamicTemperatureMeasure",
+                "set-point": 21.0,
+                "set-point-type": "IfcThermodynamicTemperatureMeasure",
+            },
+            "HeatingCurve": {
+                "id": 3,
+                "class": "IfcPropertyTableValue",
+                "defining-values": [10.0, 20.0],
+                "defined-values": [100.0, 200.0],
+                "defining-value-type": "IfcReal",
+                "defined-value-type": "IfcPowerMeasure",
+                "expression": null,
+                "curve-interpolation": null,
+            },
+        },
+        "ACME_Layers": {
+            "id": 4,
+            "Layer": {
+                "id": 5,
+                "class": "IfcComplexProperty",
+                "UsageName": "Layer",
+                "Thickness": {"id": 6, "class": "IfcPropertySingleValue", "value": 0.2, "value-type": "IfcPositiveLengthMeasure"},
+            },
+            "Material": {"id": 7, "class": "IfcPropertyReferenceValue", "value": 42},
+        },
+    });
+}
@@ -0,0 +1,269 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use indexmap::IndexMap;
+use serde_json::Value;
+use serde_json::json;
+
+use super::properties::PsetProperty;
+use super::properties::PsetReferenceValue;
+use super::psets::PsetFlattenedValue;
+use super::psets::PsetNestedValue;
+use super::psets::Psets;
+use crate::models::json::base::JsonConversion;
+use crate::models::json::model::ValueWrap;
+
+// ----------------------------------------------------------------
+// TESTS
+// ----------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enumerated_case1() {
+        let value = dummy_enumerated(json!(["LOW", "HIGH"]));
+        let psets = Psets::from_json(&json!({"Pset_Test": {"Level": value.clone()}})).unwrap();
+        let Some(PsetFlattenedValue::Property(PsetProperty::Enumerated(x))) = psets
+            .rust_flatten(None, None)
+            .get("Pset_Test:Level")
+            .cloned()
+        else {
+            panic!("expected an enumerated property");
+        };
+        assert_eq!(
+            x.values,
+            vec![ValueWrap(json!("LOW")), ValueWrap(json!("HIGH"))]
+        );
+        assert_eq!(x.enumeration.unwrap().values.len(), 3);
+        assert_eq!(
+            psets.to_json().unwrap(),
+            json!({"Pset_Test": {"Level": value}})
+        );
+    }
+
+    #[test]
+    fn test_enumerated_case2() {
+        let value = PsetProperty::from_json(&dummy_enumerated(json!("LOW"))).unwrap();
+        assert_eq!(value.value(), json!(["LOW"]));
+        assert!(PsetProperty::from_json(&dummy_enumerated(json!(["EXTREME"]))).is_err());
+        assert!(PsetProperty::from_json(&dummy_enumerated(json!([1]))).is_err());
+    }
+
+    #[test]
+    fn test_bounded_case1() {
+        let value = dummy_bounded(json!(18.0), json!(24.0));
+        let Ok(PsetProperty::Bounded(x)) = PsetProperty::from_json(&value) else {
+            panic!("expected a bounded property");
+        };
+        assert!(x.contains(20.0).unwrap());
+        assert!(!x.contains(25.0).unwrap());
+        assert_eq!(PsetProperty::Bounded(x.clone()).to_json().unwrap(), value);
+        assert_eq!(
+            x.to_string(),
+            "PsetBoundedValue([18.0, 24.0], set-point: 21.0, IfcThermodynamicTemperatureMeasure)"
+        );
+    }
+
+    #[test]
+    fn test_bounded_case2() {
+        let value = dummy_bounded(Value::Null, json!(24.0));
+        let Ok(PsetProperty::Bounded(x)) = PsetProperty::from_json(&value) else {
+            panic!("expected a bounded property");
+        };
+        assert!(x.contains(-100.0).unwrap());
+        assert!(PsetProperty::from_json(&dummy_bounded(json!(30.0), json!(24.0))).is_err());
+        assert!(PsetProperty::from_json(&dummy_bounded(json!("cold"), json!(24.0))).is_err());
+    }
+
+    #[test]
+    fn test_table_case1() {
+        let value = dummy_table(json!("LINEAR"));
+        let Ok(PsetProperty::Table(x)) = PsetProperty::from_json(&value) else {
+            panic!("expected a table property");
+        };
+        assert_eq!(x.rust_rows().len(), 3);
+        assert_eq!(x.lookup(&json!(10.0)).unwrap(), Some(json!(100.0)));
+        assert_eq!(x.lookup(&json!(15.0)).unwrap(), Some(json!(150.0)));
+        assert_eq!(x.lookup(&json!(40.0)).unwrap(), None);
+        assert_eq!(PsetProperty::Table(x).to_json().unwrap(), value);
+    }
+
+    #[test]
+    fn test_table_case2() {
+        let Ok(PsetProperty::Table(x)) = PsetProperty::from_json(&dummy_table(json!("LOG_LOG")))
+        else {
+            panic!("expected a table property");
+        };
+        let y = x.lookup(&json!(20.0)).unwrap().unwrap().as_f64().unwrap();
+        assert!((y - 200.0).abs() < 1e-9);
+        assert_eq!(x.lookup(&json!(20)).unwrap(), Some(json!(200.0)));
+        let Ok(PsetProperty::Table(x)) = PsetProperty::from_json(&dummy_table(Value::Null)) else {
+            panic!("expected a table property");
+        };
+        assert_eq!(x.lookup(&json!(15.0)).unwrap(), None);
+        assert_eq!(x.lookup(&json!(10)).unwrap(), Some(json!(100.0)));
+        let mut value = dummy_table(Value::Null);
+        value["defined-values"] = json!([1.0]);
+        assert!(PsetProperty::from_json(&value).is_err());
+    }
+
+    #[test]
+    fn test_list_case1() {
+        let value = dummy_list(json!([0.1, 0.2, 0.3]));
+        let Ok(PsetProperty::List(x)) = PsetProperty::from_json(&value) else {
+            panic!("expected a list property");
+        };
+        assert_eq!(x.rust_typed_values().unwrap().unwrap().len(), 3);
+        assert_eq!(
+            x.to_string(),
+            "PsetListValue([0.1, 0.2, 0.3], IfcLengthMeasure)"
+        );
+        assert_eq!(PsetProperty::List(x).to_json().unwrap(), value);
+        let Ok(PsetProperty::List(x)) = PsetProperty::from_json(&dummy_list(json!([2.0, 3.0])))
+        else {
+            panic!("expected a list property");
+        };
+        assert!(x.contains(&ValueWrap(json!(2))));
+        assert!(!x.contains(&ValueWrap(json!(4))));
+        let err = PsetProperty::from_json(&dummy_list(json!([0.1, "thick"]))).unwrap_err();
+        assert!(err.starts_with("[1]"));
+    }
+
+    #[test]
+    fn test_complex_case1() {
+        let value = json!({"Pset_Test": {"id": 1, "Layer": dummy_complex()}});
+        let psets = Psets::from_json(&value).unwrap();
+        let Some(PsetNestedValue::Psets(Psets::Complex(x))) =
+            psets.rust_get("Pset_Test:Layer", None)
+        else {
+            panic!("expected a complex property");
+        };
+        assert_eq!(x.usage_name, Some("Layer".to_string()));
+        assert!(psets.rust_get("Pset_Test:Layer:Thickness", None).is_some());
+        assert_eq!(psets.to_json().unwrap(), value);
+    }
+
+    #[test]
+    fn test_complex_case2() {
+        let value = json!({"Pset_Test": {"Layer": dummy_complex()}});
+        let psets = Psets::from_json(&value).unwrap();
+        let elements = psets.rust_flatten(None, None);
+        let keys: Vec<&String> = elements.keys().collect();
+        assert_eq!(keys, vec!["Pset_Test:Layer", "Pset_Test:Layer:Thickness"]);
+        assert_eq!(Psets::rust_unflatten(&elements, None).unwrap(), psets);
+        assert!(psets.to_string().contains("Layer: PsetComplexProperty("));
+    }
+
+    #[test]
+    fn test_reference_case1() {
+        let value = dummy_reference(json!({"id": 7, "class": "IfcMaterial"}));
+        let Ok(PsetProperty::Reference(x)) = PsetProperty::from_json(&value) else {
+            panic!("expected a reference property");
+        };
+        assert_eq!(x.referenced_id, Some(7));
+        assert_eq!(x.to_string(), "PsetReferenceValue(-> IfcMaterial #7)");
+        assert_eq!(PsetProperty::Reference(x).to_json().unwrap(), value);
+        let Ok(PsetProperty::Reference(x)) = PsetProperty::from_json(&dummy_reference(json!(7)))
+        else {
+            panic!("expected a reference property");
+        };
+        assert_eq!((x.referenced_id, x.referenced_class), (Some(7), None));
+    }
+
+    #[test]
+    fn test_reference_case2() {
+        let value = json!({
+            "Pset_Test": {
+                "Material": dummy_reference(json!({"id": 7, "class": "IfcMaterial"})),
+                "Missing": dummy_reference(json!(8)),
+            },
+        });
+        let psets = Psets::from_json(&value).unwrap();
+        let mut entities = IndexMap::<i64, String>::from([(7, "Concrete".to_string())]);
+        let result = psets.rust_resolve_references(&mut entities, None).unwrap();
+        assert_eq!(result["Pset_Test:Material"], Some("Concrete".to_string()));
+        assert_eq!(result["Pset_Test:Missing"], None);
+        let mut failing = |_: &PsetReferenceValue| -> Result<Option<String>, String> {
+            return Err("unavailable".to_string());
+        };
+        assert!(psets.rust_resolve_references(&mut failing, None).is_err());
+    }
+}
+
+// ----------------------------------------------------------------
+// AUXILIARY
+// ----------------------------------------------------------------
+
+fn dummy_enumerated(value: Value) -> Value {
+    json!({
+        "id": 1,
+        "class": "IfcPropertyEnumeratedValue",
+        "value": value,
+        "value-type": "IfcLabel",
+        "enumeration": {
+            "id": 2,
+            "name": "PEnum_Level",
+            "values": ["LOW", "MEDIUM", "HIGH"],
+        },
+    })
+}
+
+fn dummy_bounded(lower: Value, upper: Value) -> Value {
+    json!({
+        "id": 1,
+        "class": "IfcPropertyBoundedValue",
+        "upper-bound": upper,
+        "upper-bound-type": "IfcThermodynamicTemperatureMeasure",
+        "lower-bound": lower,
+        "lower-bound-type": "IfcThermodynamicTemperatureMeasure",
+        "set-point": 21.0,
+        "set-point-type": "IfcThermodynamicTemperatureMeasure",
+    })
+}
+
+fn dummy_table(interpolation: Value) -> Value {
+    json!({
+        "id": 1,
+        "class": "IfcPropertyTableValue",
+        "defining-values": [10.0, 20.0, 30.0],
+        "defined-values": [100.0, 200.0, 300.0],
+        "defining-value-type": "IfcReal",
+        "defined-value-type": "IfcPowerMeasure",
+        "expression": null,
+        "curve-interpolation": interpolation,
+    })
+}
+
+fn dummy_list(values: Value) -> Value {
+    json!({
+        "id": 1,
+        "class": "IfcPropertyListValue",
+        "value": values,
+        "value-type": "IfcLengthMeasure",
+    })
+}
+
+fn dummy_complex() -> Value {
+    json!({
+        "id": 2,
+        "class": "IfcComplexProperty",
+        "UsageName": "Layer",
+        "Thickness": {
+            "id": 3,
+            "class": "IfcPropertySingleValue",
+            "value": 0.2,
+            "value-type": "IfcPositiveLengthMeasure",
+        },
+    })
+}
+
+fn dummy_reference(value: Value) -> Value {
+    json!({
+        "id": 1,
+        "class": "IfcPropertyReferenceValue",
+        "value": value,
+    })
+}
@@ -0,0 +1,138 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use indexmap::IndexMap;
+use serde_json::json;
+
+use super::psd::PsdDefinition;
+use super::psets::Psets;
+use super::templates::PsetTemplateLibrary;
+use crate::models::json::base::JsonConversion;
+
+// ----------------------------------------------------------------
+// TESTS
+// ----------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_psd_case1() {
+        let definition = PsdDefinition::from_xml(DUMMY_PSET).unwrap();
+        assert_eq!(definition.name, "ACME_Pset_Structural");
+        assert_eq!(definition.template.applicable, vec!["IfcBeam", "IfcColumn"]);
+        let properties = &definition.template.properties;
+        assert_eq!(
+            properties["LoadClass"].data_type,
+            Some("IfcLabel".to_string())
+        );
+        assert_eq!(
+            properties["LoadClass"].enumeration,
+            Some("ACME_Pset_Structural.LoadClass".to_string())
+        );
+        assert!(properties["LoadClass"].required);
+        assert_eq!(
+            properties["SpanRange"].data_type,
+            Some("IfcLengthMeasure".to_string())
+        );
+        assert!(!properties["SpanRange"].required);
+        assert_eq!(
+            definition.enumerations["ACME_Pset_Structural.LoadClass"],
+            vec!["LIGHT", "HEAVY"]
+        );
+        let definition = PsdDefinition::from_xml(DUMMY_QTO).unwrap();
+        assert_eq!(
+            definition.template.properties["Length"].class_,
+            Some("IfcQuantityLength".to_string())
+        );
+        assert!(PsdDefinition::from_xml("<PropertySetDef/>").is_err());
+        assert!(PsdDefinition::from_xml("<Other><Name>X</Name></Other>").is_err());
+    }
+
+    #[test]
+    fn test_psd_case2() {
+        let mut library = PsetTemplateLibrary::bundled("IFC4X3").unwrap().clone();
+        assert_eq!(
+            library.rust_add_psd(DUMMY_PSET).unwrap(),
+            "ACME_Pset_Structural"
+        );
+        assert_eq!(library.rust_add_psd(DUMMY_QTO).unwrap(), "ACME_Qto_Beam");
+        assert!(library.templates.contains_key("Pset_WallCommon"));
+        let value = library.to_json().unwrap();
+        assert_eq!(
+            value["templates"]["ACME_Pset_Structural"]["properties"]["LoadClass"]["required"],
+            json!(true)
+        );
+        assert_eq!(PsetTemplateLibrary::from_json(&value).unwrap(), library);
+    }
+
+    #[test]
+    fn test_psd_case3() {
+        let mut library = PsetTemplateLibrary {
+            schema: "IFC4".to_string(),
+            enumerations: IndexMap::new(),
+            templates: IndexMap::new(),
+        };
+        library.rust_add_psd(DUMMY_PSET).unwrap();
+        let value = json!({
+            "ACME_Pset_Structural": {
+                "id": 1,
+                "SpanRange": {"id": 2, "class": "IfcPropertySingleValue", "value": 6.5, "value-type": "IfcLengthMeasure"},
+            },
+        });
+        let psets = Psets::from_json(&value).unwrap();
+        let result = library.rust_validate(&psets, Some("IfcBeam"), None);
+        assert_eq!(
+            result.missing_properties,
+            vec!["ACME_Pset_Structural:LoadClass"]
+        );
+        assert!(result.wrong_types.is_empty());
+        let result = library.rust_validate(&psets, Some("IfcWall"), None);
+        assert_eq!(result.misapplied_psets, vec!["ACME_Pset_Structural"]);
+    }
+}
+
+// ----------------------------------------------------------------
+// AUXILIARY
+// ----------------------------------------------------------------
+
+const DUMMY_PSET: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<PropertySetDef xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" templatetype="PSET_TYPEDRIVENOVERRIDE">
+  <Name>ACME_Pset_Structural</Name>
+  <Definition>Structural properties required by ACME.</Definition>
+  <ApplicableClasses>
+    <ClassName>IfcBeam</ClassName>
+    <ClassName>IfcColumn</ClassName>
+  </ApplicableClasses>
+  <PropertyDefs>
+    <PropertyDef required="true">
+      <Name>LoadClass</Name>
+      <PropertyType>
+        <TypePropertyEnumeratedValue>
+          <EnumList>
+            <EnumItem>LIGHT</EnumItem>
+            <EnumItem>HEAVY</EnumItem>
+          </EnumList>
+        </TypePropertyEnumeratedValue>
+      </PropertyType>
+    </PropertyDef>
+    <PropertyDef>
+      <Name>SpanRange</Name>
+      <PropertyType>
+        <TypePropertyBoundedValue><DataType type="IfcLengthMeasure"/></TypePropertyBoundedValue>
+      </PropertyType>
+    </PropertyDef>
+  </PropertyDefs>
+</PropertySetDef>"#;
+
+const DUMMY_QTO: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<QtoSetDef>
+  <Name>ACME_Qto_Beam</Name>
+  <ApplicableClasses><ClassName>IfcBeam</ClassName></ApplicableClasses>
+  <QtoDefs>
+    <QtoDef><Name>Length</Name><QtoType>Q_LENGTH</QtoType></QtoDef>
+    <QtoDef required="true"><Name>Weight</Name><QtoType>Q_WEIGHT</QtoType></QtoDef>
+  </QtoDefs>
+</QtoSetDef>"#;
@@ -0,0 +1,518 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use pyo3::Python;
+use pyo3::ffi::c_str;
+use pyo3::types::PyDict;
+use pyo3::types::PyDictMethods;
+use pyo3::types::PyModule;
+use serde_json::Value;
+use serde_json::json;
+use std::cmp::Ordering;
+use std::ffi::CStr;
+
+use super::diff::PsetsDiff;
+use super::merge::MergeStrategy;
+use super::merge::merge_psets;
+use super::psets::PsetFlattenedValue;
+use super::psets::PsetNestedValue;
+use super::psets::Psets;
+use super::psets::Quantity;
+use super::psets::QuantityDimension;
+use super::select::PathPattern;
+use super::select::PsetsSelector;
+use crate::_core::hashing::hash_of;
+use crate::models::json::base::JsonConversion;
+use crate::models::json::model::ValueWrap;
+use crate::models::tree::base::TraversalOrder;
+
+// ----------------------------------------------------------------
+// TESTS
+// ----------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_case1() {
+        let psets = dummy_psets();
+        let path = "Pset_WallCommon:IsExternal".to_string();
+        let value = psets.rust_get(&path, None).unwrap();
+        assert_eq!(value.to_json().unwrap(), dummy_property(2, json!(true)));
+        let path = "Pset_WallCommon:Missing".to_string();
+        assert!(psets.rust_get(&path, None).is_none());
+    }
+
+    #[test]
+    fn test_get_case2() {
+        let psets = dummy_psets();
+        let delimiter = "/".to_string();
+        let path = "Pset_WallCommon/id".to_string();
+        let value = psets.rust_get(&path, Some(&delimiter)).unwrap();
+        assert_eq!(value.to_json().unwrap(), json!(1));
+    }
+
+    #[test]
+    fn test_set_case1() {
+        let mut psets = dummy_psets();
+        let path = "Pset_Custom:Rating".to_string();
+        let value = PsetNestedValue::Any(ValueWrap(json!("A+")));
+        psets.rust_set(&path, value, None).unwrap();
+        let value = psets.rust_get(&path, None).unwrap();
+        assert_eq!(value.to_json().unwrap(), json!("A+"));
+    }
+
+    #[test]
+    fn test_set_case2() {
+        let mut psets = dummy_psets();
+        let path = "Pset_WallCommon:id:below".to_string();
+        let value = PsetNestedValue::Any(ValueWrap(json!(0)));
+        assert!(psets.rust_set(&path, value, None).is_err());
+    }
+
+    #[test]
+    fn test_set_case3() {
+        with_module(c_str!(
+            r#"
+psets = module.Psets({"Pset_A": {"x": 1}, "A:B": 2})
+updated = psets.set("Pset_A:y", 3)
+assert updated.get("Pset_A:y") == 3 and "y" not in psets["Pset_A"]
+assert updated.delete("Pset_A:y") == psets
+assert psets.with_value("Pset_A:y", 3) == updated
+# keys are not split by [], paths are split by get
+assert psets["A:B"] == 2 and psets.get("A:B") is None
+assert psets.get("A:B", delimiter="/") == 2
+"#
+        ));
+    }
+
+    #[test]
+    fn test_delete_case1() {
+        let mut psets = dummy_psets();
+        let path = "Pset_WallCommon:IsExternal".to_string();
+        assert!(psets.rust_delete(&path, None).is_ok());
+        assert!(psets.rust_get(&path, None).is_none());
+        assert!(psets.rust_delete(&path, None).is_err());
+    }
+
+    #[test]
+    fn test_len_case1() {
+        let psets = dummy_psets();
+        assert_eq!(psets.len(), 1);
+        let path = "Pset_WallCommon".to_string();
+        let value = psets.rust_get(&path, None).unwrap();
+        let PsetNestedValue::Psets(pset) = value else {
+            panic!("expected nested entry");
+        };
+        assert_eq!(pset.len(), 3);
+        let psets = Psets::from_json(&dummy_property(7, json!(true))).unwrap();
+        assert_eq!(psets.len(), 1);
+    }
+
+    #[test]
+    fn test_len_case2() {
+        // the mapping protocol as seen from python, which pyo3 accessors could shadow
+        with_module(c_str!(
+            r#"
+psets = module.Psets({"Pset_A": {"x": 1}, "Pset_B": {"y": 2}})
+assert len(psets) == 2
+assert "Pset_A" in psets and "Pset_C" not in psets
+assert len(psets["Pset_A"]) == 1 and psets["Pset_A"]["x"] == 1
+assert [key for key, _ in psets] == ["Pset_A", "Pset_B"]
+assert psets.keys() == ["Pset_A", "Pset_B"]
+try:
+    psets["Pset_C"]
+    raise AssertionError("expected a KeyError")
+except KeyError:
+    pass
+"#
+        ));
+    }
+
+    #[test]
+    fn test_walk_case1() {
+        let psets = dummy_psets();
+        let paths: Vec<String> = psets
+            .rust_walk(TraversalOrder::PreOrder, None, false)
+            .map(|step| format!("{}@{}", step.path.join("/"), step.depth))
+            .collect();
+        let expected = vec![
+            "@0",
+            "Pset_WallCommon@1",
+            "Pset_WallCommon/id@2",
+            "Pset_WallCommon/IsExternal@2",
+            "Pset_WallCommon/LoadBearing@2",
+        ];
+        assert_eq!(paths, expected);
+    }
+
+    #[test]
+    fn test_walk_case2() {
+        let psets = dummy_psets();
+        let steps: Vec<_> = psets
+            .rust_walk(TraversalOrder::PostOrder, None, true)
+            .collect();
+        assert_eq!(steps.len(), 3);
+        assert!(steps.iter().all(|step| step.entity.is_some()));
+        let steps: Vec<_> = psets
+            .rust_walk(TraversalOrder::PostOrder, Some(1), false)
+            .collect();
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[1].depth, 0);
+    }
+
+    #[test]
+    fn test_walk_case3() {
+        let value = json!({"Pset_A": {}, "Pset_B": {"x": 1, "y": {"z": 2}}});
+        let psets = Psets::from_json(&value).unwrap();
+        let paths: Vec<String> = psets
+            .rust_walk(TraversalOrder::PreOrder, None, true)
+            .map(|step| step.path.join("/"))
+            .collect();
+        assert_eq!(paths, vec!["Pset_B/x", "Pset_B/y/z"]);
+        let mut steps = psets.rust_walk(TraversalOrder::PostOrder, None, false);
+        assert_eq!(steps.next().unwrap().path, vec!["Pset_A"]);
+        assert_eq!(steps.count(), 5);
+    }
+
+    #[test]
+    fn test_diff_case1() {
+        let old = dummy_psets();
+        let mut new = dummy_psets();
+        let path = "Pset_WallCommon:IsExternal".to_string();
+        let value = PsetNestedValue::from_json(&dummy_property(12, json!(false))).unwrap();
+        new.rust_set(&path, value, None).unwrap();
+        let path = "Pset_WallCommon:LoadBearing".to_string();
+        new.rust_delete(&path, None).unwrap();
+        let path = "Pset_Custom:Rating".to_string();
+        new.rust_set(&path, PsetNestedValue::Any(ValueWrap(json!(1))), None)
+            .unwrap();
+
+        let diff = PsetsDiff::new(&old, &new, false, None);
+        assert_eq!(diff.added_psets, vec!["Pset_Custom"]);
+        assert!(diff.removed_psets.is_empty());
+        assert!(diff.added_properties.is_empty());
+        assert_eq!(diff.removed_properties, vec!["Pset_WallCommon:LoadBearing"]);
+        assert_eq!(diff.changed.len(), 1);
+        let change = &diff.changed[0];
+        assert_eq!(change.path, "Pset_WallCommon:IsExternal");
+        assert!(change.value_changed() && change.id_changed());
+    }
+
+    #[test]
+    fn test_diff_case2() {
+        let old = dummy_psets();
+        let mut new = dummy_psets();
+        let path = "Pset_WallCommon:IsExternal".to_string();
+        let value = PsetNestedValue::from_json(&dummy_property(12, json!(true))).unwrap();
+        new.rust_set(&path, value, None).unwrap();
+        let path = "Pset_WallCommon:id".to_string();
+        new.rust_set(&path, PsetNestedValue::Any(ValueWrap(json!(11))), None)
+            .unwrap();
+        assert_eq!(PsetsDiff::new(&old, &new, false, None).changed.len(), 2);
+        assert!(PsetsDiff::new(&old, &new, true, None).is_empty());
+    }
+
+    #[test]
+    fn test_merge_case1() {
+        let left = dummy_psets();
+        let mut right = dummy_psets();
+        let path = "Pset_WallCommon:IsExternal".to_string();
+        let value = PsetNestedValue::from_json(&dummy_property(12, json!(false))).unwrap();
+        right.rust_set(&path, value, None).unwrap();
+        let path = "Pset_Custom:Rating".to_string();
+        right
+            .rust_set(&path, PsetNestedValue::Any(ValueWrap(json!(1))), None)
+            .unwrap();
+
+        let result = merge_psets(&left, &right, false, &mut MergeStrategy::PreferLeft).unwrap();
+        let path = "Pset_WallCommon:IsExternal".to_string();
+        let value = result.rust_get(&path, None).unwrap();
+        assert_eq!(value.to_json().unwrap(), dummy_property(2, json!(true)));
+        assert!(
+            result
+                .rust_get(&"Pset_Custom:Rating".to_string(), None)
+                .is_some()
+        );
+
+        let result = merge_psets(&left, &right, false, &mut MergeStrategy::PreferRight).unwrap();
+        let value = result.rust_get(&path, None).unwrap();
+        assert_eq!(value.to_json().unwrap(), dummy_property(12, json!(false)));
+
+        assert!(merge_psets(&left, &right, false, &mut MergeStrategy::RaiseOnConflict).is_err());
+        assert!(merge_psets(&left, &left, false, &mut MergeStrategy::RaiseOnConflict).is_ok());
+    }
+
+    #[test]
+    fn test_merge_case2() {
+        let left = dummy_psets();
+        let mut right = dummy_psets();
+        let path = "Pset_WallCommon:id".to_string();
+        right
+            .rust_set(&path, PsetNestedValue::Any(ValueWrap(json!(5))), None)
+            .unwrap();
+        let mut paths: Vec<String> = vec![];
+        let mut callback = |path: &[String], x: &PsetNestedValue, _: &PsetNestedValue| {
+            paths.push(path.join(":"));
+            return Ok(x.clone());
+        };
+        let mut strategy = MergeStrategy::Callback(&mut callback);
+        merge_psets(&left, &right, false, &mut strategy).unwrap();
+        assert_eq!(paths, vec!["Pset_WallCommon:id"]);
+    }
+
+    #[test]
+    fn test_merge_case3() {
+        // a type set layered under an occurrence set: same properties, their own ids
+        let occurrence = dummy_psets();
+        let value = json!({
+            "Pset_WallCommon": {
+                "id": 101,
+                "IsExternal": dummy_property(102, json!(true)),
+                "Combustible": dummy_property(103, json!(false)),
+            },
+        });
+        let type_ = Psets::from_json(&value).unwrap();
+        let mut strategy = MergeStrategy::RaiseOnConflict;
+        assert!(merge_psets(&type_, &occurrence, false, &mut strategy).is_err());
+        let result = merge_psets(&type_, &occurrence, true, &mut strategy).unwrap();
+        let value = result.to_json().unwrap();
+        assert_eq!(value["Pset_WallCommon"]["id"], json!(101));
+        assert_eq!(
+            value["Pset_WallCommon"]["IsExternal"],
+            dummy_property(102, json!(true))
+        );
+        assert_eq!(
+            value["Pset_WallCommon"]["Combustible"],
+            dummy_property(103, json!(false))
+        );
+        assert_eq!(
+            value["Pset_WallCommon"]["LoadBearing"],
+            dummy_property(3, json!(false))
+        );
+
+        // actual differences are still conflicts
+        let path = "Pset_WallCommon:IsExternal".to_string();
+        let mut occurrence = occurrence.clone();
+        let value = PsetNestedValue::from_json(&dummy_property(2, json!(false))).unwrap();
+        occurrence.rust_set(&path, value, None).unwrap();
+        assert!(merge_psets(&type_, &occurrence, true, &mut strategy).is_err());
+    }
+
+    #[test]
+    fn test_select_case1() {
+        let pattern = PathPattern::glob("Pset_*Common:Is*", ":").unwrap();
+        assert!(pattern.matches("Pset_WallCommon:IsExternal", ":"));
+        assert!(!pattern.matches("Pset_WallCommon:Sub:IsExternal", ":"));
+        assert!(!pattern.matches("Pset_Wall:IsExternal", ":"));
+        let pattern = PathPattern::glob("**:Is?xternal", ":").unwrap();
+        assert!(pattern.matches("IsExternal", ":"));
+        assert!(pattern.matches("Pset_WallCommon:Sub:IsExternal", ":"));
+        let pattern = PathPattern::regex("Common:.*Bearing$").unwrap();
+        assert!(pattern.matches("Pset_WallCommon:LoadBearing", ":"));
+    }
+
+    #[test]
+    fn test_select_case2() {
+        let psets = dummy_psets();
+        let pattern = PathPattern::glob("Pset_WallCommon:*", ":").unwrap();
+        let mut selector = PsetsSelector::new(pattern);
+        assert_eq!(selector.select(&psets, None).len(), 3);
+        selector.value_type = Some("IfcBoolean".to_string());
+        assert_eq!(selector.select(&psets, None).len(), 2);
+        selector.value = Some(json!(false));
+        let result = selector.select(&psets, None);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].0, "Pset_WallCommon:LoadBearing");
+    }
+
+    #[test]
+    fn test_eq_case1() {
+        let psets = dummy_psets();
+        let mut other = dummy_psets();
+        assert_eq!(psets, other);
+        assert_eq!(hash_of(&psets), hash_of(&other));
+        let path = "Pset_WallCommon:IsExternal".to_string();
+        let value = other.rust_delete(&path, None).unwrap();
+        other.rust_set(&path, value, None).unwrap();
+        assert_eq!(psets, other);
+        assert_eq!(hash_of(&psets), hash_of(&other));
+    }
+
+    #[test]
+    fn test_eq_case2() {
+        let psets = dummy_psets();
+        let mut other = dummy_psets();
+        let path = "Pset_WallCommon:IsExternal".to_string();
+        let value = PsetNestedValue::from_json(&dummy_property(12, json!(true))).unwrap();
+        other.rust_set(&path, value, None).unwrap();
+        let path = "Pset_WallCommon:id".to_string();
+        other
+            .rust_set(&path, PsetNestedValue::Any(ValueWrap(json!(11))), None)
+            .unwrap();
+        assert_ne!(psets, other);
+        assert!(psets.eq_ignore_ids(&other));
+        let path = "Pset_WallCommon:Extra".to_string();
+        other
+            .rust_set(&path, PsetNestedValue::Any(ValueWrap(json!(1))), None)
+            .unwrap();
+        assert!(!psets.eq_ignore_ids(&other));
+    }
+
+    #[test]
+    fn test_unflatten_case1() {
+        let psets = dummy_psets();
+        let delimiter = "::".to_string();
+        let elements = psets.rust_flatten(Some(&delimiter), None);
+        let result = Psets::rust_unflatten(&elements, Some(&delimiter)).unwrap();
+        assert_eq!(result.to_json().unwrap(), psets.to_json().unwrap());
+    }
+
+    #[test]
+    fn test_unflatten_case2() {
+        let psets = Psets::from_json(&dummy_property(7, json!(true))).unwrap();
+        let elements = psets.rust_flatten(None, None);
+        let result = Psets::rust_unflatten(&elements, None).unwrap();
+        assert!(matches!(result, Psets::Pset(_)));
+        assert_eq!(result.to_json().unwrap(), psets.to_json().unwrap());
+    }
+
+    #[test]
+    fn test_unflatten_case3() {
+        let value = json!({"Pset_X": {}, "Pset_Y": {"A:B": 1, "C\\D": {}}});
+        let psets = Psets::from_json(&value).unwrap();
+        let elements = psets.rust_flatten(None, None);
+        let keys: Vec<&String> = elements.keys().collect();
+        assert_eq!(keys, vec!["Pset_X", "Pset_Y:A\\:B", "Pset_Y:C\\\\D"]);
+        let result = Psets::rust_unflatten(&elements, None).unwrap();
+        assert_eq!(result, psets);
+        let path = "Pset_Y:A\\:B".to_string();
+        assert!(psets.rust_get(&path, None).is_some());
+    }
+
+    #[test]
+    fn test_order_case1() {
+        let psets = dummy_psets();
+        let keys: Vec<String> = psets.rust_flatten(None, None).into_keys().collect();
+        let expected = vec![
+            "Pset_WallCommon:id",
+            "Pset_WallCommon:IsExternal",
+            "Pset_WallCommon:LoadBearing",
+        ];
+        assert_eq!(keys, expected);
+        let text = serde_json::to_string(&psets.to_json().unwrap()).unwrap();
+        assert!(text.starts_with(r#"{"Pset_WallCommon":{"id":1,"IsExternal":"#));
+    }
+
+    #[test]
+    fn test_order_case2() {
+        let mut psets = dummy_psets();
+        psets.sort_keys();
+        let keys: Vec<String> = psets.rust_flatten(None, None).into_keys().collect();
+        let expected = vec![
+            "Pset_WallCommon:IsExternal",
+            "Pset_WallCommon:LoadBearing",
+            "Pset_WallCommon:id",
+        ];
+        assert_eq!(keys, expected);
+    }
+
+    #[test]
+    fn test_quantity_case1() {
+        let value = json!({"Qto_WallBaseQuantities": {"id": 1, "Length": dummy_quantity(2, "IfcQuantityLength", json!(5.0))}});
+        let psets = Psets::from_json(&value).unwrap();
+        let elements = psets.rust_flatten(None, None);
+        let Some(PsetFlattenedValue::Quantity(x)) = elements.get("Qto_WallBaseQuantities:Length")
+        else {
+            panic!("expected a quantity");
+        };
+        assert_eq!(x.dimension().unwrap(), QuantityDimension::Length);
+        assert_eq!(x.magnitude().unwrap(), 5.0);
+        assert_eq!(psets.to_json().unwrap(), value);
+        assert_eq!(Psets::rust_unflatten(&elements, None).unwrap(), psets);
+    }
+
+    #[test]
+    fn test_quantity_case2() {
+        let x = Quantity::from_json(&dummy_quantity(1, "IfcQuantityArea", json!(2.5))).unwrap();
+        let y = Quantity::from_json(&dummy_quantity(2, "IfcQuantityArea", json!(1.5))).unwrap();
+        let z = Quantity::from_json(&dummy_quantity(3, "IfcQuantityVolume", json!(1.0))).unwrap();
+        let sum = x.rust_add(&y).unwrap();
+        assert_eq!(sum.magnitude().unwrap(), 4.0);
+        assert_eq!((sum.id_, sum.formula), (0, None));
+        assert_eq!(x.rust_compare(&y).unwrap(), Ordering::Greater);
+        assert!(x.rust_add(&z).is_err());
+        assert!(x.rust_compare(&z).is_err());
+        assert!(
+            Quantity::from_json(&dummy_quantity(4, "IfcQuantityCount", json!("many"))).is_err()
+        );
+        let x =
+            Quantity::from_json(&dummy_quantity(4, "IfcQuantityCount", json!(i64::MAX))).unwrap();
+        let y = Quantity::from_json(&dummy_quantity(5, "IfcQuantityCount", json!(1))).unwrap();
+        assert!(x.rust_add(&y).is_err());
+    }
+
+    #[test]
+    fn test_quantity_case3() {
+        // equality agrees with the one of the Psets holding the quantities
+        with_module(c_str!(
+            r#"
+x = {"id": 1, "class": "IfcQuantityCount", "value": 1}
+y = {"id": 1, "class": "IfcQuantityCount", "value": 1.0}
+qx, qy = module.Quantity.model_validate(x), module.Quantity.model_validate(y)
+px, py = module.Psets({"Qto_X": {"A": x}}), module.Psets({"Qto_X": {"A": y}})
+assert (qx == qy) == (px == py) == False
+assert qx == module.Quantity.model_validate(x)
+assert qx.same_magnitude(qy) and qx <= qy and not qx < qy
+"#
+        ));
+    }
+}
+
+// ----------------------------------------------------------------
+// AUXILIARY
+// ----------------------------------------------------------------
+
+fn dummy_property(id: i64, value: Value) -> Value {
+    json!({
+        "id": id,
+        "class": "IfcPropertySingleValue",
+        "value": value,
+        "value-type": "IfcBoolean",
+    })
+}
+
+fn dummy_psets() -> Psets {
+    let value = json!({
+        "Pset_WallCommon": {
+            "id": 1,
+            "IsExternal": dummy_property(2, json!(true)),
+            "LoadBearing": dummy_property(3, json!(false)),
+        },
+    });
+    return Psets::from_json(&value).unwrap();
+}
+
+fn dummy_quantity(id: i64, class_: &str, value: Value) -> Value {
+    json!({
+        "id": id,
+        "class": class_,
+        "value": value,
+    })
+}
+
+/// Runs python code with the module available as `module`
+fn with_module(code: &CStr) {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let module = PyModule::new(py, "example_package").unwrap();
+        crate::createmodule(py, &module).unwrap();
+        let locals = PyDict::new(py);
+        locals.set_item("module", module).unwrap();
+        if let Err(err) = py.run(code, None, Some(&locals)) {
+            panic!("{}", err);
+        }
+    });
+}
@@ -0,0 +1,131 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use serde_json::Value;
+use serde_json::json;
+
+use super::psets::Psets;
+use super::templates::PsetTemplateLibrary;
+use crate::models::json::base::JsonConversion;
+
+// ----------------------------------------------------------------
+// TESTS
+// ----------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_templates_case1() {
+        for schema in ["IFC4", "IFC4X3"] {
+            let library = PsetTemplateLibrary::bundled(schema).unwrap();
+            assert!(library.templates.contains_key("Pset_WallCommon"));
+            assert!(library.templates.contains_key("Qto_SlabBaseQuantities"));
+            assert_eq!(
+                &PsetTemplateLibrary::from_json(&library.to_json().unwrap()).unwrap(),
+                library
+            );
+        }
+        assert!(PsetTemplateLibrary::bundled("IFC2X3").is_err());
+    }
+
+    #[test]
+    fn test_templates_case2() {
+        let library = PsetTemplateLibrary::bundled("IFC4X3").unwrap();
+        let psets = Psets::from_json(&dummy_psets()).unwrap();
+        let result = library.rust_validate(&psets, Some("IfcWall"), None);
+        assert!(result.misapplied_psets.is_empty());
+        assert!(result.unknown_properties.is_empty());
+        assert!(result.wrong_types.is_empty());
+        assert!(result.invalid_values.is_empty());
+        assert_eq!(result.unchecked_psets, vec!["CustomPset"]);
+        assert_eq!(result.to_string(), "unchecked pset: CustomPset");
+        let result = library.rust_validate(&psets, Some("IfcDoor"), None);
+        assert_eq!(
+            result.misapplied_psets,
+            vec!["Pset_WallCommon", "Qto_WallBaseQuantities"]
+        );
+    }
+
+    #[test]
+    fn test_templates_case3() {
+        let library = PsetTemplateLibrary::bundled("IFC4X3").unwrap();
+        let mut value = dummy_psets();
+        value["Pset_WallCommon"]["Colour"] = json!("red");
+        value["Pset_WallCommon"]["IsExternal"]["value-type"] = json!("IfcLabel");
+        value["Pset_WallCommon"]["IsExternal"]["value"] = json!("yes");
+        value["Pset_WallCommon"]["Status"]["value"] = json!(["BROKEN"]);
+        value["Pset_WallCommon"]["Status"]["enumeration"]["values"] = json!(["NEW", "BROKEN"]);
+        value["Qto_WallBaseQuantities"]["Length"]["class"] = json!("IfcQuantityArea");
+        let psets = Psets::from_json(&value).unwrap();
+        let result = library.rust_validate(&psets, None, None);
+        assert_eq!(result.unknown_properties, vec!["Pset_WallCommon:Colour"]);
+        let paths: Vec<&String> = result.wrong_types.iter().map(|x| &x.path).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "Pset_WallCommon:IsExternal",
+                "Qto_WallBaseQuantities:Length"
+            ]
+        );
+        assert_eq!(result.invalid_values.len(), 1);
+        assert_eq!(result.invalid_values[0].expected, "PEnum_ElementStatus");
+    }
+
+    #[test]
+    fn test_templates_case4() {
+        let library = PsetTemplateLibrary::bundled("IFC4X3").unwrap();
+        let value = json!({"Pset_ManufacturerTypeInformation": {"ArticleNumber": "A-1"}});
+        let psets = Psets::from_json(&value).unwrap();
+        for entity_class in ["IfcWall", "IfcWallStandardCase", "IFCDOOR", "IfcElement"] {
+            let result = library.rust_validate(&psets, Some(entity_class), None);
+            assert!(result.misapplied_psets.is_empty());
+            assert!(result.undecided_psets.is_empty());
+        }
+        // sets of elements do not apply to spatial elements or to the project
+        for entity_class in ["IfcSpace", "IfcSite", "IfcProject", "IfcProduct"] {
+            let result = library.rust_validate(&psets, Some(entity_class), None);
+            assert_eq!(
+                result.misapplied_psets,
+                vec!["Pset_ManufacturerTypeInformation"]
+            );
+        }
+        // classes outside the bundled hierarchy are not taken to be elements
+        let result = library.rust_validate(&psets, Some("IfcPump"), None);
+        assert!(result.misapplied_psets.is_empty());
+        assert_eq!(
+            result.undecided_psets,
+            vec!["Pset_ManufacturerTypeInformation"]
+        );
+        let template = &library.templates["Pset_SpaceCommon"];
+        assert_eq!(template.applies_to("IfcPump"), Some(false));
+        assert_eq!(template.applies_to("IfcSpace"), Some(true));
+    }
+}
+
+// ----------------------------------------------------------------
+// AUXILIARY
+// ----------------------------------------------------------------
+
+fn dummy_psets() -> Value {
+    json!({
+        "Pset_WallCommon": {
+            "id": 1,
+            "IsExternal": {"id": 2, "class": "IfcPropertySingleValue", "value": true, "value-type": "IfcBoolean"},
+            "Status": {
+                "id": 3,
+                "class": "IfcPropertyEnumeratedValue",
+                "value": ["NEW"],
+                "value-type": "IfcLabel",
+                "enumeration": {"values": ["NEW", "EXISTING", "DEMOLISH"]},
+            },
+        },
+        "Qto_WallBaseQuantities": {
+            "id": 4,
+            "Length": {"id": 5, "class": "IfcQuantityLength", "value": 5.0},
+        },
+        "CustomPset": {"id": 6, "Anything": "goes"},
+    })
+}
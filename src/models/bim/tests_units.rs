@@ -0,0 +1,106 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use serde_json::Value;
+use serde_json::json;
+
+use super::psets::Pset;
+use super::psets::Psets;
+use super::units::Unit;
+use super::units::UnitAssignment;
+use crate::models::json::base::JsonConversion;
+
+// ----------------------------------------------------------------
+// TESTS
+// ----------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_units_case1() {
+        let mm = Unit::parse("mm").unwrap();
+        let m = Unit::parse("m").unwrap();
+        assert_eq!(mm.convert(2500.0, &m).unwrap(), 2.5);
+        let mm2 = Unit::parse("mm2").unwrap();
+        assert_eq!(
+            Unit::parse("m2").unwrap().convert(1.5, &mm2).unwrap(),
+            1.5e6
+        );
+        let celsius = Unit::parse("degC").unwrap();
+        let kelvin = Unit::parse("K").unwrap();
+        assert!((celsius.convert(20.0, &kelvin).unwrap() - 293.15).abs() < 1e-9);
+        assert!(mm.convert(1.0, &mm2).is_err());
+        assert!(Unit::parse("furlong").is_err());
+    }
+
+    #[test]
+    fn test_units_case2() {
+        let units = UnitAssignment::from_json(&dummy_unit_assignment()).unwrap();
+        assert_eq!(units.unit_of("IfcLengthMeasure").unwrap().symbol, "mm");
+        assert_eq!(units.unit_of("IfcAreaMeasure").unwrap().symbol, "ft2");
+        assert_eq!(units.unit_of("IfcMassMeasure").unwrap().symbol, "kg");
+        assert!(units.unit_of("IfcLabel").is_none());
+        let area = units.rust_convert(1.0, "IfcAreaMeasure", "m2").unwrap();
+        assert!((area - 0.09290304).abs() < 1e-12);
+        let explicit = UnitAssignment::from_json(&json!({"LENGTHUNIT": "mm"})).unwrap();
+        assert_eq!(explicit.to_json().unwrap(), json!({"LENGTHUNIT": "mm"}));
+        assert!(UnitAssignment::from_json(&json!({"LENGTHUNIT": "m2"})).is_err());
+    }
+
+    #[test]
+    fn test_units_case3() {
+        let value = json!({
+            "Pset_WallCommon": {
+                "id": 1,
+                "Width": {"id": 2, "class": "IfcPropertySingleValue", "value": 200, "value-type": "IfcLengthMeasure"},
+                "Reference": {"id": 3, "class": "IfcPropertySingleValue", "value": "W1", "value-type": "IfcLabel"},
+            },
+        });
+        let psets = Psets::from_json(&value).unwrap();
+        let units = UnitAssignment::from_json(&json!({"LENGTHUNIT": "mm"})).unwrap();
+        let result = psets
+            .rust_convert_units(&units, &UnitAssignment::default())
+            .unwrap();
+        let expected = json!(0.2);
+        assert_eq!(
+            result.to_json().unwrap()["Pset_WallCommon"]["Width"]["value"],
+            expected
+        );
+        assert_eq!(
+            result.to_json().unwrap()["Pset_WallCommon"]["Reference"],
+            value["Pset_WallCommon"]["Reference"]
+        );
+        let pset = Pset::from_json(&value["Pset_WallCommon"]["Reference"]).unwrap();
+        assert!(pset.rust_convert_to("m", &units).is_err());
+    }
+}
+
+// ----------------------------------------------------------------
+// AUXILIARY
+// ----------------------------------------------------------------
+
+fn dummy_unit_assignment() -> Value {
+    json!({
+        "id": 10,
+        "type": "IfcUnitAssignment",
+        "Units": [
+            {"id": 11, "type": "IfcSIUnit", "UnitType": "LENGTHUNIT", "Prefix": "MILLI", "Name": "METRE"},
+            {
+                "id": 12,
+                "type": "IfcConversionBasedUnit",
+                "UnitType": "AREAUNIT",
+                "Name": "square foot",
+                "ConversionFactor": {
+                    "id": 13,
+                    "type": "IfcMeasureWithUnit",
+                    "ValueComponent": {"type": "IfcAreaMeasure", "wrappedValue": 0.09290304},
+                    "UnitComponent": {"id": 14, "type": "IfcSIUnit", "UnitType": "AREAUNIT", "Prefix": null, "Name": "SQUARE_METRE"},
+                },
+            },
+            {"id": 15, "type": "IfcMonetaryUnit", "Currency": "EUR"},
+        ],
+    })
+}
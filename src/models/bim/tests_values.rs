@@ -0,0 +1,65 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use serde_json::json;
+
+use super::psets::Pset;
+use super::psets::Psets;
+use super::values::IfcLogical;
+use super::values::IfcValue;
+use crate::models::json::base::JsonConversion;
+
+// ----------------------------------------------------------------
+// TESTS
+// ----------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_values_case1() {
+        let value = IfcValue::from_json("IfcLengthMeasure", &json!(2)).unwrap();
+        assert_eq!(value, Some(IfcValue::LengthMeasure { value: 2.0 }));
+        let value = IfcValue::from_json("IfcLogical", &json!("UNKNOWN")).unwrap();
+        let expected = IfcValue::Logical {
+            value: IfcLogical::Unknown,
+        };
+        assert_eq!(value, Some(expected));
+        let value = IfcValue::from_json("IfcNotModelled", &json!(2)).unwrap();
+        assert_eq!(value, None);
+    }
+
+    #[test]
+    fn test_values_case2() {
+        assert!(IfcValue::from_json("IfcLabel", &json!(2)).is_err());
+        assert!(IfcValue::from_json("IfcInteger", &json!(2.5)).is_err());
+        assert!(IfcValue::from_json("IfcPositiveLengthMeasure", &json!(0)).is_err());
+        assert!(IfcValue::from_json("IfcNormalisedRatioMeasure", &json!(1.5)).is_err());
+    }
+
+    #[test]
+    fn test_values_case3() {
+        let pset = Pset::from_json(&dummy_property("IfcBoolean", json!(true))).unwrap();
+        let expected = IfcValue::Boolean { value: true };
+        assert_eq!(pset.rust_typed_value().unwrap(), Some(expected));
+        assert!(Pset::from_json(&dummy_property("IfcBoolean", json!("yes"))).is_err());
+        let psets =
+            json!({"Pset_WallCommon": {"IsExternal": dummy_property("IfcBoolean", json!(1))}});
+        assert!(Psets::from_json(&psets).is_err());
+    }
+}
+
+// ----------------------------------------------------------------
+// AUXILIARY
+// ----------------------------------------------------------------
+
+fn dummy_property(value_type: &str, value: serde_json::Value) -> serde_json::Value {
+    return json!({
+        "id": 1,
+        "class": "IfcPropertySingleValue",
+        "value": value,
+        "value-type": value_type,
+    });
+}
@@ -0,0 +1,461 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use indexmap::IndexMap;
+use pyo3::Bound;
+use pyo3::FromPyObject; // NOTE: needed for ValueWrap::extract_bound
+use pyo3::PyRef;
+use pyo3::PyResult;
+use pyo3::prelude::pyclass;
+use pyo3::prelude::pymethods;
+use pyo3::types::PyAny;
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+use std::f64::consts::PI;
+use std::result::Result;
+
+use crate::_core::errors::err_to_py_string;
+use crate::models::json::base::JsonConversion;
+use crate::models::json::model::ValueWrap;
+
+// ----------------------------------------------------------------
+// CONSTANTS
+// ----------------------------------------------------------------
+
+/// SI prefixes by symbol and by their IFC name (IfcSIPrefix)
+const PREFIXES: [(&str, &str, f64); 16] = [
+    ("E", "EXA", 1e18),
+    ("P", "PETA", 1e15),
+    ("T", "TERA", 1e12),
+    ("G", "GIGA", 1e9),
+    ("M", "MEGA", 1e6),
+    ("k", "KILO", 1e3),
+    ("h", "HECTO", 1e2),
+    ("da", "DECA", 1e1),
+    ("d", "DECI", 1e-1),
+    ("c", "CENTI", 1e-2),
+    ("m", "MILLI", 1e-3),
+    ("u", "MICRO", 1e-6),
+    ("n", "NANO", 1e-9),
+    ("p", "PICO", 1e-12),
+    ("f", "FEMTO", 1e-15),
+    ("a", "ATTO", 1e-18),
+];
+
+/// Unit types (IfcUnitEnum) with their SI unit, used where no unit is assigned
+const SI_UNITS: [(&str, &str); 10] = [
+    ("LENGTHUNIT", "m"),
+    ("AREAUNIT", "m2"),
+    ("VOLUMEUNIT", "m3"),
+    ("MASSUNIT", "kg"),
+    ("TIMEUNIT", "s"),
+    ("PLANEANGLEUNIT", "rad"),
+    ("THERMODYNAMICTEMPERATUREUNIT", "K"),
+    ("POWERUNIT", "W"),
+    ("PRESSUREUNIT", "Pa"),
+    ("THERMALTRANSMITTANCEUNIT", "W/(m2K)"),
+];
+
+// ----------------------------------------------------------------
+// STRUCTS
+// ----------------------------------------------------------------
+
+/// Unit of measure, relating values to the SI unit of its type
+#[derive(Clone, Debug, PartialEq)]
+pub struct Unit {
+    pub symbol: String,
+    pub unit_type: String,
+    /// factor to the SI unit
+    scale: f64,
+    /// offset to the SI unit, after scaling (temperatures only)
+    offset: f64,
+}
+
+/// Units of a project (IfcUnitAssignment), falling back to SI units for unassigned types
+#[pyclass]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UnitAssignment {
+    pub units: IndexMap<String, Unit>,
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF Unit
+// ----------------------------------------------------------------
+
+/// Rust implementation of struct
+impl Unit {
+    /// Parses a unit symbol, e.g. `mm`, `m2`, `kN`, `ft3`, `degC`
+    pub fn parse(symbol: &str) -> Result<Self, String> {
+        let unknown = || format!("unknown unit '{}'", symbol);
+        let special = match symbol {
+            "l" | "L" => Some(("VOLUMEUNIT", 1e-3, 0.0)),
+            "t" => Some(("MASSUNIT", 1e3, 0.0)),
+            "lb" => Some(("MASSUNIT", 0.45359237, 0.0)),
+            "min" => Some(("TIMEUNIT", 60.0, 0.0)),
+            "h" => Some(("TIMEUNIT", 3600.0, 0.0)),
+            "d" => Some(("TIMEUNIT", 86400.0, 0.0)),
+            "deg" => Some(("PLANEANGLEUNIT", PI / 180.0, 0.0)),
+            "degC" => Some(("THERMODYNAMICTEMPERATUREUNIT", 1.0, 273.15)),
+            "degF" => Some((
+                "THERMODYNAMICTEMPERATUREUNIT",
+                5.0 / 9.0,
+                459.67 * 5.0 / 9.0,
+            )),
+            "bar" => Some(("PRESSUREUNIT", 1e5, 0.0)),
+            "W/(m2K)" | "W/m2K" => Some(("THERMALTRANSMITTANCEUNIT", 1.0, 0.0)),
+            _ => None,
+        };
+        if let Some((unit_type, scale, offset)) = special {
+            return Ok(Self::new(symbol, unit_type, scale, offset));
+        }
+
+        // powers only apply to lengths, giving areas and volumes
+        let (stem, power) = match symbol.strip_suffix('2') {
+            Some(stem) => (stem, 2),
+            None => match symbol.strip_suffix('3') {
+                Some(stem) => (stem, 3),
+                None => (symbol, 1),
+            },
+        };
+        let (base, factor) = match stem {
+            "in" => ("m", 0.0254),
+            "ft" => ("m", 0.3048),
+            _ => {
+                let base = ["Pa", "rad", "m", "g", "s", "K", "W"]
+                    .into_iter()
+                    .find(|x| stem.ends_with(x))
+                    .ok_or_else(unknown)?;
+                let prefix = &stem[..stem.len() - base.len()];
+                let factor = match prefix {
+                    "" => 1.0,
+                    _ => PREFIXES
+                        .iter()
+                        .find(|(x, _, _)| *x == prefix)
+                        .map(|(_, _, x)| *x)
+                        .ok_or_else(unknown)?,
+                };
+                (base, factor)
+            }
+        };
+        let unit_type = match (base, power) {
+            ("m", 1) => "LENGTHUNIT",
+            ("m", 2) => "AREAUNIT",
+            ("m", 3) => "VOLUMEUNIT",
+            ("g", 1) => "MASSUNIT",
+            ("s", 1) => "TIMEUNIT",
+            ("rad", 1) => "PLANEANGLEUNIT",
+            ("K", 1) => "THERMODYNAMICTEMPERATUREUNIT",
+            ("W", 1) => "POWERUNIT",
+            ("Pa", 1) => "PRESSUREUNIT",
+            _ => {
+                return Err(unknown());
+            }
+        };
+        // NOTE: the SI unit of mass is the kilogram
+        let factor = match base {
+            "g" => factor * 1e-3,
+            _ => factor,
+        };
+        return Ok(Self::new(symbol, unit_type, factor.powi(power), 0.0));
+    }
+
+    /// SI unit of a unit type (IfcUnitEnum)
+    pub fn si(unit_type: &str) -> Option<Self> {
+        let (_, symbol) = SI_UNITS.iter().find(|(x, _)| *x == unit_type)?;
+        return Self::parse(symbol).ok();
+    }
+
+    /// Reads a unit in the form of `get_info(recursive=True)` from ifcopenshell,
+    /// returning `None` for kinds of units without conversion (derived and monetary units)
+    pub fn from_ifc(value: &Value) -> Result<Option<Self>, String> {
+        let class_ = value
+            .get("type")
+            .or(value.get("class"))
+            .and_then(Value::as_str)
+            .ok_or("unit requires a type")?;
+        let unit_type = value.get("UnitType").and_then(Value::as_str);
+        match class_ {
+            "IfcSIUnit" => {
+                let name = value
+                    .get("Name")
+                    .and_then(Value::as_str)
+                    .ok_or("SI unit requires a name")?;
+                let base = match name {
+                    "METRE" => "m",
+                    "SQUARE_METRE" => "m2",
+                    "CUBIC_METRE" => "m3",
+                    "GRAM" => "g",
+                    "SECOND" => "s",
+                    "RADIAN" => "rad",
+                    "KELVIN" => "K",
+                    "DEGREE_CELSIUS" => "degC",
+                    "WATT" => "W",
+                    "PASCAL" => "Pa",
+                    _ => {
+                        return Ok(None);
+                    }
+                };
+                let prefix = match value.get("Prefix").and_then(Value::as_str) {
+                    None => "",
+                    Some(prefix) => PREFIXES
+                        .iter()
+                        .find(|(_, x, _)| *x == prefix)
+                        .map(|(x, _, _)| *x)
+                        .ok_or_else(|| format!("unknown SI prefix '{}'", prefix))?,
+                };
+                let result = Self::parse(&format!("{}{}", prefix, base))?;
+                return Ok(Some(result));
+            }
+            "IfcConversionBasedUnit" | "IfcConversionBasedUnitWithOffset" => {
+                let name = value
+                    .get("Name")
+                    .and_then(Value::as_str)
+                    .ok_or("conversion based unit requires a name")?;
+                let unit_type = unit_type.ok_or("conversion based unit requires a unit type")?;
+                let factor = value
+                    .get("ConversionFactor")
+                    .ok_or("conversion based unit requires a conversion factor")?;
+                let component = factor
+                    .get("ValueComponent")
+                    .and_then(|x| x.get("wrappedValue").unwrap_or(x).as_f64())
+                    .ok_or("conversion factor requires a numeric value component")?;
+                let Some(base) = factor
+                    .get("UnitComponent")
+                    .map(Self::from_ifc)
+                    .transpose()?
+                    .flatten()
+                else {
+                    return Ok(None);
+                };
+                let offset = value
+                    .get("ConversionOffset")
+                    .and_then(Value::as_f64)
+                    .unwrap_or(0.0);
+                let symbol = match name.to_uppercase().replace('_', " ").as_str() {
+                    "INCH" => "in".to_string(),
+                    "FOOT" => "ft".to_string(),
+                    "SQUARE INCH" => "in2".to_string(),
+                    "SQUARE FOOT" => "ft2".to_string(),
+                    "CUBIC INCH" => "in3".to_string(),
+                    "CUBIC FOOT" => "ft3".to_string(),
+                    "POUND" => "lb".to_string(),
+                    "DEGREE" => "deg".to_string(),
+                    "LITRE" => "l".to_string(),
+                    _ => name.to_lowercase(),
+                };
+                let scale = component * base.scale;
+                let offset = (offset * component + base.offset / base.scale) * base.scale;
+                let result = Self::new(&symbol, unit_type, scale, offset);
+                return Ok(Some(result));
+            }
+            _ => {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Converts a value of this unit into another unit of the same type
+    pub fn convert(&self, value: f64, other: &Self) -> Result<f64, String> {
+        if self.unit_type != other.unit_type {
+            return Err(format!(
+                "cannot convert {} ({}) into {} ({})",
+                self.symbol, self.unit_type, other.symbol, other.unit_type
+            ));
+        }
+        if self.offset == 0.0 && other.offset == 0.0 {
+            return Ok(value * (self.scale / other.scale));
+        }
+        return Ok((value * self.scale + self.offset - other.offset) / other.scale);
+    }
+
+    fn new(symbol: &str, unit_type: &str, scale: f64, offset: f64) -> Self {
+        return Self {
+            symbol: symbol.to_string(),
+            unit_type: unit_type.to_string(),
+            scale,
+            offset,
+        };
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF UnitAssignment
+// ----------------------------------------------------------------
+
+#[pymethods]
+impl UnitAssignment {
+    #[new]
+    #[pyo3(signature = (units=None, /))]
+    pub fn new(units: Option<IndexMap<String, String>>) -> PyResult<Self> {
+        let value = json!(units.unwrap_or_default());
+        let result = Self::from_json(&value).map_err(err_to_py_string)?;
+        return Ok(result);
+    }
+
+    #[staticmethod]
+    fn __class_name__() -> String {
+        "UnitAssignment".to_string()
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (value, /))]
+    pub fn model_validate(value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let raw = ValueWrap::extract_bound(value)?;
+        let value = raw.to_json().map_err(err_to_py_string)?;
+        let result = Self::from_json(&value).map_err(err_to_py_string)?;
+        return Ok(result);
+    }
+
+    /// Symbols of the assigned units by unit type
+    #[getter]
+    #[pyo3(name = "units")]
+    fn py_units(&self) -> IndexMap<String, String> {
+        return self
+            .units
+            .iter()
+            .map(|(key, x)| (key.clone(), x.symbol.clone()))
+            .collect();
+    }
+
+    /// Symbol of the unit of a measure type, or `None` if the type has no unit
+    #[pyo3(name = "unit_of", signature = (value_type, /))]
+    fn py_unit_of(&self, value_type: &str) -> Option<String> {
+        return self.unit_of(value_type).map(|x| x.symbol);
+    }
+
+    #[pyo3(name = "convert", signature = (value, value_type, unit, /))]
+    fn py_convert(&self, value: f64, value_type: &str, unit: &str) -> PyResult<f64> {
+        return self
+            .rust_convert(value, value_type, unit)
+            .map_err(err_to_py_string);
+    }
+
+    fn __eq__(&self, other: PyRef<'_, Self>) -> bool {
+        self == &*other
+    }
+
+    pub fn __str__(&self) -> PyResult<String> {
+        Ok(self.to_string())
+    }
+}
+
+/// Rust implementation of struct
+impl UnitAssignment {
+    /// Unit of a measure type, as assigned or else the SI unit
+    pub fn unit_of(&self, value_type: &str) -> Option<Unit> {
+        let unit_type = unit_type_of(value_type)?;
+        match self.units.get(unit_type) {
+            Some(x) => Some(x.clone()),
+            None => Unit::si(unit_type),
+        }
+    }
+
+    /// Converts a value of a measure type from the assigned unit into another unit
+    pub fn rust_convert(&self, value: f64, value_type: &str, unit: &str) -> Result<f64, String> {
+        let source = self
+            .unit_of(value_type)
+            .ok_or_else(|| format!("{} has no unit", value_type))?;
+        let target = Unit::parse(unit)?;
+        return source.convert(value, &target);
+    }
+
+    /// Converts a json value of a measure type, leaving nulls as they are
+    pub fn convert_json(
+        &self,
+        value: &Value,
+        value_type: &str,
+        unit: &str,
+    ) -> Result<Value, String> {
+        match value {
+            Value::Null => Ok(Value::Null),
+            Value::Number(x) => {
+                let x = x.as_f64().unwrap_or_default();
+                return Ok(json!(self.rust_convert(x, value_type, unit)?));
+            }
+            _ => Err(format!("cannot convert non-numeric value {}", value)),
+        }
+    }
+}
+
+impl ToString for UnitAssignment {
+    fn to_string(&self) -> String {
+        match self.to_json() {
+            Ok(value) => {
+                return format!("{}({})", Self::__class_name__(), value);
+            }
+            Err(err) => {
+                panic!("{:?}", err);
+            }
+        }
+    }
+}
+
+impl JsonConversion<Value> for UnitAssignment {
+    /// Reads either an IfcUnitAssignment (with a list of `Units`)
+    /// or a mapping of unit types onto symbols, e.g. `{"LENGTHUNIT": "mm"}`
+    fn from_json(value: &Value) -> Result<Self, String> {
+        let mut units = IndexMap::<String, Unit>::new();
+        match value.get("Units") {
+            Some(Value::Array(items)) => {
+                for item in items {
+                    if let Some(unit) = Unit::from_ifc(item)? {
+                        units.insert(unit.unit_type.clone(), unit);
+                    }
+                }
+            }
+            Some(_) => {
+                return Err("units of a unit assignment must be a list".to_string());
+            }
+            None => {
+                let Value::Object(object) = value else {
+                    return Err("unit assignment must be an object".to_string());
+                };
+                for (unit_type, symbol) in object {
+                    let symbol = symbol
+                        .as_str()
+                        .ok_or_else(|| format!("unit of {} must be a symbol", unit_type))?;
+                    let unit = Unit::parse(symbol)?;
+                    if &unit.unit_type != unit_type {
+                        return Err(format!("{} is not a {}", symbol, unit_type));
+                    }
+                    units.insert(unit_type.clone(), unit);
+                }
+            }
+        }
+        return Ok(Self { units });
+    }
+
+    fn to_json(&self) -> Result<Value, String> {
+        let result: Map<String, Value> = self
+            .units
+            .iter()
+            .map(|(key, x)| (key.clone(), json!(x.symbol)))
+            .collect();
+        return Ok(Value::Object(result));
+    }
+}
+
+// ----------------------------------------------------------------
+// AUXILIARY METHODS
+// ----------------------------------------------------------------
+
+/// Unit type (IfcUnitEnum) of a measure type, or `None` for unitless types
+pub fn unit_type_of(value_type: &str) -> Option<&'static str> {
+    match value_type {
+        "IfcLengthMeasure" | "IfcPositiveLengthMeasure" | "IfcNonNegativeLengthMeasure" => {
+            Some("LENGTHUNIT")
+        }
+        "IfcAreaMeasure" => Some("AREAUNIT"),
+        "IfcVolumeMeasure" => Some("VOLUMEUNIT"),
+        "IfcMassMeasure" => Some("MASSUNIT"),
+        "IfcTimeMeasure" => Some("TIMEUNIT"),
+        "IfcPlaneAngleMeasure" | "IfcPositivePlaneAngleMeasure" => Some("PLANEANGLEUNIT"),
+        "IfcThermodynamicTemperatureMeasure" => Some("THERMODYNAMICTEMPERATUREUNIT"),
+        "IfcPowerMeasure" => Some("POWERUNIT"),
+        "IfcPressureMeasure" => Some("PRESSUREUNIT"),
+        "IfcThermalTransmittanceMeasure" => Some("THERMALTRANSMITTANCEUNIT"),
+        _ => None,
+    }
+}
@@ -0,0 +1,288 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use pyo3::PyResult;
+use pyo3::prelude::pyclass;
+use pyo3::prelude::pymethods;
+use serde_json::Number;
+use serde_json::Value;
+use std::result::Result;
+
+// ----------------------------------------------------------------
+// STRUCTS
+// ----------------------------------------------------------------
+
+/// Three-valued logic of IfcLogical
+#[pyclass(eq, eq_int)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IfcLogical {
+    #[pyo3(name = "TRUE")]
+    True,
+    #[pyo3(name = "FALSE")]
+    False,
+    #[pyo3(name = "UNKNOWN")]
+    Unknown,
+}
+
+/// Value of a property, typed by its IFC defined type
+#[pyclass(eq)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum IfcValue {
+    Boolean { value: bool },
+    Logical { value: IfcLogical },
+    Label { value: String },
+    Text { value: String },
+    Identifier { value: String },
+    Integer { value: i64 },
+    Real { value: f64 },
+    CountMeasure { value: f64 },
+    LengthMeasure { value: f64 },
+    PositiveLengthMeasure { value: f64 },
+    AreaMeasure { value: f64 },
+    VolumeMeasure { value: f64 },
+    MassMeasure { value: f64 },
+    TimeMeasure { value: f64 },
+    PlaneAngleMeasure { value: f64 },
+    RatioMeasure { value: f64 },
+    PositiveRatioMeasure { value: f64 },
+    NormalisedRatioMeasure { value: f64 },
+    PowerMeasure { value: f64 },
+    PressureMeasure { value: f64 },
+    ThermodynamicTemperatureMeasure { value: f64 },
+    ThermalTransmittanceMeasure { value: f64 },
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF IfcLogical
+// ----------------------------------------------------------------
+
+/// Rust implementation of struct
+impl IfcLogical {
+    /// Accepts booleans as well as the STEP forms (`.T.`, `.F.`, `.U.`) and their names
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::Bool(true) => Ok(Self::True),
+            Value::Bool(false) => Ok(Self::False),
+            Value::String(x) => match x.trim_matches('.').to_uppercase().as_str() {
+                "T" | "TRUE" => Ok(Self::True),
+                "F" | "FALSE" => Ok(Self::False),
+                "U" | "UNKNOWN" => Ok(Self::Unknown),
+                _ => Err(format!("invalid logical '{}'", x)),
+            },
+            _ => Err(format!("invalid logical {}", value)),
+        }
+    }
+
+    pub fn to_json(self) -> Value {
+        match self {
+            Self::True => Value::Bool(true),
+            Self::False => Value::Bool(false),
+            Self::Unknown => Value::String("UNKNOWN".to_string()),
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF IfcValue
+// ----------------------------------------------------------------
+
+#[pymethods]
+impl IfcValue {
+    #[getter]
+    #[pyo3(name = "value_type")]
+    fn py_value_type(&self) -> String {
+        self.value_type().to_string()
+    }
+
+    pub fn __str__(&self) -> PyResult<String> {
+        Ok(self.to_string())
+    }
+}
+
+/// Rust implementation of struct
+impl IfcValue {
+    /// Parses a value of the given IFC type, returning `None` for types not modelled here
+    pub fn from_json(value_type: &str, value: &Value) -> Result<Option<Self>, String> {
+        let result = match value_type {
+            "IfcBoolean" => Self::Boolean {
+                value: value.as_bool().ok_or_else(|| mismatch(value_type, value))?,
+            },
+            "IfcLogical" => Self::Logical {
+                value: IfcLogical::from_json(value).map_err(|_| mismatch(value_type, value))?,
+            },
+            "IfcLabel" => Self::Label {
+                value: as_text(value_type, value)?,
+            },
+            "IfcText" => Self::Text {
+                value: as_text(value_type, value)?,
+            },
+            "IfcIdentifier" => Self::Identifier {
+                value: as_text(value_type, value)?,
+            },
+            "IfcInteger" => Self::Integer {
+                value: value.as_i64().ok_or_else(|| mismatch(value_type, value))?,
+            },
+            "IfcReal" => Self::Real {
+                value: as_number(value_type, value)?,
+            },
+            "IfcCountMeasure" => Self::CountMeasure {
+                value: as_number(value_type, value)?,
+            },
+            "IfcLengthMeasure" => Self::LengthMeasure {
+                value: as_number(value_type, value)?,
+            },
+            "IfcPositiveLengthMeasure" => Self::PositiveLengthMeasure {
+                value: as_positive(value_type, value)?,
+            },
+            "IfcAreaMeasure" => Self::AreaMeasure {
+                value: as_number(value_type, value)?,
+            },
+            "IfcVolumeMeasure" => Self::VolumeMeasure {
+                value: as_number(value_type, value)?,
+            },
+            "IfcMassMeasure" => Self::MassMeasure {
+                value: as_number(value_type, value)?,
+            },
+            "IfcTimeMeasure" => Self::TimeMeasure {
+                value: as_number(value_type, value)?,
+            },
+            "IfcPlaneAngleMeasure" => Self::PlaneAngleMeasure {
+                value: as_number(value_type, value)?,
+            },
+            "IfcRatioMeasure" => Self::RatioMeasure {
+                value: as_number(value_type, value)?,
+            },
+            "IfcPositiveRatioMeasure" => Self::PositiveRatioMeasure {
+                value: as_positive(value_type, value)?,
+            },
+            "IfcNormalisedRatioMeasure" => {
+                let x = as_number(value_type, value)?;
+                if !(0.0..=1.0).contains(&x) {
+                    return Err(format!("{} must lie within [0, 1], got {}", value_type, x));
+                }
+                Self::NormalisedRatioMeasure { value: x }
+            }
+            "IfcPowerMeasure" => Self::PowerMeasure {
+                value: as_number(value_type, value)?,
+            },
+            "IfcPressureMeasure" => Self::PressureMeasure {
+                value: as_number(value_type, value)?,
+            },
+            "IfcThermodynamicTemperatureMeasure" => Self::ThermodynamicTemperatureMeasure {
+                value: as_number(value_type, value)?,
+            },
+            "IfcThermalTransmittanceMeasure" => Self::ThermalTransmittanceMeasure {
+                value: as_number(value_type, value)?,
+            },
+            _ => {
+                return Ok(None);
+            }
+        };
+        return Ok(Some(result));
+    }
+
+    /// Name of the IFC defined type
+    pub fn value_type(&self) -> &'static str {
+        match self {
+            Self::Boolean { .. } => "IfcBoolean",
+            Self::Logical { .. } => "IfcLogical",
+            Self::Label { .. } => "IfcLabel",
+            Self::Text { .. } => "IfcText",
+            Self::Identifier { .. } => "IfcIdentifier",
+            Self::Integer { .. } => "IfcInteger",
+            Self::Real { .. } => "IfcReal",
+            Self::CountMeasure { .. } => "IfcCountMeasure",
+            Self::LengthMeasure { .. } => "IfcLengthMeasure",
+            Self::PositiveLengthMeasure { .. } => "IfcPositiveLengthMeasure",
+            Self::AreaMeasure { .. } => "IfcAreaMeasure",
+            Self::VolumeMeasure { .. } => "IfcVolumeMeasure",
+            Self::MassMeasure { .. } => "IfcMassMeasure",
+            Self::TimeMeasure { .. } => "IfcTimeMeasure",
+            Self::PlaneAngleMeasure { .. } => "IfcPlaneAngleMeasure",
+            Self::RatioMeasure { .. } => "IfcRatioMeasure",
+            Self::PositiveRatioMeasure { .. } => "IfcPositiveRatioMeasure",
+            Self::NormalisedRatioMeasure { .. } => "IfcNormalisedRatioMeasure",
+            Self::PowerMeasure { .. } => "IfcPowerMeasure",
+            Self::PressureMeasure { .. } => "IfcPressureMeasure",
+            Self::ThermodynamicTemperatureMeasure { .. } => "IfcThermodynamicTemperatureMeasure",
+            Self::ThermalTransmittanceMeasure { .. } => "IfcThermalTransmittanceMeasure",
+        }
+    }
+
+    /// Numeric value, for integers and measures
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Boolean { .. }
+            | Self::Logical { .. }
+            | Self::Label { .. }
+            | Self::Text { .. }
+            | Self::Identifier { .. } => None,
+            Self::Integer { value } => Some(*value as f64),
+            Self::Real { value }
+            | Self::CountMeasure { value }
+            | Self::LengthMeasure { value }
+            | Self::PositiveLengthMeasure { value }
+            | Self::AreaMeasure { value }
+            | Self::VolumeMeasure { value }
+            | Self::MassMeasure { value }
+            | Self::TimeMeasure { value }
+            | Self::PlaneAngleMeasure { value }
+            | Self::RatioMeasure { value }
+            | Self::PositiveRatioMeasure { value }
+            | Self::NormalisedRatioMeasure { value }
+            | Self::PowerMeasure { value }
+            | Self::PressureMeasure { value }
+            | Self::ThermodynamicTemperatureMeasure { value }
+            | Self::ThermalTransmittanceMeasure { value } => Some(*value),
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        match self {
+            Self::Boolean { value } => Value::Bool(*value),
+            Self::Logical { value } => value.to_json(),
+            Self::Label { value } | Self::Text { value } | Self::Identifier { value } => {
+                Value::String(value.clone())
+            }
+            Self::Integer { value } => Value::Number(Number::from(*value)),
+            _ => self
+                .as_f64()
+                .and_then(Number::from_f64)
+                .map_or(Value::Null, Value::Number),
+        }
+    }
+}
+
+impl ToString for IfcValue {
+    fn to_string(&self) -> String {
+        return format!("{}({})", self.value_type(), self.to_json());
+    }
+}
+
+// ----------------------------------------------------------------
+// AUXILIARY METHODS
+// ----------------------------------------------------------------
+
+fn mismatch(value_type: &str, value: &Value) -> String {
+    format!("value {} does not match type {}", value, value_type)
+}
+
+fn as_text(value_type: &str, value: &Value) -> Result<String, String> {
+    match value {
+        Value::String(x) => Ok(x.clone()),
+        _ => Err(mismatch(value_type, value)),
+    }
+}
+
+fn as_number(value_type: &str, value: &Value) -> Result<f64, String> {
+    value.as_f64().ok_or_else(|| mismatch(value_type, value))
+}
+
+fn as_positive(value_type: &str, value: &Value) -> Result<f64, String> {
+    let x = as_number(value_type, value)?;
+    if x <= 0.0 {
+        return Err(format!("{} must be positive, got {}", value_type, x));
+    }
+    return Ok(x);
+}
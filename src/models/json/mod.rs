@@ -1,2 +1,6 @@
 pub mod base;
 pub mod model;
+pub mod patch;
+
+#[cfg(test)]
+pub mod tests_patch;
@@ -29,7 +29,7 @@ use crate::_core::errors::err_to_py_string;
 // STRUCTURES/TYPES
 // ----------------------------------------------------------------
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ValueWrap(pub Value);
 
 // ----------------------------------------------------------------
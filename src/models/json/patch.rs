@@ -0,0 +1,239 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use std::result::Result;
+
+use super::base::JsonConversion;
+use crate::_core::errors::err_to_string;
+
+// ----------------------------------------------------------------
+// STRUCTURES/TYPES
+// ----------------------------------------------------------------
+
+/// Single operation of a JSON Patch document (RFC 6902)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase", deny_unknown_fields)]
+pub enum JsonPatchOperation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: Value },
+}
+
+/// JSON Patch document (RFC 6902)
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct JsonPatch(pub Vec<JsonPatchOperation>);
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF JsonPatch
+// ----------------------------------------------------------------
+
+impl JsonPatch {
+    /// Creates the patch turning `old` into `new` (arrays are replaced as a whole)
+    pub fn new(old: &Value, new: &Value) -> Self {
+        let mut operations = vec![];
+        diff_at(old, new, "", &mut operations);
+        return Self(operations);
+    }
+
+    /// Applies all operations, failing without partial changes if any operation fails
+    pub fn apply(&self, value: &Value) -> Result<Value, String> {
+        let mut result = value.clone();
+        for operation in self.0.iter() {
+            apply_operation(&mut result, operation)?;
+        }
+        return Ok(result);
+    }
+}
+
+impl JsonConversion<Value> for JsonPatch {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        let result = Self::deserialize(value).map_err(err_to_string)?;
+        return Ok(result);
+    }
+
+    fn to_json(&self) -> Result<Value, String> {
+        let result = serde_json::to_value(self).map_err(err_to_string)?;
+        return Ok(result);
+    }
+}
+
+// ----------------------------------------------------------------
+// AUXILIARY METHODS - JSON POINTER (RFC 6901)
+// ----------------------------------------------------------------
+
+pub fn escape_pointer_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+pub fn unescape_pointer_token(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+fn split_pointer(pointer: &str) -> Result<Vec<String>, String> {
+    if pointer.is_empty() {
+        return Ok(vec![]);
+    }
+    let Some(rest) = pointer.strip_prefix('/') else {
+        return Err(format!("invalid pointer '{}'", pointer));
+    };
+    return Ok(rest.split('/').map(unescape_pointer_token).collect());
+}
+
+fn array_index(token: &str, len: usize, allow_end: bool) -> Result<usize, String> {
+    if allow_end && token == "-" {
+        return Ok(len);
+    }
+    let valid = !token.is_empty() && token.chars().all(|c| c.is_ascii_digit());
+    let index = match (valid, token.len() > 1 && token.starts_with('0')) {
+        (true, false) => token.parse::<usize>().map_err(err_to_string)?,
+        _ => return Err(format!("invalid array index '{}'", token)),
+    };
+    let bound = if allow_end { len + 1 } else { len };
+    if index >= bound {
+        return Err(format!("array index {} out of bounds", index));
+    }
+    return Ok(index);
+}
+
+fn resolve<'a>(value: &'a Value, tokens: &[String]) -> Result<&'a Value, String> {
+    let mut current = value;
+    for token in tokens.iter() {
+        current = match current {
+            Value::Object(elements) => elements
+                .get(token)
+                .ok_or_else(|| format!("no entry '{}'", token))?,
+            Value::Array(elements) => &elements[array_index(token, elements.len(), false)?],
+            _ => return Err(format!("cannot descend into '{}'", token)),
+        };
+    }
+    return Ok(current);
+}
+
+fn resolve_mut<'a>(value: &'a mut Value, tokens: &[String]) -> Result<&'a mut Value, String> {
+    let mut current = value;
+    for token in tokens.iter() {
+        current = match current {
+            Value::Object(elements) => elements
+                .get_mut(token)
+                .ok_or_else(|| format!("no entry '{}'", token))?,
+            Value::Array(elements) => {
+                let index = array_index(token, elements.len(), false)?;
+                &mut elements[index]
+            }
+            _ => return Err(format!("cannot descend into '{}'", token)),
+        };
+    }
+    return Ok(current);
+}
+
+// ----------------------------------------------------------------
+// AUXILIARY METHODS - OPERATIONS
+// ----------------------------------------------------------------
+
+fn add_at(document: &mut Value, pointer: &str, value: Value) -> Result<(), String> {
+    let tokens = split_pointer(pointer)?;
+    let Some((key, parent)) = tokens.split_last() else {
+        *document = value;
+        return Ok(());
+    };
+    match resolve_mut(document, parent)? {
+        Value::Object(elements) => {
+            elements.insert(key.clone(), value);
+        }
+        Value::Array(elements) => {
+            let index = array_index(key, elements.len(), true)?;
+            elements.insert(index, value);
+        }
+        _ => return Err(format!("cannot add at '{}'", pointer)),
+    }
+    return Ok(());
+}
+
+fn remove_at(document: &mut Value, pointer: &str) -> Result<Value, String> {
+    let tokens = split_pointer(pointer)?;
+    let Some((key, parent)) = tokens.split_last() else {
+        return Err("cannot remove the root".to_string());
+    };
+    match resolve_mut(document, parent)? {
+        Value::Object(elements) => elements
+            .shift_remove(key)
+            .ok_or_else(|| format!("no entry at '{}'", pointer)),
+        Value::Array(elements) => {
+            let index = array_index(key, elements.len(), false)?;
+            return Ok(elements.remove(index));
+        }
+        _ => Err(format!("cannot remove at '{}'", pointer)),
+    }
+}
+
+fn apply_operation(document: &mut Value, operation: &JsonPatchOperation) -> Result<(), String> {
+    match operation {
+        JsonPatchOperation::Add { path, value } => {
+            return add_at(document, path, value.clone());
+        }
+        JsonPatchOperation::Remove { path } => {
+            remove_at(document, path)?;
+            return Ok(());
+        }
+        JsonPatchOperation::Replace { path, value } => {
+            let tokens = split_pointer(path)?;
+            *resolve_mut(document, &tokens)? = value.clone();
+            return Ok(());
+        }
+        JsonPatchOperation::Move { from, path } => {
+            if path.starts_with(&format!("{}/", from)) {
+                return Err(format!("cannot move '{}' into itself", from));
+            }
+            let value = remove_at(document, from)?;
+            return add_at(document, path, value);
+        }
+        JsonPatchOperation::Copy { from, path } => {
+            let tokens = split_pointer(from)?;
+            let value = resolve(document, &tokens)?.clone();
+            return add_at(document, path, value);
+        }
+        JsonPatchOperation::Test { path, value } => {
+            let tokens = split_pointer(path)?;
+            if resolve(document, &tokens)? != value {
+                return Err(format!("test failed at '{}'", path));
+            }
+            return Ok(());
+        }
+    }
+}
+
+fn diff_at(old: &Value, new: &Value, pointer: &str, operations: &mut Vec<JsonPatchOperation>) {
+    if old == new {
+        return;
+    }
+    match (old, new) {
+        (Value::Object(old_elements), Value::Object(new_elements)) => {
+            for (key, x) in old_elements.iter() {
+                let path = format!("{}/{}", pointer, escape_pointer_token(key));
+                match new_elements.get(key) {
+                    Some(y) => diff_at(x, y, &path, operations),
+                    None => operations.push(JsonPatchOperation::Remove { path }),
+                }
+            }
+            for (key, y) in new_elements.iter() {
+                if !old_elements.contains_key(key) {
+                    let path = format!("{}/{}", pointer, escape_pointer_token(key));
+                    let value = y.clone();
+                    operations.push(JsonPatchOperation::Add { path, value });
+                }
+            }
+        }
+        _ => {
+            let path = pointer.to_string();
+            let value = new.clone();
+            operations.push(JsonPatchOperation::Replace { path, value });
+        }
+    }
+}
@@ -0,0 +1,102 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use serde_json::Value;
+use serde_json::json;
+
+use super::base::JsonConversion;
+use super::patch::JsonPatch;
+use super::patch::JsonPatchOperation;
+
+// ----------------------------------------------------------------
+// TESTS
+// ----------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case1() {
+        let old = dummy_document();
+        let mut new = dummy_document();
+        new["Pset_WallCommon"]["FireRating"]["value"] = json!("F90");
+        new["Pset_WallCommon"]
+            .as_object_mut()
+            .unwrap()
+            .shift_remove("IsExternal");
+        new["Pset_Custom/Extra"] = json!({"Rating": [1, 2]});
+
+        let patch = JsonPatch::new(&old, &new);
+        let expected = vec![
+            JsonPatchOperation::Remove {
+                path: "/Pset_WallCommon/IsExternal".to_string(),
+            },
+            JsonPatchOperation::Replace {
+                path: "/Pset_WallCommon/FireRating/value".to_string(),
+                value: json!("F90"),
+            },
+            JsonPatchOperation::Add {
+                path: "/Pset_Custom~1Extra".to_string(),
+                value: json!({"Rating": [1, 2]}),
+            },
+        ];
+        assert_eq!(patch.0, expected);
+        assert_eq!(patch.apply(&old).unwrap(), new);
+    }
+
+    #[test]
+    fn test_case2() {
+        let raw = json!([
+            {"op": "test", "path": "/Pset_WallCommon/id", "value": 1},
+            {"op": "copy", "from": "/Pset_WallCommon/FireRating", "path": "/Pset_WallCommon/Copy"},
+            {"op": "move", "from": "/Pset_WallCommon/Copy", "path": "/Moved"},
+            {"op": "add", "path": "/List", "value": [1, 3]},
+            {"op": "add", "path": "/List/1", "value": 2},
+            {"op": "add", "path": "/List/-", "value": 4},
+            {"op": "remove", "path": "/List/0"},
+        ]);
+        let patch = JsonPatch::from_json(&raw).unwrap();
+        let result = patch.apply(&dummy_document()).unwrap();
+        assert_eq!(
+            result["Moved"],
+            dummy_document()["Pset_WallCommon"]["FireRating"]
+        );
+        assert!(result["Pset_WallCommon"].get("Copy").is_none());
+        assert_eq!(result["List"], json!([2, 3, 4]));
+        assert_eq!(patch.to_json().unwrap(), raw);
+    }
+
+    #[test]
+    fn test_case3() {
+        let document = dummy_document();
+        let failures = vec![
+            json!([{"op": "test", "path": "/Pset_WallCommon/id", "value": 2}]),
+            json!([{"op": "remove", "path": "/Missing"}]),
+            json!([{"op": "replace", "path": "/Missing", "value": 1}]),
+            json!([{"op": "move", "from": "/Pset_WallCommon", "path": "/Pset_WallCommon/x"}]),
+            json!([{"op": "add", "path": "missing-slash", "value": 1}]),
+        ];
+        for raw in failures.iter() {
+            let patch = JsonPatch::from_json(raw).unwrap();
+            assert!(patch.apply(&document).is_err(), "{}", raw);
+        }
+        let raw = json!([{"op": "unknown", "path": "/x"}]);
+        assert!(JsonPatch::from_json(&raw).is_err());
+    }
+}
+
+// ----------------------------------------------------------------
+// AUXILIARY
+// ----------------------------------------------------------------
+
+fn dummy_document() -> Value {
+    json!({
+        "Pset_WallCommon": {
+            "id": 1,
+            "IsExternal": {"id": 2, "class": "IfcPropertySingleValue", "value": true},
+            "FireRating": {"id": 3, "class": "IfcPropertySingleValue", "value": "F30"},
+        },
+    })
+}
@@ -1,3 +1,4 @@
 pub mod bim;
 pub mod json;
+pub mod step;
 pub mod tree;
@@ -0,0 +1,559 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use indexmap::IndexMap;
+use pyo3::PyResult;
+use pyo3::prelude::pyfunction;
+use serde_json::Map;
+use serde_json::Number;
+use serde_json::Value;
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::result::Result;
+
+use super::parser::StepEntity;
+use super::parser::StepReader;
+use super::parser::StepValue;
+use crate::_core::errors::err_to_py_string;
+use crate::models::bim::psets::PsetNestedValue;
+use crate::models::bim::psets::Psets;
+use crate::models::json::base::JsonConversion;
+use crate::models::json::model::ValueWrap;
+
+// ----------------------------------------------------------------
+// CONSTANTS
+// ----------------------------------------------------------------
+
+/// Classes of property definitions read from a file
+const DEFINITION_CLASSES: [&str; 16] = [
+    "IfcPropertySet",
+    "IfcElementQuantity",
+    "IfcPropertySingleValue",
+    "IfcPropertyEnumeratedValue",
+    "IfcPropertyEnumeration",
+    "IfcPropertyBoundedValue",
+    "IfcPropertyListValue",
+    "IfcPropertyTableValue",
+    "IfcPropertyReferenceValue",
+    "IfcComplexProperty",
+    "IfcQuantityLength",
+    "IfcQuantityArea",
+    "IfcQuantityVolume",
+    "IfcQuantityCount",
+    "IfcQuantityWeight",
+    "IfcQuantityTime",
+];
+
+/// Defined types of IfcValue, to restore the spelling of their upper case names
+const DEFINED_TYPES: [&str; 110] = [
+    "IfcAbsorbedDoseMeasure",
+    "IfcAccelerationMeasure",
+    "IfcAmountOfSubstanceMeasure",
+    "IfcAngularVelocityMeasure",
+    "IfcAreaDensityMeasure",
+    "IfcAreaMeasure",
+    "IfcBinary",
+    "IfcBoolean",
+    "IfcComplexNumber",
+    "IfcCompoundPlaneAngleMeasure",
+    "IfcContextDependentMeasure",
+    "IfcCountMeasure",
+    "IfcCurvatureMeasure",
+    "IfcDate",
+    "IfcDateTime",
+    "IfcDescriptiveMeasure",
+    "IfcDoseEquivalentMeasure",
+    "IfcDuration",
+    "IfcDynamicViscosityMeasure",
+    "IfcElectricCapacitanceMeasure",
+    "IfcElectricChargeMeasure",
+    "IfcElectricConductanceMeasure",
+    "IfcElectricCurrentMeasure",
+    "IfcElectricResistanceMeasure",
+    "IfcElectricVoltageMeasure",
+    "IfcEnergyMeasure",
+    "IfcForceMeasure",
+    "IfcFrequencyMeasure",
+    "IfcHeatFluxDensityMeasure",
+    "IfcHeatingValueMeasure",
+    "IfcIdentifier",
+    "IfcIlluminanceMeasure",
+    "IfcInductanceMeasure",
+    "IfcInteger",
+    "IfcIntegerCountRateMeasure",
+    "IfcIonConcentrationMeasure",
+    "IfcIsothermalMoistureCapacityMeasure",
+    "IfcKinematicViscosityMeasure",
+    "IfcLabel",
+    "IfcLengthMeasure",
+    "IfcLinearForceMeasure",
+    "IfcLinearMomentMeasure",
+    "IfcLinearStiffnessMeasure",
+    "IfcLinearVelocityMeasure",
+    "IfcLogical",
+    "IfcLuminousFluxMeasure",
+    "IfcLuminousIntensityDistributionMeasure",
+    "IfcLuminousIntensityMeasure",
+    "IfcMagneticFluxDensityMeasure",
+    "IfcMagneticFluxMeasure",
+    "IfcMassDensityMeasure",
+    "IfcMassFlowRateMeasure",
+    "IfcMassMeasure",
+    "IfcMassPerLengthMeasure",
+    "IfcModulusOfElasticityMeasure",
+    "IfcModulusOfLinearSubgradeReactionMeasure",
+    "IfcModulusOfRotationalSubgradeReactionMeasure",
+    "IfcModulusOfSubgradeReactionMeasure",
+    "IfcMoistureDiffusivityMeasure",
+    "IfcMolecularWeightMeasure",
+    "IfcMomentOfInertiaMeasure",
+    "IfcMonetaryMeasure",
+    "IfcNonNegativeLengthMeasure",
+    "IfcNormalisedRatioMeasure",
+    "IfcNumericMeasure",
+    "IfcParameterValue",
+    "IfcPHMeasure",
+    "IfcPlanarForceMeasure",
+    "IfcPlaneAngleMeasure",
+    "IfcPositiveInteger",
+    "IfcPositiveLengthMeasure",
+    "IfcPositivePlaneAngleMeasure",
+    "IfcPositiveRatioMeasure",
+    "IfcPowerMeasure",
+    "IfcPressureMeasure",
+    "IfcRadioActivityMeasure",
+    "IfcRatioMeasure",
+    "IfcReal",
+    "IfcRotationalFrequencyMeasure",
+    "IfcRotationalMassMeasure",
+    "IfcRotationalStiffnessMeasure",
+    "IfcSectionalAreaIntegralMeasure",
+    "IfcSectionModulusMeasure",
+    "IfcShearModulusMeasure",
+    "IfcSolidAngleMeasure",
+    "IfcSoundPowerLevelMeasure",
+    "IfcSoundPowerMeasure",
+    "IfcSoundPressureLevelMeasure",
+    "IfcSoundPressureMeasure",
+    "IfcSpecificHeatCapacityMeasure",
+    "IfcTemperatureGradientMeasure",
+    "IfcTemperatureRateOfChangeMeasure",
+    "IfcText",
+    "IfcThermalAdmittanceMeasure",
+    "IfcThermalConductivityMeasure",
+    "IfcThermalExpansionCoefficientMeasure",
+    "IfcThermalResistanceMeasure",
+    "IfcThermalTransmittanceMeasure",
+    "IfcThermodynamicTemperatureMeasure",
+    "IfcTime",
+    "IfcTimeMeasure",
+    "IfcTimeStamp",
+    "IfcTorqueMeasure",
+    "IfcURIReference",
+    "IfcVaporPermeabilityMeasure",
+    "IfcVolumeMeasure",
+    "IfcVolumetricFlowRateMeasure",
+    "IfcWarpingConstantMeasure",
+    "IfcWarpingMomentMeasure",
+    "IfcWorkTime",
+];
+
+// ----------------------------------------------------------------
+// STRUCTS
+// ----------------------------------------------------------------
+
+/// Element (or type object) of a file with its property and quantity sets
+#[derive(Clone, Debug, PartialEq)]
+pub struct StepElement {
+    pub id: i64,
+    pub global_id: String,
+    /// upper case class name, as written in the file
+    pub class_: String,
+    pub psets: Psets,
+}
+
+/// Collects the entities needed to assemble the sets of the elements, while streaming a file
+#[derive(Default)]
+pub struct StepPsetsCollector {
+    pub include_types: bool,
+    /// whether sets with invalid properties fail the file, rather than being kept as they are
+    pub strict: bool,
+    /// class and GlobalId of rooted instances
+    rooted: HashMap<i64, (String, String)>,
+    /// property definitions with their class and parameters
+    definitions: HashMap<i64, (&'static str, Vec<StepValue>)>,
+    /// sets of the elements, in the order of the relationships
+    occurrences: IndexMap<i64, Vec<i64>>,
+    /// type of the elements
+    types: HashMap<i64, i64>,
+    /// sets of the type objects (`HasPropertySets`)
+    type_psets: IndexMap<i64, Vec<i64>>,
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF StepElement
+// ----------------------------------------------------------------
+
+/// Rust implementation of struct
+impl StepElement {
+    /// GlobalId of the element, or its STEP id if it has none
+    pub fn key(&self) -> String {
+        match self.global_id.is_empty() {
+            true => self.id.to_string(),
+            false => self.global_id.clone(),
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF StepPsetsCollector
+// ----------------------------------------------------------------
+
+/// Rust implementation of struct
+impl StepPsetsCollector {
+    pub fn new(include_types: bool, strict: bool) -> Self {
+        return Self {
+            include_types,
+            strict,
+            ..Self::default()
+        };
+    }
+
+    pub fn add(&mut self, entity: &StepEntity) -> Result<(), String> {
+        if let Some(class_) = definition_class(&entity.class_) {
+            self.definitions
+                .insert(entity.id, (class_, entity.arguments()?));
+            return Ok(());
+        }
+        if !entity.is_rooted() {
+            return Ok(());
+        }
+        let arguments = entity.arguments()?;
+        let global_id = arguments[0].as_str().unwrap_or_default().to_string();
+        self.rooted
+            .insert(entity.id, (entity.class_.clone(), global_id));
+        match entity.class_.as_str() {
+            "IFCRELDEFINESBYPROPERTIES" => {
+                let psets = argument(&arguments, 5).references();
+                for id in argument(&arguments, 4).references() {
+                    self.occurrences
+                        .entry(id)
+                        .or_default()
+                        .extend(psets.iter().copied());
+                }
+            }
+            "IFCRELDEFINESBYTYPE" => {
+                if let Some(type_id) = argument(&arguments, 5).as_reference() {
+                    for id in argument(&arguments, 4).references() {
+                        self.types.insert(id, type_id);
+                    }
+                }
+            }
+            // NOTE: type objects (IfcTypeObject, IFC2x3 door and window styles) list their sets
+            x if (x.ends_with("TYPE") || x.ends_with("STYLE")) && arguments.len() > 5 => {
+                let psets = argument(&arguments, 5).references();
+                if !psets.is_empty() {
+                    self.type_psets.insert(entity.id, psets);
+                }
+            }
+            _ => {}
+        }
+        return Ok(());
+    }
+
+    /// Assembles the sets of all elements with sets, with those of their type (if included)
+    pub fn finish(&self) -> Result<Vec<StepElement>, String> {
+        let mut ids: IndexMap<i64, ()> = IndexMap::new();
+        ids.extend(self.occurrences.keys().map(|x| (*x, ())));
+        if self.include_types {
+            ids.extend(self.types.keys().map(|x| (*x, ())));
+            ids.extend(self.type_psets.keys().map(|x| (*x, ())));
+        }
+        let mut result: Vec<StepElement> = vec![];
+        for id in ids.keys() {
+            let mut pset_ids: Vec<i64> = vec![];
+            if self.include_types {
+                let type_id = self.types.get(id);
+                let inherited = type_id.and_then(|x| self.type_psets.get(x));
+                pset_ids.extend(inherited.into_iter().flatten());
+                pset_ids.extend(self.type_psets.get(id).into_iter().flatten());
+            }
+            pset_ids.extend(self.occurrences.get(id).into_iter().flatten());
+
+            // NOTE: sets of the occurrence override those of the type property by property
+            let mut value: Map<String, Value> = Map::new();
+            for pset_id in pset_ids {
+                let Some((name, pset)) = self.pset_to_json(pset_id)? else {
+                    continue;
+                };
+                match (value.get_mut(&name), pset) {
+                    (Some(Value::Object(existing)), Value::Object(pset)) => existing.extend(pset),
+                    (_, pset) => {
+                        value.insert(name, pset);
+                    }
+                }
+            }
+            if value.is_empty() {
+                continue;
+            }
+            let psets = match self.strict {
+                true => Psets::from_json(&Value::Object(value))
+                    .map_err(|err| format!("element #{}: {}", id, err))?,
+                // NOTE: sets are parsed one by one, and kept as they are if invalid,
+                // so that a malformed set does not fail the whole file
+                false => {
+                    let elements = value
+                        .into_iter()
+                        .map(|(name, x)| match PsetNestedValue::from_json(&x) {
+                            Ok(pset) => (name, pset),
+                            Err(_) => (name, PsetNestedValue::Any(ValueWrap(x))),
+                        })
+                        .collect();
+                    Psets::Nested { elements }
+                }
+            };
+            let (class_, global_id) = self.rooted.get(id).cloned().unwrap_or_default();
+            result.push(StepElement {
+                id: *id,
+                global_id,
+                class_,
+                psets,
+            });
+        }
+        return Ok(result);
+    }
+
+    /// Name and verbose form of a property or quantity set (`None` for other definitions)
+    fn pset_to_json(&self, id: i64) -> Result<Option<(String, Value)>, String> {
+        let Some((class_, arguments)) = self.definitions.get(&id) else {
+            return Ok(None);
+        };
+        let children = match *class_ {
+            "IfcPropertySet" => argument(arguments, 4),
+            "IfcElementQuantity" => argument(arguments, 5),
+            _ => {
+                return Ok(None);
+            }
+        };
+        let name = argument(arguments, 2)
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let mut result: Map<String, Value> = Map::new();
+        result.insert("id".to_string(), json!(id));
+        result.extend(self.properties_to_json(children)?);
+        return Ok(Some((name, Value::Object(result))));
+    }
+
+    fn properties_to_json(&self, children: &StepValue) -> Result<Map<String, Value>, String> {
+        let mut result: Map<String, Value> = Map::new();
+        for id in children.references() {
+            if let Some((name, value)) = self.property_to_json(id)? {
+                result.insert(name, value);
+            }
+        }
+        return Ok(result);
+    }
+
+    /// Name and verbose form of a property or quantity, as by `ifcopenshell.util.element.get_psets`
+    fn property_to_json(&self, id: i64) -> Result<Option<(String, Value)>, String> {
+        let Some((class_, arguments)) = self.definitions.get(&id) else {
+            return Ok(None);
+        };
+        let name = argument(arguments, 0)
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let mut result = json!({"id": id, "class": class_});
+        let object = result.as_object_mut().ok_or("invalid property")?;
+        match *class_ {
+            "IfcPropertySingleValue" => {
+                let (value, value_type) = typed_value(argument(arguments, 2));
+                object.insert("value".to_string(), value);
+                object.insert("value-type".to_string(), json!(value_type));
+            }
+            "IfcPropertyEnumeratedValue" | "IfcPropertyListValue" => {
+                let (values, value_type) = typed_values(argument(arguments, 2));
+                if *class_ == "IfcPropertyEnumeratedValue" {
+                    let enumeration = argument(arguments, 3).as_reference();
+                    let enumeration = enumeration.and_then(|x| self.enumeration_to_json(x));
+                    // NOTE: enumerations not covering the selected values are left out
+                    let allowed = enumeration.as_ref().and_then(|x| x["values"].as_array());
+                    if allowed.is_some_and(|allowed| values.iter().all(|x| allowed.contains(x))) {
+                        object.insert("enumeration".to_string(), json!(enumeration));
+                    }
+                }
+                object.insert("value".to_string(), Value::Array(values));
+                object.insert("value-type".to_string(), json!(value_type));
+            }
+            "IfcPropertyBoundedValue" => {
+                let bounds = [("upper-bound", 2), ("lower-bound", 3), ("set-point", 5)];
+                for (key, i) in bounds {
+                    let (value, value_type) = typed_value(argument(arguments, i));
+                    object.insert(key.to_string(), value);
+                    object.insert(format!("{}-type", key), json!(value_type));
+                }
+            }
+            "IfcPropertyTableValue" => {
+                let (defining, defining_type) = typed_values(argument(arguments, 2));
+                let (defined, defined_type) = typed_values(argument(arguments, 3));
+                let (expression, _) = typed_value(argument(arguments, 4));
+                let (interpolation, _) = typed_value(argument(arguments, 7));
+                object.insert("defining-values".to_string(), Value::Array(defining));
+                object.insert("defined-values".to_string(), Value::Array(defined));
+                object.insert("defining-value-type".to_string(), json!(defining_type));
+                object.insert("defined-value-type".to_string(), json!(defined_type));
+                object.insert("expression".to_string(), expression);
+                object.insert("curve-interpolation".to_string(), interpolation);
+            }
+            "IfcPropertyReferenceValue" => {
+                if let Some(x) = argument(arguments, 2).as_str() {
+                    object.insert("UsageName".to_string(), json!(x));
+                }
+                // NOTE: referenced objects (materials, persons, ...) are not kept, only their id
+                let (value, _) = typed_value(argument(arguments, 3));
+                object.insert("value".to_string(), value);
+            }
+            "IfcComplexProperty" => {
+                if let Some(x) = argument(arguments, 2).as_str() {
+                    object.insert("UsageName".to_string(), json!(x));
+                }
+                object.extend(self.properties_to_json(argument(arguments, 3))?);
+            }
+            // quantities
+            _ => {
+                let (value, _) = typed_value(argument(arguments, 3));
+                object.insert("value".to_string(), value);
+                if let Some(x) = argument(arguments, 4).as_str() {
+                    object.insert("Formula".to_string(), json!(x));
+                }
+            }
+        }
+        return Ok(Some((name, result)));
+    }
+
+    fn enumeration_to_json(&self, id: i64) -> Option<Value> {
+        let (class_, arguments) = self.definitions.get(&id)?;
+        if *class_ != "IfcPropertyEnumeration" {
+            return None;
+        }
+        let (values, _) = typed_values(argument(arguments, 1));
+        let result = json!({
+            "id": id,
+            "name": argument(arguments, 0).as_str(),
+            "values": values,
+        });
+        return Some(result);
+    }
+}
+
+// ----------------------------------------------------------------
+// PYTHON INTERFACE
+// ----------------------------------------------------------------
+
+/// Reads the property and quantity sets of all elements of an IFC (STEP) file,
+/// by the GlobalId of the elements (or their STEP id for elements without GlobalId),
+/// keeping sets with invalid properties as plain values unless `strict`
+#[pyfunction]
+#[pyo3(signature = (path, /, *, include_types=true, strict=true))]
+pub fn read_ifc_psets(
+    path: &str,
+    include_types: bool,
+    strict: bool,
+) -> PyResult<IndexMap<String, Psets>> {
+    let file = File::open(path).map_err(err_to_py_string)?;
+    let elements =
+        read_psets(BufReader::new(file), include_types, strict).map_err(err_to_py_string)?;
+    let result = elements.into_iter().map(|x| (x.key(), x.psets)).collect();
+    return Ok(result);
+}
+
+// ----------------------------------------------------------------
+// AUXILIARY METHODS
+// ----------------------------------------------------------------
+
+/// Streams a file, keeping only the entities needed for the sets
+pub fn read_psets<R: BufRead>(
+    reader: R,
+    include_types: bool,
+    strict: bool,
+) -> Result<Vec<StepElement>, String> {
+    let mut collector = StepPsetsCollector::new(include_types, strict);
+    for entity in StepReader::new(reader) {
+        collector.add(&entity?)?;
+    }
+    return collector.finish();
+}
+
+fn definition_class(class_: &str) -> Option<&'static str> {
+    let x = class_.as_bytes();
+    // NOTE: cheap test first, since most instances of a file are geometry
+    if !(x.starts_with(b"IFCPROPERTY")
+        || x.starts_with(b"IFCQUANTITY")
+        || class_ == "IFCELEMENTQUANTITY"
+        || class_ == "IFCCOMPLEXPROPERTY")
+    {
+        return None;
+    }
+    return DEFINITION_CLASSES
+        .iter()
+        .find(|x| x.eq_ignore_ascii_case(class_))
+        .copied();
+}
+
+/// Spelling of an upper case name, e.g. `IfcLengthMeasure` for `IFCLENGTHMEASURE`
+fn defined_name(name: &str) -> String {
+    if let Some(x) = DEFINED_TYPES.iter().find(|x| x.eq_ignore_ascii_case(name)) {
+        return x.to_string();
+    }
+    let mut result = String::new();
+    if let Some(rest) = name.strip_prefix("IFC") {
+        result.push_str("Ifc");
+        let mut chars = rest.chars();
+        result.extend(chars.next());
+        result.push_str(&chars.as_str().to_lowercase());
+        return result;
+    }
+    return name.to_string();
+}
+
+fn argument(arguments: &[StepValue], i: usize) -> &StepValue {
+    arguments.get(i).unwrap_or(&StepValue::Null)
+}
+
+/// Value with its defined type, if any
+fn typed_value(value: &StepValue) -> (Value, Option<String>) {
+    match value {
+        StepValue::Typed(name, x) => (plain_value(x), Some(defined_name(name))),
+        _ => (plain_value(value), None),
+    }
+}
+
+/// Values of an aggregate with the defined type of the first
+fn typed_values(value: &StepValue) -> (Vec<Value>, Option<String>) {
+    let values: Vec<(Value, Option<String>)> = value.as_list().iter().map(typed_value).collect();
+    let value_type = values.iter().find_map(|(_, x)| x.clone());
+    return (values.into_iter().map(|(x, _)| x).collect(), value_type);
+}
+
+fn plain_value(value: &StepValue) -> Value {
+    match value {
+        StepValue::Null | StepValue::Derived => Value::Null,
+        StepValue::Integer(x) => json!(x),
+        StepValue::Real(x) => Number::from_f64(*x).map_or(Value::Null, Value::Number),
+        StepValue::String(x) | StepValue::Binary(x) => json!(x),
+        StepValue::Enumeration(x) => match x.as_str() {
+            "T" => Value::Bool(true),
+            "F" => Value::Bool(false),
+            "U" => json!("UNKNOWN"),
+            _ => json!(x),
+        },
+        StepValue::Reference(x) => json!(x),
+        StepValue::Typed(_, x) => plain_value(x),
+        StepValue::List(x) => Value::Array(x.iter().map(plain_value).collect()),
+    }
+}
@@ -0,0 +1,10 @@
+pub mod extract;
+pub mod parser;
+pub mod writer;
+
+#[cfg(test)]
+pub mod tests_extract;
+#[cfg(test)]
+pub mod tests_parser;
+#[cfg(test)]
+pub mod tests_writer;
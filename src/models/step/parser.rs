@@ -0,0 +1,555 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use std::io::BufRead;
+use std::result::Result;
+
+use crate::_core::errors::err_to_string;
+
+// ----------------------------------------------------------------
+// STRUCTS
+// ----------------------------------------------------------------
+
+/// Parameter of an entity instance (ISO 10303-21)
+#[derive(Clone, Debug, PartialEq)]
+pub enum StepValue {
+    /// unset value (`$`)
+    Null,
+    /// value derived from other attributes (`*`)
+    Derived,
+    Integer(i64),
+    Real(f64),
+    String(String),
+    /// enumeration or boolean/logical literal, without the dots (`T` for `.T.`)
+    Enumeration(String),
+    Binary(String),
+    Reference(i64),
+    /// value of a defined type, e.g. `IFCLABEL('x')`
+    Typed(String, Box<StepValue>),
+    List(Vec<StepValue>),
+}
+
+/// Entity instance of the data section, with its parameters left unparsed
+#[derive(Clone, Debug, PartialEq)]
+pub struct StepEntity {
+    pub id: i64,
+    /// upper case class name, as written in the file
+    pub class_: String,
+    /// text between the outer parentheses of the instance
+    pub raw: String,
+}
+
+/// Streaming reader of the entity instances of an exchange file
+pub struct StepReader<R: BufRead> {
+    reader: R,
+    /// schemas of the header (`FILE_SCHEMA`), as soon as the header has been read
+    pub schemas: Vec<String>,
+    chunk: Vec<u8>,
+    statement: Vec<u8>,
+    finished: bool,
+}
+
+/// Cursor over the text of a statement
+struct StepCursor<'a> {
+    text: &'a [u8],
+    position: usize,
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF StepValue
+// ----------------------------------------------------------------
+
+/// Rust implementation of struct
+impl StepValue {
+    pub fn as_reference(&self) -> Option<i64> {
+        match self {
+            Self::Reference(x) => Some(*x),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// Members of an aggregate, where unset aggregates are empty
+    pub fn as_list(&self) -> &[StepValue] {
+        match self {
+            Self::List(x) => x,
+            _ => &[],
+        }
+    }
+
+    /// References of an aggregate (or of a single reference)
+    pub fn references(&self) -> Vec<i64> {
+        match self {
+            Self::Reference(x) => vec![*x],
+            _ => self
+                .as_list()
+                .iter()
+                .filter_map(Self::as_reference)
+                .collect(),
+        }
+    }
+}
+
+/// Writes the value as a parameter of an entity instance
+impl ToString for StepValue {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Null => "$".to_string(),
+            Self::Derived => "*".to_string(),
+            Self::Integer(x) => x.to_string(),
+            Self::Real(x) => format_real(*x),
+            Self::String(x) => format!("'{}'", encode_string(x)),
+            Self::Enumeration(x) => format!(".{}.", x),
+            Self::Binary(x) => format!("\"{}\"", x),
+            Self::Reference(x) => format!("#{}", x),
+            Self::Typed(name, x) => format!("{}({})", name, x.to_string()),
+            Self::List(x) => {
+                let values: Vec<String> = x.iter().map(|x| x.to_string()).collect();
+                return format!("({})", values.join(","));
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF StepEntity
+// ----------------------------------------------------------------
+
+/// Rust implementation of struct
+impl StepEntity {
+    /// Parses a statement of the data section (`#12=IFCWALL(...)`)
+    pub fn from_statement(text: &str) -> Result<Self, String> {
+        let (id, rest) = text
+            .trim()
+            .strip_prefix('#')
+            .and_then(|x| x.split_once('='))
+            .ok_or_else(|| format!("invalid entity instance '{}'", abbreviate(text)))?;
+        let id = id
+            .trim()
+            .parse::<i64>()
+            .map_err(|_| format!("invalid entity id '{}'", id.trim()))?;
+        let rest = rest.trim();
+        let (class_, raw) = match rest.find('(') {
+            // NOTE: complex instances `(A(...)B(...))` are kept whole, without a class
+            Some(0) => ("", rest),
+            Some(i) => (rest[..i].trim(), &rest[i..]),
+            None => {
+                return Err(format!("entity #{} has no parameters", id));
+            }
+        };
+        let raw = raw
+            .strip_prefix('(')
+            .and_then(|x| x.strip_suffix(')'))
+            .ok_or_else(|| format!("entity #{} has unbalanced parameters", id))?;
+        let result = Self {
+            id,
+            class_: class_.to_uppercase(),
+            raw: raw.to_string(),
+        };
+        return Ok(result);
+    }
+
+    pub fn arguments(&self) -> Result<Vec<StepValue>, String> {
+        let mut cursor = StepCursor::new(self.raw.as_bytes());
+        let result = cursor
+            .parse_values(None)
+            .map_err(|err| format!("entity #{}: {}", self.id, err))?;
+        return Ok(result);
+    }
+
+    /// Whether the first parameter is a GlobalId, i.e. the instance is rooted (IfcRoot)
+    pub fn is_rooted(&self) -> bool {
+        let bytes = self.raw.trim_start().as_bytes();
+        return bytes.len() > 23 && bytes[0] == b'\'' && bytes[23] == b'\'';
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF StepReader
+// ----------------------------------------------------------------
+
+/// Rust implementation of struct
+impl<R: BufRead> StepReader<R> {
+    pub fn new(reader: R) -> Self {
+        return Self {
+            reader,
+            schemas: vec![],
+            chunk: vec![],
+            statement: vec![],
+            finished: false,
+        };
+    }
+
+    /// Reads the next statement (up to its `;`), skipping comments
+    fn next_statement(&mut self) -> Result<Option<String>, String> {
+        self.statement.clear();
+        let mut in_string = false;
+        let mut in_comment = false;
+        loop {
+            self.chunk.clear();
+            self.reader
+                .read_until(b';', &mut self.chunk)
+                .map_err(err_to_string)?;
+            // NOTE: a chunk ends with `;`, so comment delimiters never straddle two chunks
+            let mut i = 0;
+            while i < self.chunk.len() {
+                let x = self.chunk[i];
+                let next = self.chunk.get(i + 1).copied();
+                if in_comment {
+                    if x == b'*' && next == Some(b'/') {
+                        in_comment = false;
+                        i += 1;
+                    }
+                } else if !in_string && x == b'/' && next == Some(b'*') {
+                    in_comment = true;
+                    i += 1;
+                } else {
+                    if x == b'\'' {
+                        // NOTE: escaped quotes (`''`) toggle twice
+                        in_string = !in_string;
+                    }
+                    self.statement.push(x);
+                }
+                i += 1;
+            }
+            // NOTE: a chunk without `;` is the end of the file
+            if self.chunk.last() != Some(&b';') {
+                if in_string || in_comment || !self.statement.trim_ascii().is_empty() {
+                    return Err("unexpected end of file".to_string());
+                }
+                return Ok(None);
+            }
+            if !in_string && !in_comment {
+                break;
+            }
+        }
+        self.statement.pop();
+        let result = String::from_utf8_lossy(&self.statement).trim().to_string();
+        return Ok(Some(result));
+    }
+
+    fn read_schemas(&mut self, statement: &str) -> Result<(), String> {
+        let Some(i) = statement.find('(') else {
+            return Ok(());
+        };
+        let mut cursor = StepCursor::new(&statement.as_bytes()[i + 1..]);
+        let values = cursor.parse_values(Some(b')'))?;
+        self.schemas = values
+            .iter()
+            .flat_map(StepValue::as_list)
+            .filter_map(|x| x.as_str().map(|x| x.to_string()))
+            .collect();
+        return Ok(());
+    }
+}
+
+impl<R: BufRead> Iterator for StepReader<R> {
+    type Item = Result<StepEntity, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.finished {
+            let statement = match self.next_statement() {
+                Ok(Some(x)) => x,
+                Ok(None) => {
+                    self.finished = true;
+                    return None;
+                }
+                Err(err) => {
+                    self.finished = true;
+                    return Some(Err(err));
+                }
+            };
+            if statement.starts_with('#') {
+                return Some(StepEntity::from_statement(&statement));
+            }
+            let keyword = statement
+                .split(|x: char| x == '(' || x.is_whitespace())
+                .next()
+                .unwrap_or_default()
+                .to_uppercase();
+            match keyword.as_str() {
+                "FILE_SCHEMA" => {
+                    if let Err(err) = self.read_schemas(&statement) {
+                        self.finished = true;
+                        return Some(Err(err));
+                    }
+                }
+                "END-ISO-10303-21" => {
+                    self.finished = true;
+                }
+                _ => {}
+            }
+        }
+        return None;
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF StepCursor
+// ----------------------------------------------------------------
+
+/// Rust implementation of struct
+impl<'a> StepCursor<'a> {
+    fn new(text: &'a [u8]) -> Self {
+        return Self { text, position: 0 };
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        while self
+            .text
+            .get(self.position)
+            .is_some_and(|x| x.is_ascii_whitespace())
+        {
+            self.position += 1;
+        }
+        return self.text.get(self.position).copied();
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<(), String> {
+        match self.peek() {
+            Some(x) if x == expected => {
+                self.position += 1;
+                return Ok(());
+            }
+            Some(x) => Err(format!(
+                "expected '{}', found '{}' at {}",
+                expected as char, x as char, self.position
+            )),
+            None => Err(format!("expected '{}', found end", expected as char)),
+        }
+    }
+
+    /// Comma separated values up to the closing parenthesis (or the end of the text)
+    fn parse_values(&mut self, end: Option<u8>) -> Result<Vec<StepValue>, String> {
+        let mut result: Vec<StepValue> = vec![];
+        if self.peek() == end {
+            self.position += 1;
+            return Ok(result);
+        }
+        loop {
+            result.push(self.parse_value()?);
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                x if x == end => {
+                    self.position += 1;
+                    return Ok(result);
+                }
+                Some(x) => {
+                    return Err(format!("unexpected '{}' at {}", x as char, self.position));
+                }
+                None => return Err("unbalanced parentheses".to_string()),
+            }
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<StepValue, String> {
+        let Some(x) = self.peek() else {
+            return Err("expected a value, found end".to_string());
+        };
+        match x {
+            b'$' => {
+                self.position += 1;
+                return Ok(StepValue::Null);
+            }
+            b'*' => {
+                self.position += 1;
+                return Ok(StepValue::Derived);
+            }
+            b'(' => {
+                self.position += 1;
+                return Ok(StepValue::List(self.parse_values(Some(b')'))?));
+            }
+            b'#' => {
+                self.position += 1;
+                let text = self.take_while(|x| x.is_ascii_digit());
+                let id = text.parse::<i64>().map_err(err_to_string)?;
+                return Ok(StepValue::Reference(id));
+            }
+            b'\'' => {
+                return Ok(StepValue::String(self.parse_string()?));
+            }
+            b'"' => {
+                self.position += 1;
+                let text = self.take_while(|x| x != b'"');
+                self.expect(b'"')?;
+                return Ok(StepValue::Binary(text));
+            }
+            b'.' => {
+                self.position += 1;
+                let text = self.take_while(|x| x != b'.');
+                self.expect(b'.')?;
+                return Ok(StepValue::Enumeration(text.to_uppercase()));
+            }
+            b'+' | b'-' | b'0'..=b'9' => {
+                let text = self.take_while(|x| {
+                    x.is_ascii_digit() || matches!(x, b'+' | b'-' | b'.' | b'E' | b'e')
+                });
+                if let Ok(x) = text.parse::<i64>() {
+                    return Ok(StepValue::Integer(x));
+                }
+                // NOTE: reals may omit the digits after the point (`1.E-3`)
+                let value = text
+                    .replace(".E", ".0E")
+                    .replace(".e", ".0e")
+                    .parse::<f64>()
+                    .map_err(|_| format!("invalid number '{}'", text))?;
+                return Ok(StepValue::Real(value));
+            }
+            x if x.is_ascii_alphabetic() || x == b'_' => {
+                let keyword = self.take_while(|x| x.is_ascii_alphanumeric() || x == b'_');
+                self.expect(b'(')?;
+                let mut values = self.parse_values(Some(b')'))?;
+                let value = match values.len() {
+                    1 => values.remove(0),
+                    _ => StepValue::List(values),
+                };
+                return Ok(StepValue::Typed(keyword.to_uppercase(), Box::new(value)));
+            }
+            x => Err(format!("unexpected '{}' at {}", x as char, self.position)),
+        }
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(u8) -> bool) -> String {
+        let start = self.position;
+        while self.text.get(self.position).is_some_and(|x| predicate(*x)) {
+            self.position += 1;
+        }
+        return String::from_utf8_lossy(&self.text[start..self.position]).to_string();
+    }
+
+    /// Reads a string literal, decoding quotes and control directives
+    fn parse_string(&mut self) -> Result<String, String> {
+        self.expect(b'\'')?;
+        let mut raw: Vec<u8> = vec![];
+        loop {
+            match self.text.get(self.position) {
+                None => return Err("unterminated string".to_string()),
+                Some(b'\'') if self.text.get(self.position + 1) == Some(&b'\'') => {
+                    raw.push(b'\'');
+                    self.position += 2;
+                }
+                Some(b'\'') => {
+                    self.position += 1;
+                    break;
+                }
+                Some(x) => {
+                    raw.push(*x);
+                    self.position += 1;
+                }
+            }
+        }
+        return decode_string(&String::from_utf8_lossy(&raw));
+    }
+}
+
+// ----------------------------------------------------------------
+// AUXILIARY METHODS
+// ----------------------------------------------------------------
+
+/// Decodes the control directives of a string (`\X\`, `\X2\`, `\X4\`, `\S\`, `\\`)
+pub fn decode_string(text: &str) -> Result<String, String> {
+    if !text.contains('\\') {
+        return Ok(text.to_string());
+    }
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = String::new();
+    let mut i = 0;
+    let invalid = || format!("invalid string directive in '{}'", text);
+    let hex = |from: usize, to: usize| -> Result<u32, String> {
+        let digits: String = chars.get(from..to).ok_or_else(invalid)?.iter().collect();
+        return u32::from_str_radix(&digits, 16).map_err(|_| invalid());
+    };
+    while i < chars.len() {
+        let rest: String = chars[i..chars.len().min(i + 4)].iter().collect();
+        if rest.starts_with("\\\\") {
+            result.push('\\');
+            i += 2;
+        } else if rest.starts_with("\\X\\") {
+            result.push(char::from_u32(hex(i + 3, i + 5)?).ok_or_else(invalid)?);
+            i += 5;
+        } else if rest == "\\X2\\" || rest == "\\X4\\" {
+            let width = if rest == "\\X2\\" { 4 } else { 8 };
+            i += 4;
+            let mut units: Vec<u32> = vec![];
+            while chars.get(i) != Some(&'\\') {
+                units.push(hex(i, i + width)?);
+                i += width;
+            }
+            let end: String = chars.get(i..i + 4).ok_or_else(invalid)?.iter().collect();
+            if end != "\\X0\\" {
+                return Err(invalid());
+            }
+            i += 4;
+            match width {
+                4 => {
+                    let units: Vec<u16> = units.iter().map(|x| *x as u16).collect();
+                    result.push_str(&String::from_utf16(&units).map_err(|_| invalid())?);
+                }
+                _ => {
+                    for x in units {
+                        result.push(char::from_u32(x).ok_or_else(invalid)?);
+                    }
+                }
+            }
+        } else if rest.starts_with("\\S\\") {
+            // NOTE: shifts a character of the (default) ISO 8859-1 page to the upper half
+            let x = *chars.get(i + 3).ok_or_else(invalid)? as u32;
+            result.push(char::from_u32(x + 128).ok_or_else(invalid)?);
+            i += 4;
+        } else if rest.starts_with("\\P") && chars.get(i + 3) == Some(&'\\') {
+            // NOTE: code page switches are ignored
+            i += 4;
+        } else {
+            result.push(chars[i]);
+            i += 1;
+        }
+    }
+    return Ok(result);
+}
+
+/// Encodes a string for a literal, escaping quotes and backslashes and non-ASCII characters
+pub fn encode_string(text: &str) -> String {
+    let mut result = String::new();
+    for x in text.chars() {
+        match x {
+            '\'' => result.push_str("''"),
+            '\\' => result.push_str("\\\\"),
+            ' '..='~' => result.push(x),
+            _ => {
+                let mut units = [0u16; 2];
+                let hex: Vec<String> = x
+                    .encode_utf16(&mut units)
+                    .iter()
+                    .map(|x| format!("{:04X}", x))
+                    .collect();
+                result.push_str(&format!("\\X2\\{}\\X0\\", hex.join("")));
+            }
+        }
+    }
+    return result;
+}
+
+/// Formats a real with the decimal point required by the standard (`2.`, `1.5E-7`)
+pub fn format_real(value: f64) -> String {
+    let text = format!("{:?}", value);
+    let (mantissa, exponent) = match text.split_once('e') {
+        Some((mantissa, exponent)) => (mantissa.to_string(), format!("E{}", exponent)),
+        None => (text, String::new()),
+    };
+    let mantissa = match mantissa.contains('.') {
+        true => mantissa.trim_end_matches('0').to_string(),
+        false => format!("{}.", mantissa),
+    };
+    return format!("{}{}", mantissa, exponent);
+}
+
+fn abbreviate(text: &str) -> String {
+    return text.chars().take(40).collect();
+}
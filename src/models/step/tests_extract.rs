@@ -0,0 +1,140 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use serde_json::json;
+
+use super::extract::read_psets;
+use crate::models::bim::psets::PsetNestedValue;
+use crate::models::json::base::JsonConversion;
+
+// ----------------------------------------------------------------
+// TESTS
+// ----------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_case1() {
+        let elements = read_psets(DUMMY_IFC.as_bytes(), false, true).unwrap();
+        assert_eq!(elements.len(), 1);
+        assert_eq!(elements[0].id, 20);
+        assert_eq!(elements[0].global_id, "2O2Fr$t4X7Zf8NOew3FLOH");
+        assert_eq!(elements[0].key(), "2O2Fr$t4X7Zf8NOew3FLOH");
+        assert_eq!(elements[0].class_, "IFCWALL");
+        let value = elements[0].psets.to_json().unwrap();
+        assert_eq!(
+            value["Pset_WallCommon"],
+            json!({
+                "id": 10,
+                "IsExternal": {"id": 11, "class": "IfcPropertySingleValue", "value": true, "value-type": "IfcBoolean"},
+                "Status": {
+                    "id": 12,
+                    "class": "IfcPropertyEnumeratedValue",
+                    "value": ["NEW"],
+                    "value-type": "IfcLabel",
+                    "enumeration": {"id": 13, "name": "PEnum_ElementStatus", "values": ["NEW", "EXISTING"]},
+                },
+                "Reference": {"id": 14, "class": "IfcPropertySingleValue", "value": "Wände", "value-type": "IfcIdentifier"},
+            })
+        );
+        assert_eq!(
+            value["Qto_WallBaseQuantities"]["Width"],
+            json!({"id": 16, "class": "IfcQuantityLength", "value": 0.2})
+        );
+    }
+
+    #[test]
+    fn test_extract_case2() {
+        let elements = read_psets(DUMMY_IFC.as_bytes(), true, true).unwrap();
+        let ids: Vec<i64> = elements.iter().map(|x| x.id).collect();
+        assert_eq!(ids, vec![20, 30]);
+        let value = elements[0].psets.to_json().unwrap();
+        // properties of the occurrence override those of its type
+        assert_eq!(value["Pset_WallCommon"]["id"], json!(10));
+        assert_eq!(value["Pset_WallCommon"]["IsExternal"]["value"], json!(true));
+        assert_eq!(
+            value["Pset_WallCommon"]["LoadBearing"]["value"],
+            json!(false)
+        );
+        assert_eq!(
+            value["ACME_Layers"]["Layer"],
+            json!({
+                "id": 33,
+                "class": "IfcComplexProperty",
+                "UsageName": "Layer",
+                "Thickness": {"id": 34, "class": "IfcPropertySingleValue", "value": 0.1, "value-type": "IfcPositiveLengthMeasure"},
+            })
+        );
+        let value = elements[1].psets.to_json().unwrap();
+        assert!(value.get("Qto_WallBaseQuantities").is_none());
+        assert_eq!(elements[1].class_, "IFCWALLTYPE");
+    }
+
+    #[test]
+    fn test_extract_case3() {
+        let broken = DUMMY_IFC.replace("#11=IFCPROPERTYSINGLEVALUE('IsExternal'", "#11=IFCX(");
+        assert!(read_psets(broken.as_bytes(), false, true).is_ok());
+        let broken = DUMMY_IFC.replace("IFCBOOLEAN(.T.)", "IFCBOOLEAN(.T.");
+        let result = read_psets(broken.as_bytes(), false, true);
+        assert!(result.is_err_and(|x| x.contains("#11")));
+        // invalid values fail the file, unless read leniently
+        let invalid = DUMMY_IFC.replace(
+            "IFCPOSITIVELENGTHMEASURE(0.1)",
+            "IFCPOSITIVELENGTHMEASURE(0.)",
+        );
+        let result = read_psets(invalid.as_bytes(), true, true);
+        assert!(result.is_err_and(|x| x.contains("must be positive")));
+        let elements = read_psets(invalid.as_bytes(), true, false).unwrap();
+        let value = elements[0].psets.to_json().unwrap();
+        assert_eq!(
+            value["ACME_Layers"]["Layer"]["Thickness"]["value"],
+            json!(0.0)
+        );
+        assert!(matches!(
+            elements[0].psets.rust_get("ACME_Layers", None),
+            Some(PsetNestedValue::Any(_))
+        ));
+        // elements without GlobalId are keyed by their STEP id
+        let anonymous = DUMMY_IFC.replace("IFCWALL('2O2Fr$t4X7Zf8NOew3FLOH'", "IFCWALL($");
+        let elements = read_psets(anonymous.as_bytes(), false, true).unwrap();
+        assert_eq!(elements[0].key(), "20");
+    }
+}
+
+// ----------------------------------------------------------------
+// AUXILIARY
+// ----------------------------------------------------------------
+
+const DUMMY_IFC: &str = r#"ISO-10303-21;
+HEADER;
+FILE_DESCRIPTION(('ViewDefinition [ReferenceView]'),'2;1');
+FILE_NAME('wall.ifc','2024-01-01T00:00:00',(''),(''),'','','');
+FILE_SCHEMA(('IFC4'));
+ENDSEC;
+DATA;
+#1=IFCOWNERHISTORY($,$,$,.NOCHANGE.,$,$,$,0);
+#10=IFCPROPERTYSET('1YvrIcHHT1Ogl5IqvMa3Yx',#1,'Pset_WallCommon',$,(#11,#12,#14));
+#11=IFCPROPERTYSINGLEVALUE('IsExternal',$,IFCBOOLEAN(.T.),$);
+#12=IFCPROPERTYENUMERATEDVALUE('Status',$,(IFCLABEL('NEW')),#13);
+#13=IFCPROPERTYENUMERATION('PEnum_ElementStatus',(IFCLABEL('NEW'),IFCLABEL('EXISTING')),$);
+#14=IFCPROPERTYSINGLEVALUE('Reference',$,IFCIDENTIFIER('W\X2\00E4\X0\nde'),$);
+#15=IFCELEMENTQUANTITY('0sYFq3JmX5ux2L7fJ5AfNq',#1,'Qto_WallBaseQuantities',$,$,(#16));
+#16=IFCQUANTITYLENGTH('Width',$,$,0.2,$);
+/* the wall and its type */
+#20=IFCWALL('2O2Fr$t4X7Zf8NOew3FLOH',#1,'Wall',$,$,$,$,$,.STANDARD.);
+#21=IFCRELDEFINESBYPROPERTIES('3fXHDwnNX8dRjx1YCjfXY5',#1,$,$,(#20),#10);
+#22=IFCRELDEFINESBYPROPERTIES('1cG2yS3hz3SxmGQ8ZJDgKQ',#1,$,$,(#20),#15);
+#30=IFCWALLTYPE('0rX$OcLQb3OOA9NA2EphMK',#1,'Type',$,$,(#31,#32),$,$,$,.STANDARD.);
+#31=IFCPROPERTYSET('2s1pLrkzn7QfM3Z_tkq1$v',#1,'Pset_WallCommon',$,(#35,#36));
+#32=IFCPROPERTYSET('0Fw_qLfLP4bPgv0v1ZGGTx',#1,'ACME_Layers',$,(#33));
+#33=IFCCOMPLEXPROPERTY('Layer',$,'Layer',(#34));
+#34=IFCPROPERTYSINGLEVALUE('Thickness',$,IFCPOSITIVELENGTHMEASURE(0.1),$);
+#35=IFCPROPERTYSINGLEVALUE('IsExternal',$,IFCBOOLEAN(.F.),$);
+#36=IFCPROPERTYSINGLEVALUE('LoadBearing',$,IFCBOOLEAN(.F.),$);
+#40=IFCRELDEFINESBYTYPE('3VZ$uQx0f0ZeBxi0lp0iV2',#1,$,$,(#20),#30);
+ENDSEC;
+END-ISO-10303-21;
+"#;
@@ -0,0 +1,91 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use super::parser::StepEntity;
+use super::parser::StepReader;
+use super::parser::StepValue;
+use super::parser::decode_string;
+
+// ----------------------------------------------------------------
+// TESTS
+// ----------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parser_case1() {
+        let entity = StepEntity::from_statement(
+            "#5= IFCPROPERTYSINGLEVALUE('Width',$,IFCLENGTHMEASURE(0.2),#3)",
+        )
+        .unwrap();
+        assert_eq!(entity.id, 5);
+        assert_eq!(entity.class_, "IFCPROPERTYSINGLEVALUE");
+        assert_eq!(
+            entity.arguments().unwrap(),
+            vec![
+                StepValue::String("Width".to_string()),
+                StepValue::Null,
+                StepValue::Typed(
+                    "IFCLENGTHMEASURE".to_string(),
+                    Box::new(StepValue::Real(0.2))
+                ),
+                StepValue::Reference(3),
+            ]
+        );
+        let entity = StepEntity::from_statement("#6=IFCX((#1,#2),.T.,*,-3,1.E-3,\"0FF\")").unwrap();
+        assert_eq!(
+            entity.arguments().unwrap(),
+            vec![
+                StepValue::List(vec![StepValue::Reference(1), StepValue::Reference(2)]),
+                StepValue::Enumeration("T".to_string()),
+                StepValue::Derived,
+                StepValue::Integer(-3),
+                StepValue::Real(0.001),
+                StepValue::Binary("0FF".to_string()),
+            ]
+        );
+        assert!(StepEntity::from_statement("IFCX()").is_err());
+        assert!(StepEntity::from_statement("#7=IFCX").is_err());
+        for text in ["#7=IFCX('open)", "#7=IFCX((1,2)", "#7=IFCX(1 2)"] {
+            let entity = StepEntity::from_statement(text).unwrap();
+            assert!(entity.arguments().is_err());
+        }
+    }
+
+    #[test]
+    fn test_parser_case2() {
+        assert_eq!(decode_string("it''s").unwrap(), "it''s");
+        assert_eq!(decode_string("W\\X\\E4nde").unwrap(), "Wände");
+        assert_eq!(decode_string("\\X2\\00C400DF\\X0\\").unwrap(), "Äß");
+        assert_eq!(decode_string("\\X4\\0001F600\\X0\\").unwrap(), "😀");
+        assert_eq!(decode_string("\\S\\D").unwrap(), "Ä");
+        assert_eq!(decode_string("a\\\\b").unwrap(), "a\\b");
+        assert!(decode_string("\\X2\\00C4").is_err());
+        let entity = StepEntity::from_statement("#1=IFCLABEL('it''s; \\X\\E4')").unwrap();
+        assert_eq!(
+            entity.arguments().unwrap(),
+            vec![StepValue::String("it's; ä".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_parser_case3() {
+        let text = "ISO-10303-21;\nHEADER;\nFILE_DESCRIPTION(('ViewDefinition [x]'),'2;1');\n\
+            FILE_SCHEMA(('IFC4'));\nENDSEC;\nDATA;\n/* comment; with 'quote */\n\
+            #1=IFCLABEL('a;b');\n#2=IFCX(#1);\nENDSEC;\nEND-ISO-10303-21;\n";
+        let mut reader = StepReader::new(text.as_bytes());
+        let entities: Vec<StepEntity> = reader.by_ref().map(|x| x.unwrap()).collect();
+        assert_eq!(reader.schemas, vec!["IFC4"]);
+        assert_eq!(entities.len(), 2);
+        assert_eq!(entities[0].raw, "'a;b'");
+        assert_eq!(entities[1].class_, "IFCX");
+        let truncated = "DATA;\n#1=IFCLABEL('a');\n#2=IFCX(";
+        let results: Vec<Result<StepEntity, String>> =
+            StepReader::new(truncated.as_bytes()).collect();
+        assert_eq!(results.len(), 2);
+        assert!(results[1].is_err());
+    }
+}
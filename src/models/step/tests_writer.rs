@@ -0,0 +1,196 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use indexmap::IndexMap;
+use serde_json::Value;
+use serde_json::json;
+use std::fs;
+
+use super::extract::read_psets;
+use super::writer::StepIdAllocator;
+use super::writer::StepPsetsWriter;
+use super::writer::new_global_id;
+use super::writer::patch_file;
+use crate::models::bim::psets::Pset;
+use crate::models::bim::psets::PsetNestedValue;
+use crate::models::bim::psets::Psets;
+use crate::models::json::base::JsonConversion;
+use crate::models::json::model::ValueWrap;
+
+// ----------------------------------------------------------------
+// TESTS
+// ----------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_writer_case1() {
+        let mut allocator = StepIdAllocator::from_reader(DUMMY_IFC.as_bytes()).unwrap();
+        assert_eq!(allocator.next_id, 21);
+        let psets = Psets::from_json(&dummy_psets()).unwrap();
+        let mut writer = StepPsetsWriter::new(&mut allocator, "IFC4", Some(1)).unwrap();
+        writer.write(&psets, &[20]).unwrap();
+        let lines = writer.lines.clone();
+        assert_eq!(lines.len(), 10);
+        assert_eq!(
+            lines[0],
+            "#21=IFCPROPERTYSINGLEVALUE('IsExternal',$,IFCBOOLEAN(.T.),$);"
+        );
+        assert_eq!(
+            lines[1],
+            "#22=IFCPROPERTYSINGLEVALUE('Width',$,IFCLENGTHMEASURE(2.5),$);"
+        );
+        assert_eq!(
+            lines[2],
+            "#23=IFCPROPERTYENUMERATION('PEnum_ElementStatus',(IFCLABEL('NEW'),IFCLABEL('EXISTING')),$);"
+        );
+        assert_eq!(
+            lines[3],
+            "#24=IFCPROPERTYENUMERATEDVALUE('Status',$,(IFCLABEL('NEW')),#23);"
+        );
+        assert_eq!(
+            lines[4],
+            "#25=IFCPROPERTYSINGLEVALUE('Note',$,IFCLABEL('it''s W\\X2\\00E4\\X0\\nde'),$);"
+        );
+        assert!(lines[5].starts_with("#26=IFCPROPERTYSET('"));
+        assert!(lines[5].ends_with("',#1,'Pset_WallCommon',$,(#21,#22,#24,#25));"));
+        assert!(lines[6].ends_with("',#1,$,$,(#20),#26);"));
+        assert_eq!(lines[7], "#28=IFCQUANTITYCOUNT('Layers',$,$,3,$);");
+        assert!(lines[8].ends_with("',#1,'Qto_WallBaseQuantities',$,$,(#28));"));
+        assert_eq!(allocator.next_id, 31);
+    }
+
+    #[test]
+    fn test_writer_case2() {
+        let mut allocator = StepIdAllocator::from_reader(DUMMY_IFC.as_bytes()).unwrap();
+        let psets = Psets::from_json(&dummy_psets()).unwrap();
+        let mut writer = StepPsetsWriter::new(&mut allocator, "IFC4", Some(1)).unwrap();
+        writer.write(&psets, &[20]).unwrap();
+        let text = DUMMY_IFC.replace(
+            "ENDSEC;\nEND",
+            &format!("{}\nENDSEC;\nEND", writer.lines.join("\n")),
+        );
+        let elements = read_psets(text.as_bytes(), false, true).unwrap();
+        assert_eq!(elements.len(), 1);
+        let value = elements[0].psets.to_json().unwrap();
+        assert_eq!(value["Pset_WallCommon"]["IsExternal"]["value"], json!(true));
+        assert_eq!(value["Pset_WallCommon"]["Width"]["value"], json!(2.5));
+        assert_eq!(value["Pset_WallCommon"]["Status"]["value"], json!(["NEW"]));
+        assert_eq!(
+            value["Pset_WallCommon"]["Note"]["value"],
+            json!("it's Wände")
+        );
+        assert_eq!(value["Qto_WallBaseQuantities"]["Layers"]["value"], json!(3));
+
+        let source = std::env::temp_dir().join("tests_writer_source.ifc");
+        let target = std::env::temp_dir().join("tests_writer_target.ifc");
+        fs::write(&source, DUMMY_IFC).unwrap();
+        let (source, target) = (source.to_str().unwrap(), target.to_str().unwrap());
+        patch_file(source, target, &writer.lines).unwrap();
+        assert_eq!(fs::read_to_string(target).unwrap(), text);
+        assert!(patch_file(source, source, &writer.lines).is_err());
+        // the source is recognised through another path, and left untouched
+        let aliased = std::env::temp_dir()
+            .join(".")
+            .join("tests_writer_source.ifc");
+        assert!(patch_file(source, aliased.to_str().unwrap(), &writer.lines).is_err());
+        assert_eq!(fs::read_to_string(source).unwrap(), DUMMY_IFC);
+        let _ = fs::remove_file(source);
+        let _ = fs::remove_file(target);
+    }
+
+    #[test]
+    fn test_writer_case3() {
+        let global_id = new_global_id();
+        assert_eq!(global_id.len(), 22);
+        assert!("0123".contains(&global_id[..1]));
+        assert_ne!(global_id, new_global_id());
+        let mut allocator = StepIdAllocator::new(1);
+        assert!(StepPsetsWriter::new(&mut allocator, "IFC2X3", None).is_err());
+        assert!(StepPsetsWriter::new(&mut allocator, "IFC5", None).is_err());
+        let mut writer = StepPsetsWriter::new(&mut allocator, "IFC4", None).unwrap();
+        let mixed = json!({"Pset_X": {
+            "A": {"id": 1, "class": "IfcPropertySingleValue", "value": 1, "value-type": "IfcInteger"},
+            "B": {"id": 2, "class": "IfcQuantityLength", "value": 1.0},
+        }});
+        let result = writer.write(&Psets::from_json(&mixed).unwrap(), &[5]);
+        assert!(result.is_err_and(|x| x.contains("mixes")));
+        let empty = Psets::from_json(&json!({"Pset_X": {"id": 3}})).unwrap();
+        assert!(writer.write(&empty, &[5]).is_err());
+        let plain = Psets::from_json(&json!({"Pset_X": {"A": 1.0, "B": null}})).unwrap();
+        writer.lines.clear();
+        writer.write(&plain, &[5]).unwrap();
+        assert!(writer.lines[0].ends_with("=IFCPROPERTYSINGLEVALUE('A',$,IFCREAL(1.),$);"));
+        assert!(writer.lines[1].ends_with("=IFCPROPERTYSINGLEVALUE('B',$,$,$);"));
+        // values not matching their type are refused rather than written as invalid literals
+        for (value, value_type) in [
+            (json!(2.5), "IfcInteger"),
+            (json!(2.5), "IfcCountMeasure"),
+            (json!("abc"), "IfcLengthMeasure"),
+            (json!(true), "IfcLabel"),
+            (json!(true), "IfcDate"),
+        ] {
+            let psets = single_property(value, value_type);
+            assert!(writer.write(&psets, &[5]).is_err());
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+// AUXILIARY
+// ----------------------------------------------------------------
+
+const DUMMY_IFC: &str = r#"ISO-10303-21;
+HEADER;
+FILE_DESCRIPTION(('ViewDefinition [ReferenceView]'),'2;1');
+FILE_NAME('wall.ifc','2024-01-01T00:00:00',(''),(''),'','','');
+FILE_SCHEMA(('IFC4'));
+ENDSEC;
+DATA;
+#1=IFCOWNERHISTORY($,$,$,.NOCHANGE.,$,$,$,0);
+#20=IFCWALL('2O2Fr$t4X7Zf8NOew3FLOH',#1,'Wall',$,$,$,$,$,.STANDARD.);
+ENDSEC;
+END-ISO-10303-21;
+"#;
+
+fn dummy_psets() -> Value {
+    return json!({
+        "Pset_WallCommon": {
+            "id": 10,
+            "IsExternal": {"id": 11, "class": "IfcPropertySingleValue", "value": true, "value-type": "IfcBoolean"},
+            "Width": {"id": 12, "class": "IfcPropertySingleValue", "value": 2.5, "value-type": "IfcLengthMeasure"},
+            "Status": {
+                "id": 13,
+                "class": "IfcPropertyEnumeratedValue",
+                "value": ["NEW"],
+                "value-type": "IfcLabel",
+                "enumeration": {"id": 14, "name": "PEnum_ElementStatus", "values": ["NEW", "EXISTING"]},
+            },
+            "Note": {"id": 15, "class": "IfcPropertySingleValue", "value": "it's Wände", "value-type": "IfcLabel"},
+        },
+        "Qto_WallBaseQuantities": {
+            "id": 16,
+            "Layers": {"id": 17, "class": "IfcQuantityCount", "value": 3},
+        },
+    });
+}
+
+/// Psets holding a single property, built directly so that its value is not checked
+fn single_property(value: Value, value_type: &str) -> Psets {
+    let pset = Pset {
+        id_: 1,
+        class_: "IfcPropertySingleValue".to_string(),
+        value: ValueWrap(value),
+        value_type: Some(value_type.to_string()),
+    };
+    let properties = IndexMap::from([("A".to_string(), PsetNestedValue::Psets(Psets::Pset(pset)))]);
+    let pset = Psets::Nested {
+        elements: properties,
+    };
+    return Psets::Nested {
+        elements: IndexMap::from([("Pset_X".to_string(), PsetNestedValue::Psets(pset))]),
+    };
+}
@@ -0,0 +1,552 @@
+// ----------------------------------------------------------------
+// IMPORTS
+// ----------------------------------------------------------------
+
+use pyo3::PyResult;
+use pyo3::prelude::pyclass;
+use pyo3::prelude::pyfunction;
+use pyo3::prelude::pymethods;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::collections::hash_map::RandomState;
+use std::fs;
+use std::fs::File;
+use std::hash::BuildHasher;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::result::Result;
+use std::time::SystemTime;
+
+use super::parser::StepReader;
+use super::parser::StepValue;
+use crate::_core::errors::err_to_py_string;
+use crate::_core::errors::err_to_string;
+use crate::models::bim::properties::PsetProperty;
+use crate::models::bim::psets::PsetNestedValue;
+use crate::models::bim::psets::Psets;
+use crate::models::bim::psets::Quantity;
+use crate::models::bim::psets::QuantityDimension;
+use crate::models::bim::values::IfcValue;
+use crate::models::json::model::ValueWrap;
+
+// ----------------------------------------------------------------
+// CONSTANTS
+// ----------------------------------------------------------------
+
+/// Digits of the compressed GlobalId encoding
+const GLOBAL_ID_DIGITS: &[u8; 64] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz_$";
+
+/// Defined types whose values are written as integers
+const INTEGER_TYPES: [&str; 5] = [
+    "IfcInteger",
+    "IfcPositiveInteger",
+    "IfcCountMeasure",
+    "IfcIntegerCountRateMeasure",
+    "IfcTimeStamp",
+];
+
+// ----------------------------------------------------------------
+// STRUCTS
+// ----------------------------------------------------------------
+
+/// Allocator of entity ids, continuing after the ids of a file
+#[pyclass]
+#[derive(Clone, Debug, PartialEq)]
+pub struct StepIdAllocator {
+    pub next_id: i64,
+}
+
+/// Writes Psets as entity instances (`#12=IFCPROPERTYSET(...);`)
+pub struct StepPsetsWriter<'a> {
+    allocator: &'a mut StepIdAllocator,
+    /// IFC2X3 lacks some parameters of IFC4 (formulas, set points, interpolations)
+    ifc2x3: bool,
+    owner_history: Option<i64>,
+    /// enumerations already written, by their name and values
+    enumerations: HashMap<String, i64>,
+    pub lines: Vec<String>,
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF StepIdAllocator
+// ----------------------------------------------------------------
+
+#[pymethods]
+impl StepIdAllocator {
+    #[new]
+    #[pyo3(signature = (next_id=1))]
+    pub fn new(next_id: i64) -> Self {
+        return Self { next_id };
+    }
+
+    /// Allocator continuing after the largest id of an IFC (STEP) file
+    #[staticmethod]
+    #[pyo3(name = "from_ifc", signature = (path, /))]
+    fn py_from_ifc(path: &str) -> PyResult<Self> {
+        let file = File::open(path).map_err(err_to_py_string)?;
+        return Self::from_reader(BufReader::new(file)).map_err(err_to_py_string);
+    }
+
+    #[getter]
+    fn next_id(&self) -> i64 {
+        self.next_id
+    }
+
+    /// Reserves the next id
+    pub fn allocate(&mut self) -> i64 {
+        let result = self.next_id;
+        self.next_id += 1;
+        return result;
+    }
+
+    pub fn __str__(&self) -> PyResult<String> {
+        Ok(self.to_string())
+    }
+}
+
+/// Rust implementation of struct
+impl StepIdAllocator {
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Self, String> {
+        let mut last = 0;
+        for entity in StepReader::new(reader) {
+            last = last.max(entity?.id);
+        }
+        return Ok(Self { next_id: last + 1 });
+    }
+}
+
+impl ToString for StepIdAllocator {
+    fn to_string(&self) -> String {
+        format!("StepIdAllocator(next_id={})", self.next_id)
+    }
+}
+
+// ----------------------------------------------------------------
+// IMPLEMENTATIONS OF StepPsetsWriter
+// ----------------------------------------------------------------
+
+/// Rust implementation of struct
+impl<'a> StepPsetsWriter<'a> {
+    pub fn new(
+        allocator: &'a mut StepIdAllocator,
+        schema: &str,
+        owner_history: Option<i64>,
+    ) -> Result<Self, String> {
+        let ifc2x3 = match schema.to_uppercase().as_str() {
+            "IFC2X3" => true,
+            "IFC4" | "IFC4X3" | "IFC4X3_ADD2" => false,
+            _ => {
+                return Err(format!("unsupported schema '{}'", schema));
+            }
+        };
+        if ifc2x3 && owner_history.is_none() {
+            return Err("IFC2X3 requires an owner history".to_string());
+        }
+        let result = Self {
+            allocator,
+            ifc2x3,
+            owner_history,
+            enumerations: HashMap::new(),
+            lines: vec![],
+        };
+        return Ok(result);
+    }
+
+    /// Writes all sets with a relationship to the elements, returning the ids of the sets
+    pub fn write(&mut self, psets: &Psets, element_ids: &[i64]) -> Result<Vec<i64>, String> {
+        if element_ids.is_empty() {
+            return Err("sets must be attached to at least one element".to_string());
+        }
+        let elements = StepValue::List(
+            element_ids
+                .iter()
+                .map(|x| StepValue::Reference(*x))
+                .collect(),
+        );
+        let mut result: Vec<i64> = vec![];
+        for (name, pset) in psets.children().into_iter().flatten() {
+            let PsetNestedValue::Psets(pset) = pset else {
+                return Err(format!("{} is not a set of properties", name));
+            };
+            let pset_id = self.write_pset(name, pset)?;
+            let arguments = vec![
+                StepValue::String(new_global_id()),
+                self.owner(),
+                StepValue::Null,
+                StepValue::Null,
+                elements.clone(),
+                StepValue::Reference(pset_id),
+            ];
+            self.add("IFCRELDEFINESBYPROPERTIES", arguments);
+            result.push(pset_id);
+        }
+        return Ok(result);
+    }
+
+    fn add(&mut self, class_: &str, arguments: Vec<StepValue>) -> i64 {
+        let id = self.allocator.allocate();
+        let arguments = StepValue::List(arguments).to_string();
+        self.lines.push(format!("#{}={}{};", id, class_, arguments));
+        return id;
+    }
+
+    fn owner(&self) -> StepValue {
+        match self.owner_history {
+            Some(x) => StepValue::Reference(x),
+            None => StepValue::Null,
+        }
+    }
+
+    /// Writes a property set, or a quantity set if it holds quantities
+    fn write_pset(&mut self, name: &str, pset: &Psets) -> Result<i64, String> {
+        let Psets::Nested { elements: children } = pset else {
+            return Err(format!("{} is not a set of properties", name));
+        };
+        let mut ids: Vec<StepValue> = vec![];
+        let mut quantities = 0;
+        for (key, value) in children.iter() {
+            // NOTE: verbose psets store their id alongside the properties
+            if key == "id" {
+                continue;
+            }
+            if matches!(value, PsetNestedValue::Psets(Psets::Quantity(_))) {
+                quantities += 1;
+            }
+            let id = self
+                .write_property(key, value)
+                .map_err(|err| format!("{}: {}", name, err))?;
+            ids.push(StepValue::Reference(id));
+        }
+        if ids.is_empty() {
+            return Err(format!("{} has no properties", name));
+        }
+        let mut arguments = vec![
+            StepValue::String(new_global_id()),
+            self.owner(),
+            StepValue::String(name.to_string()),
+            StepValue::Null,
+        ];
+        let class_ = match quantities {
+            0 => "IFCPROPERTYSET",
+            n if n == ids.len() => {
+                // NOTE: method of measurement
+                arguments.push(StepValue::Null);
+                "IFCELEMENTQUANTITY"
+            }
+            _ => {
+                return Err(format!("{} mixes properties and quantities", name));
+            }
+        };
+        arguments.push(StepValue::List(ids));
+        return Ok(self.add(class_, arguments));
+    }
+
+    fn write_property(&mut self, name: &str, value: &PsetNestedValue) -> Result<i64, String> {
+        let name_value = StepValue::String(name.to_string());
+        let psets = match value {
+            PsetNestedValue::Any(ValueWrap(x)) => {
+                let arguments = vec![
+                    name_value,
+                    StepValue::Null,
+                    typed_literal(x, None)?,
+                    StepValue::Null,
+                ];
+                return Ok(self.add("IFCPROPERTYSINGLEVALUE", arguments));
+            }
+            PsetNestedValue::Psets(x) => x,
+        };
+        match psets {
+            Psets::Pset(x) => {
+                let ValueWrap(value) = &x.value;
+                let value = typed_literal(value, x.value_type.as_deref())
+                    .map_err(|err| format!("{}: {}", name, err))?;
+                let arguments = vec![name_value, StepValue::Null, value, StepValue::Null];
+                return Ok(self.add("IFCPROPERTYSINGLEVALUE", arguments));
+            }
+            Psets::Quantity(x) => {
+                return self.write_quantity(name_value, x);
+            }
+            Psets::Property(PsetProperty::Enumerated(x)) => {
+                let value_type = x.value_type.as_deref();
+                let values = typed_literals(&x.values, value_type)?;
+                let enumeration = match &x.enumeration {
+                    None => StepValue::Null,
+                    Some(enumeration) => {
+                        let values = typed_literals(&enumeration.values, value_type)?;
+                        // NOTE: the name of an enumeration is not optional
+                        let name = enumeration.name.clone().unwrap_or(name.to_string());
+                        StepValue::Reference(self.write_enumeration(name, values))
+                    }
+                };
+                let arguments = vec![name_value, StepValue::Null, values, enumeration];
+                return Ok(self.add("IFCPROPERTYENUMERATEDVALUE", arguments));
+            }
+            Psets::Property(PsetProperty::Bounded(x)) => {
+                let bound = |value: &Option<ValueWrap>, value_type: &Option<String>| match value {
+                    Some(ValueWrap(value)) => typed_literal(value, value_type.as_deref()),
+                    None => Ok(StepValue::Null),
+                };
+                let mut arguments = vec![
+                    name_value,
+                    StepValue::Null,
+                    bound(&x.upper_bound, &x.upper_bound_type)?,
+                    bound(&x.lower_bound, &x.lower_bound_type)?,
+                    StepValue::Null,
+                ];
+                if !self.ifc2x3 {
+                    arguments.push(bound(&x.set_point, &x.set_point_type)?);
+                }
+                return Ok(self.add("IFCPROPERTYBOUNDEDVALUE", arguments));
+            }
+            Psets::Property(PsetProperty::List(x)) => {
+                let values = typed_literals(&x.values, x.value_type.as_deref())?;
+                let arguments = vec![name_value, StepValue::Null, values, StepValue::Null];
+                return Ok(self.add("IFCPROPERTYLISTVALUE", arguments));
+            }
+            Psets::Property(PsetProperty::Table(x)) => {
+                let expression = match &x.expression {
+                    Some(x) => StepValue::String(x.clone()),
+                    None => StepValue::Null,
+                };
+                let mut arguments = vec![
+                    name_value,
+                    StepValue::Null,
+                    typed_literals(&x.defining_values, x.defining_value_type.as_deref())?,
+                    typed_literals(&x.defined_values, x.defined_value_type.as_deref())?,
+                    expression,
+                    StepValue::Null,
+                    StepValue::Null,
+                ];
+                if !self.ifc2x3 {
+                    let interpolation = match x.curve_interpolation {
+                        Some(x) => StepValue::Enumeration(x.name().to_string()),
+                        None => StepValue::Null,
+                    };
+                    arguments.push(interpolation);
+                }
+                return Ok(self.add("IFCPROPERTYTABLEVALUE", arguments));
+            }
+            Psets::Property(PsetProperty::Reference(x)) => {
+                let usage_name = match &x.usage_name {
+                    Some(x) => StepValue::String(x.clone()),
+                    None => StepValue::Null,
+                };
+                let reference = match x.referenced_id {
+                    Some(x) => StepValue::Reference(x),
+                    None => StepValue::Null,
+                };
+                let arguments = vec![name_value, StepValue::Null, usage_name, reference];
+                return Ok(self.add("IFCPROPERTYREFERENCEVALUE", arguments));
+            }
+            Psets::Complex(x) => {
+                let mut ids: Vec<StepValue> = vec![];
+                for (key, value) in x.properties.iter() {
+                    ids.push(StepValue::Reference(self.write_property(key, value)?));
+                }
+                // NOTE: the usage name of a complex property is not optional
+                let usage_name = x.usage_name.clone().unwrap_or(name.to_string());
+                let arguments = vec![
+                    name_value,
+                    StepValue::Null,
+                    StepValue::String(usage_name),
+                    StepValue::List(ids),
+                ];
+                return Ok(self.add("IFCCOMPLEXPROPERTY", arguments));
+            }
+            Psets::PsetId(_) | Psets::Nested { .. } => {
+                return Err(format!("{} is not a property", name));
+            }
+        }
+    }
+
+    fn write_quantity(&mut self, name: StepValue, quantity: &Quantity) -> Result<i64, String> {
+        let dimension = quantity.dimension()?;
+        let ValueWrap(value) = &quantity.value;
+        let value = match (dimension, value) {
+            (QuantityDimension::Count, Value::Number(x)) if x.is_i64() => {
+                StepValue::Integer(x.as_i64().unwrap_or_default())
+            }
+            (_, Value::Number(x)) => StepValue::Real(x.as_f64().unwrap_or_default()),
+            _ => {
+                return Err(format!("quantity value must be a number, got {}", value));
+            }
+        };
+        let mut arguments = vec![name, StepValue::Null, StepValue::Null, value];
+        if !self.ifc2x3 {
+            let formula = match &quantity.formula {
+                Some(x) => StepValue::String(x.clone()),
+                None => StepValue::Null,
+            };
+            arguments.push(formula);
+        }
+        let class_ = quantity.class_.to_uppercase();
+        return Ok(self.add(&class_, arguments));
+    }
+
+    fn write_enumeration(&mut self, name: String, values: StepValue) -> i64 {
+        let key = format!("{}{}", name, values.to_string());
+        if let Some(id) = self.enumerations.get(&key) {
+            return *id;
+        }
+        let arguments = vec![StepValue::String(name), values, StepValue::Null];
+        let id = self.add("IFCPROPERTYENUMERATION", arguments);
+        self.enumerations.insert(key, id);
+        return id;
+    }
+}
+
+// ----------------------------------------------------------------
+// PYTHON INTERFACE
+// ----------------------------------------------------------------
+
+/// Writes a copy of an IFC (STEP) file with entity lines added to the end of its data section
+#[pyfunction]
+#[pyo3(signature = (source, target, lines, /))]
+pub fn patch_ifc_file(source: &str, target: &str, lines: Vec<String>) -> PyResult<()> {
+    return patch_file(source, target, &lines).map_err(err_to_py_string);
+}
+
+// ----------------------------------------------------------------
+// AUXILIARY METHODS
+// ----------------------------------------------------------------
+
+/// Copies a file, inserting lines before the `ENDSEC;` closing its (last) data section
+pub fn patch_file(source: &str, target: &str, lines: &[String]) -> Result<(), String> {
+    if is_same_file(source, target)? {
+        return Err("the patched file must not replace its source".to_string());
+    }
+    let mut file = File::open(source).map_err(err_to_string)?;
+    let size = file.metadata().map_err(err_to_string)?.len();
+    // NOTE: the data section ends close to the end of the file, so only its tail is searched
+    let start = size.saturating_sub(1 << 16);
+    file.seek(SeekFrom::Start(start)).map_err(err_to_string)?;
+    let mut tail: Vec<u8> = vec![];
+    file.read_to_end(&mut tail).map_err(err_to_string)?;
+    let position = tail
+        .windows(7)
+        .rposition(|x| x.eq_ignore_ascii_case(b"ENDSEC;"))
+        .ok_or("no data section found at the end of the file")?;
+    let position = start + position as u64;
+
+    // NOTE: the copy is written next to the target and renamed once complete,
+    // so that the target is never left half written
+    let temporary = temporary_path(target)?;
+    let result = copy_patched(&mut file, position, lines, &temporary)
+        .and_then(|_| fs::rename(&temporary, target).map_err(err_to_string));
+    if result.is_err() {
+        let _ = fs::remove_file(&temporary);
+    }
+    return result;
+}
+
+fn copy_patched(
+    file: &mut File,
+    position: u64,
+    lines: &[String],
+    target: &Path,
+) -> Result<(), String> {
+    let mut output = File::create(target).map_err(err_to_string)?;
+    file.seek(SeekFrom::Start(0)).map_err(err_to_string)?;
+    let mut head = (&mut *file).take(position);
+    std::io::copy(&mut head, &mut output).map_err(err_to_string)?;
+    for line in lines {
+        writeln!(output, "{}", line).map_err(err_to_string)?;
+    }
+    std::io::copy(file, &mut output).map_err(err_to_string)?;
+    return output.sync_all().map_err(err_to_string);
+}
+
+/// Whether two paths (e.g. `a.ifc` and `./a.ifc`) lead to the same existing file
+fn is_same_file(source: &str, target: &str) -> Result<bool, String> {
+    let source = fs::canonicalize(source).map_err(err_to_string)?;
+    match fs::canonicalize(target) {
+        Ok(target) => Ok(source == target),
+        Err(_) => Ok(false),
+    }
+}
+
+/// Hidden file in the directory of the target, e.g. `.model.ifc.1234.tmp` for `model.ifc`
+fn temporary_path(target: &str) -> Result<PathBuf, String> {
+    let target = Path::new(target);
+    let name = target.file_name().ok_or("the target must be a file")?;
+    let name = format!(".{}.{}.tmp", name.to_string_lossy(), std::process::id());
+    return Ok(target.with_file_name(name));
+}
+
+/// New (random) GlobalId in the compressed 22 character form
+pub fn new_global_id() -> String {
+    let time = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|x| x.as_nanos())
+        .unwrap_or_default();
+    // NOTE: each RandomState is seeded differently, so two hashes give 128 random bits
+    let high = RandomState::new().hash_one(time) as u128;
+    let low = RandomState::new().hash_one(time) as u128;
+    let value = (high << 64) | low;
+    let mut result = String::new();
+    result.push(GLOBAL_ID_DIGITS[(value >> 126) as usize] as char);
+    for i in 1..22 {
+        let digit = (value >> (126 - 6 * i)) & 63;
+        result.push(GLOBAL_ID_DIGITS[digit as usize] as char);
+    }
+    return result;
+}
+
+/// Literal of a value of a defined type, inferring the type of untyped values
+fn typed_literal(value: &Value, value_type: Option<&str>) -> Result<StepValue, String> {
+    let value_type = match (value, value_type) {
+        (Value::Null, _) => {
+            return Ok(StepValue::Null);
+        }
+        (_, Some(x)) => x,
+        (Value::Bool(_), None) => "IfcBoolean",
+        (Value::Number(x), None) if x.is_i64() => "IfcInteger",
+        (Value::Number(_), None) => "IfcReal",
+        (Value::String(_), None) => "IfcLabel",
+        (_, None) => {
+            return Err(format!("cannot write {} as a single value", value));
+        }
+    };
+    // NOTE: types not modelled by IfcValue are only checked for integers and booleans
+    IfcValue::from_json(value_type, value)?;
+    let is_boolean = value_type == "IfcBoolean" || value_type == "IfcLogical";
+    let is_integer = INTEGER_TYPES.contains(&value_type);
+    let literal = match value {
+        Value::Bool(true) if is_boolean => StepValue::Enumeration("T".to_string()),
+        Value::Bool(false) if is_boolean => StepValue::Enumeration("F".to_string()),
+        Value::String(x) if value_type == "IfcLogical" && x.eq_ignore_ascii_case("UNKNOWN") => {
+            StepValue::Enumeration("U".to_string())
+        }
+        Value::String(x) if !is_boolean => StepValue::String(x.clone()),
+        Value::Number(x) if is_integer => match x.as_f64().filter(|x| x.fract() == 0.0) {
+            Some(_) if x.is_i64() => StepValue::Integer(x.as_i64().unwrap_or_default()),
+            Some(y) => StepValue::Integer(y as i64),
+            None => {
+                return Err(format!("cannot write {} as {}", value, value_type));
+            }
+        },
+        Value::Number(x) if !is_boolean => StepValue::Real(x.as_f64().unwrap_or_default()),
+        _ => {
+            return Err(format!("cannot write {} as {}", value, value_type));
+        }
+    };
+    return Ok(StepValue::Typed(
+        value_type.to_uppercase(),
+        Box::new(literal),
+    ));
+}
+
+fn typed_literals(values: &[ValueWrap], value_type: Option<&str>) -> Result<StepValue, String> {
+    let result: Result<Vec<StepValue>, String> = values
+        .iter()
+        .map(|ValueWrap(x)| typed_literal(x, value_type))
+        .collect();
+    return Ok(StepValue::List(result?));
+}
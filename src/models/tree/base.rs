@@ -21,6 +21,29 @@ pub enum GenericTreeOrRoot<T> {
     Tree(GenericTree<T>),
 }
 
+/// Order in which the nodes of a tree are visited
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TraversalOrder {
+    PreOrder,
+    PostOrder,
+}
+
+/// Node visited during a traversal, together with the nodes leading to it
+pub struct GenericTreeStep<'a, T> {
+    /// roots from the top of the tree down to (and including) the visited node
+    pub lineage: Vec<&'a T>,
+    pub depth: usize,
+    pub tree: &'a GenericTree<T>,
+}
+
+/// Iterator over the nodes of a tree
+pub struct GenericTreeWalk<'a, T> {
+    pub(super) order: TraversalOrder,
+    pub(super) max_depth: Option<usize>,
+    /// pending nodes, flagged if their children have already been scheduled
+    pub(super) stack: Vec<(GenericTreeStep<'a, T>, bool)>,
+}
+
 /// Interface for handling generic trees
 #[allow(unused)]
 pub trait GenericTreeLike<T> {
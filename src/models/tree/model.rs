@@ -7,6 +7,9 @@ use std::vec;
 use super::base::GenericTree;
 use super::base::GenericTreeLike;
 use super::base::GenericTreeOrRoot;
+use super::base::GenericTreeStep;
+use super::base::GenericTreeWalk;
+use super::base::TraversalOrder;
 
 // ----------------------------------------------------------------
 // BASIC IMPLEMENTATION FOR GenericTree
@@ -31,6 +34,20 @@ impl<T> GenericTree<T> {
     pub fn has_children(&self) -> bool {
         self.num_children() > 0
     }
+
+    /// Iterates through all nodes, descending at most `max_depth` levels below the root
+    pub fn walk(&self, order: TraversalOrder, max_depth: Option<usize>) -> GenericTreeWalk<'_, T> {
+        let step = GenericTreeStep {
+            lineage: vec![&self.root],
+            depth: 0,
+            tree: self,
+        };
+        return GenericTreeWalk {
+            order,
+            max_depth,
+            stack: vec![(step, false)],
+        };
+    }
 }
 
 impl<T> Clone for GenericTree<T>
@@ -139,3 +156,67 @@ where
         return result;
     }
 }
+
+// ----------------------------------------------------------------
+// IMPLEMENTATION OF TRAVERSAL
+// ----------------------------------------------------------------
+
+impl TraversalOrder {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "pre" | "pre-order" => Ok(Self::PreOrder),
+            "post" | "post-order" => Ok(Self::PostOrder),
+            _ => Err(format!("unknown traversal order '{}'", name)),
+        }
+    }
+}
+
+impl<'a, T> GenericTreeWalk<'a, T> {
+    /// Pending entries for the children of a node (reversed, so that they are popped in order)
+    fn children_of(&self, step: &GenericTreeStep<'a, T>) -> Vec<(GenericTreeStep<'a, T>, bool)> {
+        if self.max_depth.is_some_and(|n| step.depth >= n) {
+            return vec![];
+        }
+        return step
+            .tree
+            .children
+            .iter()
+            .rev()
+            .map(|child| {
+                let mut lineage = step.lineage.clone();
+                lineage.push(&child.root);
+                let substep = GenericTreeStep {
+                    lineage,
+                    depth: step.depth + 1,
+                    tree: child,
+                };
+                return (substep, false);
+            })
+            .collect();
+    }
+}
+
+impl<'a, T> Iterator for GenericTreeWalk<'a, T> {
+    type Item = GenericTreeStep<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (step, expanded) = self.stack.pop()?;
+            match (self.order, expanded) {
+                (TraversalOrder::PreOrder, _) => {
+                    let children = self.children_of(&step);
+                    self.stack.extend(children);
+                    return Some(step);
+                }
+                (TraversalOrder::PostOrder, true) => {
+                    return Some(step);
+                }
+                (TraversalOrder::PostOrder, false) => {
+                    let children = self.children_of(&step);
+                    self.stack.push((step, true));
+                    self.stack.extend(children);
+                }
+            }
+        }
+    }
+}
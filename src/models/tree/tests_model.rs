@@ -8,6 +8,7 @@ use std::vec;
 use super::base::GenericTree;
 use super::base::GenericTreeLike;
 use super::base::GenericTreeOrRoot;
+use super::base::TraversalOrder;
 
 // ----------------------------------------------------------------
 // TESTS
@@ -103,12 +104,64 @@ mod tests {
         .to_string();
         assert_eq!(t.to_string(), expected);
     }
+
+    #[test]
+    fn test_walk_case1() {
+        let t = dummy_tree();
+        let names: Vec<String> = t
+            .walk(TraversalOrder::PreOrder, None)
+            .map(|step| format!("{}@{}", step.tree.root.to_string(), step.depth))
+            .collect();
+        assert_eq!(
+            names,
+            vec!["root@0", "alice: 23@1", "bird: 2@2", "bob: 24@1"]
+        );
+    }
+
+    #[test]
+    fn test_walk_case2() {
+        let t = dummy_tree();
+        let names: Vec<String> = t
+            .walk(TraversalOrder::PostOrder, None)
+            .map(|step| step.tree.root.to_string())
+            .collect();
+        assert_eq!(names, vec!["bird: 2", "alice: 23", "bob: 24", "root"]);
+        let names: Vec<String> = t
+            .walk(TraversalOrder::PostOrder, Some(1))
+            .map(|step| step.tree.root.to_string())
+            .collect();
+        assert_eq!(names, vec!["alice: 23", "bob: 24", "root"]);
+    }
+
+    #[test]
+    fn test_walk_case3() {
+        let t = dummy_tree();
+        let step = t.walk(TraversalOrder::PreOrder, None).nth(2).unwrap();
+        let lineage: Vec<String> = step.lineage.iter().map(|x| x.to_string()).collect();
+        assert_eq!(lineage, vec!["root", "alice: 23", "bird: 2"]);
+    }
 }
 
 // ----------------------------------------------------------------
 // AUXILIARY
 // ----------------------------------------------------------------
 
+fn dummy_node(name: &str, value: Option<i32>) -> DummyNode {
+    DummyNode {
+        name: Some(name.to_string()),
+        value,
+    }
+}
+
+fn dummy_tree() -> GenericTree<DummyNode> {
+    let mut t = GenericTree::new(dummy_node("root", None), None);
+    let mut child1 = GenericTree::new(dummy_node("alice", Some(23)), None);
+    child1.add(GenericTreeOrRoot::Root(dummy_node("bird", Some(2))));
+    t.add(GenericTreeOrRoot::Tree(child1));
+    t.add(GenericTreeOrRoot::Root(dummy_node("bob", Some(24))));
+    return t;
+}
+
 #[derive(Clone)]
 struct DummyNode {
     name: Option<String>,